@@ -1,19 +1,51 @@
 use serde::{de::DeserializeOwned, Serialize};
 
 #[cfg(target_arch = "wasm32")]
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    io::{BufRead, BufReader},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 #[cfg(target_arch = "wasm32")]
-use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen_futures::JsFuture;
 #[cfg(target_arch = "wasm32")]
-use web_sys::{Request, RequestInit, Response};
+use web_sys::{EventSource, MessageEvent, Request, RequestInit, Response};
 
-pub(crate) async fn get<T: DeserializeOwned>(url: &str) -> Option<T> {
+/// Response to a GET made with the tag of the data already known.
+pub(crate) enum Conditional<T> {
+    Modified { data: T, etag: Option<String> },
+    NotModified,
+}
+
+pub(crate) async fn get_if_none_match<T: DeserializeOwned>(
+    url: &str,
+    etag: Option<String>,
+) -> Option<Conditional<T>> {
     #[cfg(not(target_arch = "wasm32"))]
-    let result = ureq::get(url).call().ok().and_then(|r| r.into_json().ok());
+    let result = {
+        let mut request = ureq::get(url);
+        if let Some(etag) = etag.as_ref() {
+            request = request.set("If-None-Match", etag);
+        }
+        let response = request.call().ok()?;
+        if response.status() == 304 {
+            Some(Conditional::NotModified)
+        } else {
+            let etag = response.header("ETag").map(str::to_string);
+            response
+                .into_json()
+                .ok()
+                .map(|data| Conditional::Modified { data, etag })
+        }
+    };
     #[cfg(target_arch = "wasm32")]
-    let result = request::<(), T>(url, None).await;
+    let result = conditional_request(url, etag).await;
 
     result
 }
@@ -30,6 +62,44 @@ pub(crate) async fn post<T: Serialize, U: DeserializeOwned>(url: &str, body: T)
     result
 }
 
+#[cfg(target_arch = "wasm32")]
+async fn conditional_request<R: DeserializeOwned>(
+    url: &str,
+    etag: Option<String>,
+) -> Option<Conditional<R>> {
+    let mut headers = HashMap::new();
+    let opts = RequestInit::new();
+    if let Some(etag) = etag.as_ref() {
+        headers.insert("If-None-Match", etag.as_str());
+        // building headers - can't fail
+        opts.set_headers(&serde_wasm_bindgen::to_value(&headers).unwrap());
+    }
+
+    // building the request - can't fail
+    let request = Request::new_with_str_and_init(&url, &opts).unwrap();
+
+    // getting the window - can't fail
+    let window = web_sys::window().unwrap();
+    // can fail on error response
+    let resp_value = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .ok()?;
+    // converting the JsValue to the correct type - can't fail
+    let resp: Response = resp_value.dyn_into().unwrap();
+    if resp.status() == 304 {
+        return Some(Conditional::NotModified);
+    }
+    // the header is exposed by the server for CORS requests
+    let etag = resp.headers().get("ETag").ok().flatten();
+    JsFuture::from(resp.json().unwrap())
+        .await
+        .ok()
+        .and_then(|value|
+            // can fail if value is not of the correct type
+            serde_wasm_bindgen::from_value(value).ok())
+        .map(|data| Conditional::Modified { data, etag })
+}
+
 #[cfg(target_arch = "wasm32")]
 async fn request<B: Serialize, R: DeserializeOwned>(url: &str, body: Option<B>) -> Option<R> {
     let mut headers = HashMap::new();
@@ -63,3 +133,121 @@ async fn request<B: Serialize, R: DeserializeOwned>(url: &str, body: Option<B>)
             // can fail if value is not of the correct type
             serde_wasm_bindgen::from_value(value).ok())
 }
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    /// Open event sources, by id of their stream.
+    static EVENT_SOURCES: RefCell<HashMap<u64, EventSource>> = RefCell::new(HashMap::new());
+}
+
+/// Server-Sent Events received in the background, until closed.
+pub(crate) struct EventStream {
+    #[cfg(not(target_arch = "wasm32"))]
+    closed: Arc<AtomicBool>,
+    #[cfg(target_arch = "wasm32")]
+    id: u64,
+}
+
+impl EventStream {
+    /// Receive the events named `names` from `url`, calling `on_event` with the name and data of
+    /// each. `on_error` is called if the connection can't be opened or is lost.
+    pub(crate) fn open(
+        url: &str,
+        names: &[&'static str],
+        on_event: impl Fn(&str, &str) + Send + Sync + 'static,
+        on_error: impl Fn() + Send + Sync + 'static,
+    ) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let closed = Arc::new(AtomicBool::new(false));
+            let url = url.to_string();
+            let names = names.to_vec();
+            let stream_closed = closed.clone();
+            // the connection stays open, it would hold a thread of the task pool
+            std::thread::spawn(move || {
+                let Ok(response) = ureq::get(&url).set("Accept", "text/event-stream").call() else {
+                    on_error();
+                    return;
+                };
+                let mut name = String::new();
+                let mut data = String::new();
+                for line in BufReader::new(response.into_reader()).lines() {
+                    // the server sends a comment regularly, so this is checked even without events
+                    if stream_closed.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    let Ok(line) = line else {
+                        break;
+                    };
+                    if line.is_empty() {
+                        if names.contains(&name.as_str()) {
+                            on_event(&name, &data);
+                        }
+                        name.clear();
+                        data.clear();
+                    } else if let Some(value) = line.strip_prefix("event:") {
+                        name = value.trim_start().to_string();
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        data.push_str(value.trim_start());
+                    }
+                }
+                if !stream_closed.load(Ordering::Relaxed) {
+                    on_error();
+                }
+            });
+            Self { closed }
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            use std::sync::atomic::{AtomicU64, Ordering};
+            static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            let Ok(source) = EventSource::new(url) else {
+                on_error();
+                return Self { id };
+            };
+            let on_event = std::rc::Rc::new(on_event);
+            for &name in names {
+                let on_event = on_event.clone();
+                let callback = Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
+                    if let Some(data) = event.data().as_string() {
+                        on_event(name, &data);
+                    }
+                });
+                // adding a listener - can't fail
+                source
+                    .add_event_listener_with_callback(name, callback.as_ref().unchecked_ref())
+                    .unwrap();
+                // the callback must live as long as the event source
+                callback.forget();
+            }
+            // the browser reconnects by itself, only report errors that closed the source
+            let on_error = Closure::<dyn Fn(web_sys::Event)>::new(move |event: web_sys::Event| {
+                let closed = event
+                    .target()
+                    .and_then(|target| target.dyn_into::<EventSource>().ok())
+                    .map(|source| source.ready_state() == EventSource::CLOSED)
+                    .unwrap_or(true);
+                if closed {
+                    on_error();
+                }
+            });
+            source.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+            on_error.forget();
+            EVENT_SOURCES.with(|sources| sources.borrow_mut().insert(id, source));
+            Self { id }
+        }
+    }
+
+    /// Stop receiving events. On native, the connection is closed when the server next sends
+    /// something.
+    pub(crate) fn close(self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.closed.store(true, Ordering::Relaxed);
+        #[cfg(target_arch = "wasm32")]
+        if let Some(source) = EVENT_SOURCES.with(|sources| sources.borrow_mut().remove(&self.id)) {
+            source.close();
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use std::sync::{Arc, RwLock};
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::{SystemTime, UNIX_EPOCH};
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
 use bevy_ecs::{
     message::{Message, MessageWriter},
@@ -14,7 +17,7 @@ use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 use uuid::Uuid;
 
-use crate::http;
+use crate::http::{self, Conditional, EventStream};
 
 /// Bevy Event that is sent when calls to Jornet finish.
 #[derive(Clone, Copy, PartialEq, Eq, Message, Debug)]
@@ -31,6 +34,12 @@ pub enum JornetEvent {
     RefreshLeaderboardSuccess,
     /// A call to [`refresh_leaderbord`] failed.
     RefreshLeaderboardFailure,
+    /// The best scores changed while [subscribed](Leaderboard::subscribe).
+    LiveLeaderboardUpdated,
+    /// The rank of the player changed while [subscribed](Leaderboard::subscribe).
+    LiveRankUpdated,
+    /// The subscription failed or was lost. It must be renewed to receive updates.
+    LiveSubscriptionFailure,
 }
 /// Leaderboard resource, used to interact with Jornet leaderboard.
 #[derive(Resource)]
@@ -38,11 +47,16 @@ pub struct Leaderboard {
     id: Uuid,
     key: Uuid,
     leaderboard: Vec<Score>,
-    updating: Arc<RwLock<Vec<Score>>>,
+    updating: Arc<RwLock<Option<Vec<Score>>>>,
+    /// Tag of the last leaderboard received, to only receive it again if it changed.
+    etag: Arc<RwLock<Option<String>>>,
     events: Arc<RwLock<Vec<JornetEvent>>>,
     host: String,
     new_player: Arc<RwLock<Option<Player>>>,
     player: Option<Player>,
+    live: Option<EventStream>,
+    rank: Option<Rank>,
+    updating_rank: Arc<RwLock<Option<Option<Rank>>>>,
 }
 
 impl Leaderboard {
@@ -52,10 +66,14 @@ impl Leaderboard {
             key,
             leaderboard: Default::default(),
             updating: Default::default(),
+            etag: Default::default(),
             host: host.unwrap_or_else(|| "https://jornet.vleue.com".to_string()),
             events: Default::default(),
             new_player: Default::default(),
             player: Default::default(),
+            live: Default::default(),
+            rank: Default::default(),
+            updating_rank: Default::default(),
         }
     }
 
@@ -113,17 +131,54 @@ impl Leaderboard {
 
     /// Send a score to the leaderboard.
     pub fn send_score(&self, score: f32) -> Option<()> {
-        self.inner_send_score_with_meta(score, None)
+        self.inner_send_score_with_meta(TypedScore::F32(score), None)
     }
 
     /// Send a score with metadata to the leaderboard.
     ///
     /// Metadata can be information about the game, victory conditions, ...
     pub fn send_score_with_meta(&self, score: f32, meta: &str) -> Option<()> {
-        self.inner_send_score_with_meta(score, Some(meta.to_string()))
+        self.inner_send_score_with_meta(TypedScore::F32(score), Some(meta.to_string()))
+    }
+
+    /// Send a score to a leaderboard keeping `i64` scores.
+    ///
+    /// The server rejects scores whose type doesn't match the type of the leaderboard.
+    pub fn send_score_i64(&self, score: i64) -> Option<()> {
+        self.inner_send_score_with_meta(TypedScore::I64(score), None)
+    }
+
+    /// Send a score with metadata to a leaderboard keeping `i64` scores.
+    pub fn send_score_i64_with_meta(&self, score: i64, meta: &str) -> Option<()> {
+        self.inner_send_score_with_meta(TypedScore::I64(score), Some(meta.to_string()))
+    }
+
+    /// Send a score to a leaderboard keeping `f64` scores.
+    ///
+    /// The server rejects scores whose type doesn't match the type of the leaderboard.
+    pub fn send_score_f64(&self, score: f64) -> Option<()> {
+        self.inner_send_score_with_meta(TypedScore::F64(score), None)
+    }
+
+    /// Send a score with metadata to a leaderboard keeping `f64` scores.
+    pub fn send_score_f64_with_meta(&self, score: f64, meta: &str) -> Option<()> {
+        self.inner_send_score_with_meta(TypedScore::F64(score), Some(meta.to_string()))
+    }
+
+    /// Send a duration, in milliseconds, to a leaderboard keeping durations.
+    ///
+    /// Leaderboards of durations rank the shortest first, send the time taken to have the fastest
+    /// players first.
+    pub fn send_score_duration(&self, score: Duration) -> Option<()> {
+        self.inner_send_score_with_meta(TypedScore::duration(score), None)
+    }
+
+    /// Send a duration with metadata to a leaderboard keeping durations.
+    pub fn send_score_duration_with_meta(&self, score: Duration, meta: &str) -> Option<()> {
+        self.inner_send_score_with_meta(TypedScore::duration(score), Some(meta.to_string()))
     }
 
-    fn inner_send_score_with_meta(&self, score: f32, meta: Option<String>) -> Option<()> {
+    fn inner_send_score_with_meta(&self, score: TypedScore, meta: Option<String>) -> Option<()> {
         let thread_pool = IoTaskPool::get();
         let leaderboard_id = self.id;
         let host = self.host.clone();
@@ -164,7 +219,8 @@ impl Leaderboard {
     ///
     /// This is done asynchronously, the resource [`Leaderboard`] will be marked as changed
     /// once the leaderboard data is available. You can then get those data with
-    /// [`Self::get_leaderboard`].
+    /// [`Self::get_leaderboard`]. If the leaderboard didn't change since the last refresh, the
+    /// server doesn't send it again and the resource is not marked as changed.
     pub fn refresh_leaderboard(&self) {
         let thread_pool = IoTaskPool::get();
         let leaderboard_id = self.id;
@@ -172,13 +228,21 @@ impl Leaderboard {
         let events = self.events.clone();
 
         let leaderboard_to_update = self.updating.clone();
+        let etag = self.etag.clone();
 
         thread_pool
             .spawn(async move {
-                if let Some(scores) =
-                    http::get(&format!("{}/api/v1/scores/{}", host, leaderboard_id)).await
+                let known = etag.read().unwrap().clone();
+                if let Some(response) = http::get_if_none_match(
+                    &format!("{}/api/v1/scores/{}", host, leaderboard_id),
+                    known,
+                )
+                .await
                 {
-                    *leaderboard_to_update.write().unwrap() = scores;
+                    if let Conditional::Modified { data, etag: new } = response {
+                        *leaderboard_to_update.write().unwrap() = Some(data);
+                        *etag.write().unwrap() = new;
+                    }
 
                     (*events)
                         .write()
@@ -218,26 +282,106 @@ impl Leaderboard {
     pub fn get_leaderboard(&self) -> Vec<Score> {
         self.leaderboard.clone()
     }
+
+    /// Subscribe to live updates of the leaderboard, instead of refreshing it regularly.
+    ///
+    /// Scores are ranked from the best, the highest or the shortest for leaderboards of durations.
+    /// [`Self::get_leaderboard`] will hold the `top` best scores, updated as soon as they change
+    /// with a [`JornetEvent::LiveLeaderboardUpdated`]. If there is a current player,
+    /// [`Self::get_rank`] will hold their rank, updated with a [`JornetEvent::LiveRankUpdated`].
+    pub fn subscribe(&mut self, top: usize) {
+        self.unsubscribe();
+
+        let mut url = format!("{}/api/v1/scores/{}/live?top={}", self.host, self.id, top);
+        if let Some(player) = self.player.as_ref() {
+            url.push_str(&format!("&player={}", player.id));
+        }
+
+        let events = self.events.clone();
+        let leaderboard_to_update = self.updating.clone();
+        let rank_to_update = self.updating_rank.clone();
+        let on_event = move |name: &str, data: &str| match name {
+            "top" => {
+                if let Ok(scores) = serde_json::from_str(data) {
+                    *leaderboard_to_update.write().unwrap() = Some(scores);
+                    (*events)
+                        .write()
+                        .unwrap()
+                        .push(JornetEvent::LiveLeaderboardUpdated);
+                }
+            }
+            "rank" => {
+                if let Ok(rank) = serde_json::from_str(data) {
+                    *rank_to_update.write().unwrap() = Some(rank);
+                    (*events)
+                        .write()
+                        .unwrap()
+                        .push(JornetEvent::LiveRankUpdated);
+                }
+            }
+            _ => (),
+        };
+
+        let events = self.events.clone();
+        let on_error = move || {
+            warn!("error receiving live updates");
+
+            (*events)
+                .write()
+                .unwrap()
+                .push(JornetEvent::LiveSubscriptionFailure);
+        };
+
+        self.live = Some(EventStream::open(
+            &url,
+            &["top", "rank"],
+            on_event,
+            on_error,
+        ));
+    }
+
+    /// Stop receiving live updates of the leaderboard.
+    pub fn unsubscribe(&mut self) {
+        if let Some(live) = self.live.take() {
+            live.close();
+        }
+    }
+
+    /// Get the rank of the current player. It is only available while
+    /// [subscribed](Self::subscribe), and once they sent a score.
+    pub fn get_rank(&self) -> Option<Rank> {
+        self.rank
+    }
+}
+
+impl Drop for Leaderboard {
+    fn drop(&mut self) {
+        self.unsubscribe();
+    }
 }
 
 /// System to handle refreshing the [`Leaderboard`] resource when new data is available.
 /// It is automatically added by the [`JornetPlugin`](crate::JornetPlugin) in stage
 /// [`CoreStage::Update`](bevy::prelude::CoreStage).
 pub fn done_refreshing_leaderboard(mut leaderboard: ResMut<Leaderboard>) {
-    if !leaderboard
+    if leaderboard
         .updating
         .try_read()
-        .map(|v| v.is_empty())
-        .unwrap_or(true)
+        .map(|v| v.is_some())
+        .unwrap_or(false)
     {
-        let updated = leaderboard
-            .updating
-            .write()
-            .unwrap()
-            .drain(..)
-            .collect::<Vec<_>>();
+        let updated = leaderboard.updating.write().unwrap().take().unwrap();
         leaderboard.leaderboard = updated;
     }
+    if leaderboard
+        .updating_rank
+        .try_read()
+        .map(|v| v.is_some())
+        .unwrap_or(false)
+    {
+        let updated = leaderboard.updating_rank.write().unwrap().take().unwrap();
+        leaderboard.rank = updated;
+    }
     if leaderboard
         .new_player
         .try_read()
@@ -251,9 +395,12 @@ pub fn done_refreshing_leaderboard(mut leaderboard: ResMut<Leaderboard>) {
 
 /// A score from a leaderboard
 #[derive(Deserialize, Debug, Clone)]
+#[serde(from = "ScoreData")]
 pub struct Score {
     /// The score.
     pub score: f32,
+    /// The score as kept by the leaderboard, without the rounding of `score`.
+    pub value: ScoreValue,
     /// The player name.
     pub player: String,
     /// Optional metadata.
@@ -262,9 +409,120 @@ pub struct Score {
     pub timestamp: String,
 }
 
+#[derive(Deserialize)]
+struct ScoreData {
+    score: ScoreValue,
+    player: String,
+    meta: Option<String>,
+    timestamp: String,
+}
+
+impl From<ScoreData> for Score {
+    fn from(data: ScoreData) -> Self {
+        Score {
+            score: data.score.as_f64() as f32,
+            value: data.score,
+            player: data.player,
+            meta: data.meta,
+            timestamp: data.timestamp,
+        }
+    }
+}
+
+/// Rank of the best score of a player in a leaderboard.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(from = "RankData")]
+pub struct Rank {
+    /// The rank, from 1. Equal scores share the same rank.
+    pub rank: usize,
+    /// The best score of the player.
+    pub score: f32,
+    /// The best score of the player as kept by the leaderboard, without the rounding of `score`.
+    pub value: ScoreValue,
+}
+
+#[derive(Deserialize)]
+struct RankData {
+    rank: usize,
+    score: ScoreValue,
+}
+
+impl From<RankData> for Rank {
+    fn from(data: RankData) -> Self {
+        Rank {
+            rank: data.rank,
+            score: data.score.as_f64() as f32,
+            value: data.score,
+        }
+    }
+}
+
+/// A score as kept by a leaderboard: an integer for leaderboards of `i64` or durations in
+/// milliseconds, a float otherwise.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum ScoreValue {
+    /// A score from a leaderboard of `i64` or durations.
+    Integer(i64),
+    /// A score from a leaderboard of `f32` or `f64`.
+    Float(f64),
+}
+
+impl ScoreValue {
+    /// The score as a float, which may round large integers.
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            ScoreValue::Integer(value) => *value as f64,
+            ScoreValue::Float(value) => *value,
+        }
+    }
+
+    /// The score as an integer, if it is one.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            ScoreValue::Integer(value) => Some(*value),
+            ScoreValue::Float(_) => None,
+        }
+    }
+
+    /// The score as a duration, if it is a number of milliseconds.
+    pub fn as_duration(&self) -> Option<Duration> {
+        self.as_i64()
+            .and_then(|value| u64::try_from(value).ok())
+            .map(Duration::from_millis)
+    }
+}
+
+/// A score in the type of the leaderboard it's sent to, which is part of its signature.
+#[derive(Serialize, Clone, Copy)]
+#[serde(untagged)]
+enum TypedScore {
+    F32(f32),
+    I64(i64),
+    F64(f64),
+    DurationMs(i64),
+}
+
+impl TypedScore {
+    fn duration(duration: Duration) -> Self {
+        TypedScore::DurationMs(i64::try_from(duration.as_millis()).unwrap_or(i64::MAX))
+    }
+
+    /// `f32` scores are signed as 4 bytes, as they always were. Other types are signed as 8 bytes
+    /// followed by the name of their type.
+    fn signed_bytes(&self) -> Vec<u8> {
+        match self {
+            TypedScore::F32(score) => score.to_le_bytes().to_vec(),
+            TypedScore::I64(score) => [&score.to_le_bytes()[..], b"i64"].concat(),
+            TypedScore::F64(score) => [&score.to_le_bytes()[..], b"f64"].concat(),
+            TypedScore::DurationMs(score) => [&score.to_le_bytes()[..], b"duration_ms"].concat(),
+        }
+    }
+}
+
 #[derive(Serialize, Clone)]
 struct ScoreInput {
-    pub score: f32,
+    pub score: TypedScore,
     pub player: Uuid,
     pub meta: Option<String>,
     pub timestamp: u64,
@@ -272,7 +530,12 @@ struct ScoreInput {
 }
 
 impl ScoreInput {
-    fn new(leaderboard_key: Uuid, score: f32, player: &Player, meta: Option<String>) -> Self {
+    fn new(
+        leaderboard_key: Uuid,
+        score: TypedScore,
+        player: &Player,
+        meta: Option<String>,
+    ) -> Self {
         #[cfg(not(target_arch = "wasm32"))]
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -285,7 +548,7 @@ impl ScoreInput {
         mac.update(&timestamp.to_le_bytes());
         mac.update(leaderboard_key.as_bytes());
         mac.update(player.id.as_bytes());
-        mac.update(&score.to_le_bytes());
+        mac.update(&score.signed_bytes());
         if let Some(meta) = meta.as_ref() {
             mac.update(meta.as_bytes());
         }
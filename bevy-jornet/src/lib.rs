@@ -5,6 +5,7 @@
 //! Bevy Plugin to integrate with [Jornet](https://jornet.vleue.com)
 //! - save high scores
 //! - get a leaderboard
+//! - follow a leaderboard live
 
 use bevy_app::{App, Plugin, Update};
 use bevy_ecs::schedule::IntoScheduleConfigs;
@@ -15,7 +16,7 @@ use uuid::Uuid;
 mod http;
 mod leaderboards;
 
-pub use leaderboards::{done_refreshing_leaderboard, JornetEvent, Player, Score};
+pub use leaderboards::{done_refreshing_leaderboard, JornetEvent, Player, Rank, Score, ScoreValue};
 
 /// Bevy Plugin handling communications with the Jornet server.
 pub struct JornetPlugin {
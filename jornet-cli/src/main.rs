@@ -2,6 +2,10 @@ use base64::{engine::general_purpose::STANDARD, Engine};
 use biscuit_auth::KeyPair;
 
 use clap::{Parser, Subcommand};
+use jornet_server::{
+    configuration::{load_configuration, Settings},
+    storage::migrations::{self, MigrationStatus},
+};
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
@@ -19,9 +23,62 @@ enum Commands {
         #[clap(short, long, value_parser)]
         dhall: bool,
     },
+    /// Manage the migrations of the database from the server configuration
+    Migrate {
+        #[clap(subcommand)]
+        command: MigrateCommands,
+    },
+    /// Inspect the server configuration, read from the file in `CONFIGURATION`, or
+    /// `configuration.dhall` or `configuration.toml`, then from the `JORNET_*` environment
+    /// variables
+    Config {
+        #[clap(subcommand)]
+        command: ConfigCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Validate the configuration, and print it with its secrets redacted
+    Check,
+}
+
+#[derive(Subcommand)]
+enum MigrateCommands {
+    /// Show which migrations are applied and which are pending
+    Status,
+    /// Apply the pending migrations
+    Apply {
+        /// Only list the migrations that would be applied
+        #[clap(long, value_parser)]
+        dry_run: bool,
+    },
+}
+
+fn configuration() -> Settings {
+    let configuration = load_configuration().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1)
+    });
+    for warning in &configuration.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    configuration
 }
 
-fn main() {
+fn print_migrations(migrations: &[MigrationStatus]) {
+    for migration in migrations {
+        println!(
+            "{}\t{}\t{}",
+            migration.version,
+            migration.state.as_str(),
+            migration.description
+        );
+    }
+}
+
+#[tokio::main]
+async fn main() {
     let cli = Cli::parse();
 
     match cli.command {
@@ -35,5 +92,42 @@ fn main() {
             };
             println!("{}", output);
         }
+        Commands::Migrate { command } => {
+            let configuration = configuration();
+            match command {
+                MigrateCommands::Status => {
+                    let status = migrations::status(&configuration)
+                        .await
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to get the migrations status: {}", err);
+                            std::process::exit(1)
+                        });
+                    print_migrations(&status);
+                }
+                MigrateCommands::Apply { dry_run } => {
+                    let applied = migrations::apply(&configuration, dry_run)
+                        .await
+                        .unwrap_or_else(|err| {
+                            eprintln!("Failed to apply the migrations: {}", err);
+                            std::process::exit(1)
+                        });
+                    match (applied.is_empty(), dry_run) {
+                        (true, _) => println!("No pending migrations"),
+                        (false, true) => println!("Migrations that would be applied:"),
+                        (false, false) => println!("Applied migrations:"),
+                    }
+                    print_migrations(&applied);
+                }
+            }
+        }
+        Commands::Config { command } => match command {
+            ConfigCommands::Check => {
+                let configuration = configuration();
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&configuration.redacted()).unwrap()
+                );
+            }
+        },
     }
 }
@@ -1,7 +1,6 @@
 import React, { Component } from "react";
 import { NavigateFunction, useNavigate, useSearchParams } from "react-router-dom";
 import validator from "validator";
-import { v4 as uuidv4 } from "uuid";
 import { Button, Col, Container, FloatingLabel, Form, InputGroup, Row } from "react-bootstrap";
 
 type ConnectProps = {
@@ -129,14 +128,13 @@ class ConnectInner extends Component<ConnectProps, ConnectState> {
     }
     handleSubmit = (event: React.FormEvent) => {
         let new_account = this.state.uuid === "";
-        let uuid = !new_account ? this.state.uuid : uuidv4();
         const requestOptions = {
             method: 'POST',
             headers: { 'Content-Type': 'application/json' },
-            body: JSON.stringify({ uuid: uuid })
+            body: JSON.stringify({ uuid: this.state.uuid })
         };
         this.setState({ error: undefined });
-        fetch('/oauth/by_uuid', requestOptions)
+        fetch(new_account ? '/oauth/anonymous' : '/oauth/by_uuid', requestOptions)
             .then(response => response.json())
             .then(data => {
                 this.props.setToken(data.token);
@@ -0,0 +1,166 @@
+//! Compare the score queries before and after the migration adding the constraints and indexes
+//! of the `scores` table.
+//!
+//! It creates a new database with the connection settings from the configuration, seeds it with
+//! `JORNET_BENCH_SCORES` scores (10 million by default), and drops it at the end.
+//!
+//! ```sh
+//! JORNET_BENCH_SCORES=1000000 cargo bench --bench scores
+//! ```
+
+use std::time::{Duration, Instant};
+
+use jornet_server::configuration::get_configuration;
+use sqlx::{Connection, Executor, PgConnection, PgPool};
+use uuid::Uuid;
+
+const MIGRATION: &str =
+    include_str!("../migrations/20261019110000_add_score_constraints_and_indexes.sql");
+const BEFORE_MIGRATION: &str = r#"
+ALTER TABLE scores DROP CONSTRAINT scores_leaderboard_fkey, DROP CONSTRAINT scores_player_fkey;
+DROP INDEX scores_leaderboard_score;
+DROP INDEX scores_leaderboard_player;
+DROP INDEX scores_player;
+DROP TABLE orphaned_scores;
+"#;
+
+const LEADERBOARDS: i64 = 1_000;
+const PLAYERS: i64 = 100_000;
+const ITERATIONS: u32 = 20;
+
+/// Ids are derived from their index so that scores can reference them while seeding.
+const SEED: &str = r#"
+INSERT INTO leaderboards (id, name, organization, key)
+SELECT md5('leaderboard' || i)::uuid, 'leaderboard ' || i, md5('organization')::uuid, md5('key' || i)::uuid
+FROM generate_series(0, $1 - 1) AS i;
+INSERT INTO players (id, name, key)
+SELECT md5('player' || i)::uuid, 'player ' || i, md5('key' || i)::uuid
+FROM generate_series(0, $2 - 1) AS i;
+INSERT INTO scores (id, leaderboard, score, player, timestamp)
+SELECT md5('score' || i)::uuid, md5('leaderboard' || (i % $1))::uuid, random() * 10000,
+    md5('player' || (i % $2))::uuid, TIMESTAMP '2022-07-01' + i * INTERVAL '1 second'
+FROM generate_series(0, $3 - 1) AS i;
+"#;
+
+struct Query {
+    name: &'static str,
+    sql: &'static str,
+}
+
+const QUERIES: &[Query] = &[
+    Query {
+        name: "get scores",
+        sql: "SELECT score, meta, timestamp, players.name FROM scores, players WHERE leaderboard = md5('leaderboard1')::uuid and scores.player = players.id",
+    },
+    Query {
+        name: "top 100",
+        sql: "SELECT score, players.name FROM scores, players WHERE leaderboard = md5('leaderboard1')::uuid and scores.player = players.id ORDER BY score DESC LIMIT 100",
+    },
+    Query {
+        name: "duplicate check",
+        sql: "SELECT id FROM scores WHERE leaderboard = md5('leaderboard1')::uuid AND player = md5('player1')::uuid AND score = 10 AND timestamp = TO_TIMESTAMP(1656633600)",
+    },
+    Query {
+        name: "player scores",
+        sql: "SELECT id FROM scores WHERE leaderboard = md5('leaderboard1')::uuid AND player = md5('player1')::uuid",
+    },
+];
+
+async fn measure(pool: &PgPool) -> Vec<Duration> {
+    pool.execute("ANALYZE scores").await.unwrap();
+    let mut results = vec![];
+    for query in QUERIES {
+        // warm up the cache
+        pool.execute(query.sql).await.unwrap();
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            pool.execute(query.sql).await.unwrap();
+        }
+        results.push(start.elapsed() / ITERATIONS);
+    }
+    results
+}
+
+#[tokio::main]
+async fn main() {
+    let scores: i64 = std::env::var("JORNET_BENCH_SCORES")
+        .ok()
+        .and_then(|scores| scores.parse().ok())
+        .unwrap_or(10_000_000);
+
+    let mut configuration = get_configuration();
+    configuration.database.database_name = format!("bench-{}", Uuid::new_v4());
+    let mut connection =
+        PgConnection::connect(&configuration.database.connection_string_without_db())
+            .await
+            .expect("Failed to connect to Postgres");
+    connection
+        .execute(
+            format!(
+                r#"CREATE DATABASE "{}";"#,
+                configuration.database.database_name
+            )
+            .as_str(),
+        )
+        .await
+        .expect("Failed to create database.");
+    let pool = PgPool::connect(&configuration.database.connection_string())
+        .await
+        .expect("Failed to connect to Postgres.");
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("Failed to migrate the database");
+    pool.execute(BEFORE_MIGRATION).await.unwrap();
+
+    println!(
+        "seeding {} scores in {} leaderboards from {} players",
+        scores, LEADERBOARDS, PLAYERS
+    );
+    let start = Instant::now();
+    let mut seeding = pool.acquire().await.unwrap();
+    for statement in SEED.split(';').filter(|sql| !sql.trim().is_empty()) {
+        let statement = statement
+            .replace("$1", &LEADERBOARDS.to_string())
+            .replace("$2", &PLAYERS.to_string())
+            .replace("$3", &scores.to_string());
+        seeding.execute(statement.as_str()).await.unwrap();
+    }
+    drop(seeding);
+    println!("seeded in {:?}", start.elapsed());
+
+    let before = measure(&pool).await;
+
+    let start = Instant::now();
+    pool.execute(MIGRATION).await.unwrap();
+    println!("migration applied in {:?}", start.elapsed());
+
+    let after = measure(&pool).await;
+
+    println!();
+    println!(
+        "{:<20}{:>15}{:>15}{:>10}",
+        "query", "before", "after", "speedup"
+    );
+    for ((query, before), after) in QUERIES.iter().zip(before).zip(after) {
+        println!(
+            "{:<20}{:>15}{:>15}{:>9.1}x",
+            query.name,
+            format!("{:.2?}", before),
+            format!("{:.2?}", after),
+            before.as_secs_f64() / after.as_secs_f64()
+        );
+    }
+
+    pool.close().await;
+    connection
+        .execute(
+            format!(
+                r#"DROP DATABASE "{}";"#,
+                configuration.database.database_name
+            )
+            .as_str(),
+        )
+        .await
+        .expect("Failed to drop database.");
+}
@@ -0,0 +1,26 @@
+CREATE TABLE organizations(
+    id UUID NOT NULL,
+    PRIMARY KEY (id),
+    name TEXT NOT NULL,
+    personal BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE TABLE organization_members(
+    organization UUID NOT NULL,
+    admin UUID NOT NULL,
+    PRIMARY KEY (organization, admin),
+    role TEXT NOT NULL
+);
+CREATE TABLE organization_invitations(
+    id UUID NOT NULL,
+    PRIMARY KEY (id),
+    organization UUID NOT NULL,
+    role TEXT NOT NULL,
+    github_login TEXT,
+    email TEXT,
+    invited_by UUID NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+-- every admin gets a personal organization, sharing its id, that takes over its leaderboards
+INSERT INTO organizations (id, name, personal) SELECT id, 'Personal', TRUE FROM admins;
+INSERT INTO organization_members (organization, admin, role) SELECT id, id, 'owner' FROM admins;
+ALTER TABLE leaderboards RENAME COLUMN owner TO organization;
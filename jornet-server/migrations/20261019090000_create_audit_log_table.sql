@@ -0,0 +1,20 @@
+CREATE TABLE audit_log(
+    id BIGSERIAL NOT NULL,
+    PRIMARY KEY (id),
+    timestamp TIMESTAMP NOT NULL DEFAULT NOW(),
+    actor UUID NOT NULL,
+    organization UUID,
+    action TEXT NOT NULL,
+    target UUID,
+    payload TEXT
+);
+CREATE INDEX audit_log_organization ON audit_log (organization, id);
+CREATE FUNCTION audit_log_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+CREATE TRIGGER audit_log_no_update BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_append_only();
+CREATE TRIGGER audit_log_no_truncate BEFORE TRUNCATE ON audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION audit_log_append_only();
@@ -0,0 +1,7 @@
+CREATE TABLE leaderboard_bans(
+    leaderboard UUID NOT NULL,
+    player UUID NOT NULL,
+    PRIMARY KEY (leaderboard, player),
+    banned_by UUID NOT NULL,
+    timestamp TIMESTAMP NOT NULL DEFAULT NOW()
+);
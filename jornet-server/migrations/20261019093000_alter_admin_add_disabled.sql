@@ -0,0 +1,3 @@
+ALTER TABLE admins
+ADD disabled BOOLEAN NOT NULL
+DEFAULT FALSE
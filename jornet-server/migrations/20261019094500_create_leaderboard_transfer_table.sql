@@ -0,0 +1,12 @@
+CREATE TABLE leaderboard_transfers(
+   id UUID NOT NULL,
+   PRIMARY KEY (id),
+   leaderboard UUID NOT NULL,
+   from_organization UUID NOT NULL,
+   to_admin UUID NOT NULL,
+   requested_by UUID NOT NULL,
+   status TEXT NOT NULL,
+   created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+   resolved_at TIMESTAMP
+);
+CREATE INDEX leaderboard_transfers_to_admin ON leaderboard_transfers (to_admin, status);
@@ -0,0 +1,27 @@
+-- scores of a deleted leaderboard or player can't be displayed, they are moved aside to
+-- `orphaned_scores` before adding the foreign keys, to be checked and dropped by hand
+CREATE TABLE orphaned_scores AS
+SELECT * FROM scores
+WHERE NOT EXISTS (SELECT 1 FROM leaderboards WHERE leaderboards.id = scores.leaderboard)
+OR NOT EXISTS (SELECT 1 FROM players WHERE players.id = scores.player);
+DELETE FROM scores WHERE id IN (SELECT id FROM orphaned_scores);
+DO $$
+DECLARE
+    orphans BIGINT;
+BEGIN
+    SELECT COUNT(*) INTO orphans FROM orphaned_scores;
+    IF orphans > 0 THEN
+        RAISE WARNING '% scores without leaderboard or player were moved to orphaned_scores', orphans;
+    END IF;
+END $$;
+ALTER TABLE scores
+ADD CONSTRAINT scores_leaderboard_fkey
+    FOREIGN KEY (leaderboard) REFERENCES leaderboards (id) ON DELETE CASCADE,
+ADD CONSTRAINT scores_player_fkey
+    FOREIGN KEY (player) REFERENCES players (id) ON DELETE CASCADE;
+-- ranking the scores of a leaderboard, with `get_ranked_scores` and `get_best_score`
+CREATE INDEX scores_leaderboard_score ON scores (leaderboard, score DESC);
+-- duplicate check when saving a score, and removing the scores of a banned player
+CREATE INDEX scores_leaderboard_player ON scores (leaderboard, player, timestamp);
+-- cascading the deletion of a player
+CREATE INDEX scores_player ON scores (player);
@@ -0,0 +1,27 @@
+CREATE TABLE webhooks(
+    id UUID NOT NULL,
+    PRIMARY KEY (id),
+    leaderboard UUID NOT NULL REFERENCES leaderboards (id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT NOT NULL,
+    format TEXT NOT NULL,
+    created_by UUID NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX webhooks_leaderboard ON webhooks (leaderboard);
+CREATE TABLE webhook_deliveries(
+    id UUID NOT NULL,
+    PRIMARY KEY (id),
+    webhook UUID NOT NULL REFERENCES webhooks (id) ON DELETE CASCADE,
+    event TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    response_status INTEGER,
+    error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    last_attempt_at TIMESTAMP
+);
+CREATE INDEX webhook_deliveries_webhook ON webhook_deliveries (webhook, created_at);
+CREATE INDEX webhook_deliveries_status ON webhook_deliveries (status);
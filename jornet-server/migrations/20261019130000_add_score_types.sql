@@ -0,0 +1,7 @@
+-- leaderboards created before score types keep 32 bits float scores
+ALTER TABLE leaderboards ADD COLUMN score_type TEXT NOT NULL DEFAULT 'f32';
+-- floats are kept with double precision, which holds the existing 32 bits floats exactly, and
+-- integers are kept exactly beside them
+ALTER TABLE scores
+ALTER COLUMN score TYPE DOUBLE PRECISION,
+ADD COLUMN exact BIGINT;
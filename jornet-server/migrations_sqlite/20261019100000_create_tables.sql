@@ -0,0 +1,86 @@
+-- SQLite schema, matching the state of the PostgreSQL migrations in `migrations`
+CREATE TABLE admins(
+    id BLOB PRIMARY KEY NOT NULL,
+    disabled BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE TABLE admins_github(
+    id INTEGER PRIMARY KEY NOT NULL,
+    login TEXT NOT NULL,
+    admin_id BLOB NOT NULL
+);
+CREATE TABLE leaderboards(
+    id BLOB PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    organization BLOB NOT NULL,
+    key BLOB NOT NULL
+);
+CREATE TABLE scores(
+    id BLOB PRIMARY KEY NOT NULL,
+    leaderboard BLOB NOT NULL,
+    score REAL NOT NULL,
+    player BLOB NOT NULL,
+    timestamp TEXT NOT NULL,
+    meta TEXT
+);
+CREATE TABLE players(
+    id BLOB PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    key BLOB NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE TABLE organizations(
+    id BLOB PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    personal BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE TABLE organization_members(
+    organization BLOB NOT NULL,
+    admin BLOB NOT NULL,
+    role TEXT NOT NULL,
+    PRIMARY KEY (organization, admin)
+);
+CREATE TABLE organization_invitations(
+    id BLOB PRIMARY KEY NOT NULL,
+    organization BLOB NOT NULL,
+    role TEXT NOT NULL,
+    github_login TEXT,
+    email TEXT,
+    invited_by BLOB NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE TABLE audit_log(
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    actor BLOB NOT NULL,
+    organization BLOB,
+    action TEXT NOT NULL,
+    target BLOB,
+    payload TEXT
+);
+CREATE INDEX audit_log_organization ON audit_log (organization, id);
+CREATE TRIGGER audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+CREATE TRIGGER audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+CREATE TABLE leaderboard_bans(
+    leaderboard BLOB NOT NULL,
+    player BLOB NOT NULL,
+    banned_by BLOB NOT NULL,
+    timestamp TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (leaderboard, player)
+);
+CREATE TABLE leaderboard_transfers(
+    id BLOB PRIMARY KEY NOT NULL,
+    leaderboard BLOB NOT NULL,
+    from_organization BLOB NOT NULL,
+    to_admin BLOB NOT NULL,
+    requested_by BLOB NOT NULL,
+    status TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TEXT
+);
+CREATE INDEX leaderboard_transfers_to_admin ON leaderboard_transfers (to_admin, status);
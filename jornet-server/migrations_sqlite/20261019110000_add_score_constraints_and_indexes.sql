@@ -0,0 +1,27 @@
+-- SQLite can't add foreign keys to an existing table, the scores are copied to a new one.
+-- Scores of a deleted leaderboard or player can't be displayed, they are moved aside to
+-- `orphaned_scores`, to be checked and dropped by hand
+CREATE TABLE orphaned_scores AS
+SELECT * FROM scores
+WHERE NOT EXISTS (SELECT 1 FROM leaderboards WHERE leaderboards.id = scores.leaderboard)
+OR NOT EXISTS (SELECT 1 FROM players WHERE players.id = scores.player);
+CREATE TABLE scores_with_constraints(
+    id BLOB PRIMARY KEY NOT NULL,
+    leaderboard BLOB NOT NULL REFERENCES leaderboards (id) ON DELETE CASCADE,
+    score REAL NOT NULL,
+    player BLOB NOT NULL REFERENCES players (id) ON DELETE CASCADE,
+    timestamp TEXT NOT NULL,
+    meta TEXT
+);
+INSERT INTO scores_with_constraints (id, leaderboard, score, player, timestamp, meta)
+SELECT id, leaderboard, score, player, timestamp, meta FROM scores
+WHERE EXISTS (SELECT 1 FROM leaderboards WHERE leaderboards.id = scores.leaderboard)
+AND EXISTS (SELECT 1 FROM players WHERE players.id = scores.player);
+DROP TABLE scores;
+ALTER TABLE scores_with_constraints RENAME TO scores;
+-- ranking the scores of a leaderboard, with `get_ranked_scores` and `get_best_score`
+CREATE INDEX scores_leaderboard_score ON scores (leaderboard, score DESC);
+-- duplicate check when saving a score, and removing the scores of a banned player
+CREATE INDEX scores_leaderboard_player ON scores (leaderboard, player, timestamp);
+-- cascading the deletion of a player
+CREATE INDEX scores_player ON scores (player);
@@ -0,0 +1,25 @@
+CREATE TABLE webhooks(
+    id BLOB PRIMARY KEY NOT NULL,
+    leaderboard BLOB NOT NULL REFERENCES leaderboards (id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT NOT NULL,
+    format TEXT NOT NULL,
+    created_by BLOB NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX webhooks_leaderboard ON webhooks (leaderboard);
+CREATE TABLE webhook_deliveries(
+    id BLOB PRIMARY KEY NOT NULL,
+    webhook BLOB NOT NULL REFERENCES webhooks (id) ON DELETE CASCADE,
+    event TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    response_status INTEGER,
+    error TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_attempt_at TEXT
+);
+CREATE INDEX webhook_deliveries_webhook ON webhook_deliveries (webhook, created_at);
+CREATE INDEX webhook_deliveries_status ON webhook_deliveries (status);
@@ -0,0 +1,4 @@
+-- leaderboards created before score types keep 32 bits float scores
+ALTER TABLE leaderboards ADD COLUMN score_type TEXT NOT NULL DEFAULT 'f32';
+-- REAL is already a 64 bits float in SQLite, integers are kept exactly beside it
+ALTER TABLE scores ADD COLUMN exact INTEGER;
@@ -0,0 +1,4 @@
+-- accounts created by `/oauth/anonymous`, the only ones that can log in by UUID outside of dev mode
+ALTER TABLE admins
+ADD anonymous BOOLEAN NOT NULL
+DEFAULT FALSE
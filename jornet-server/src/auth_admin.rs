@@ -3,29 +3,48 @@ use actix_web_httpauth::extractors::{
     bearer::{BearerAuth, Config},
     AuthenticationError,
 };
-use biscuit_auth::{Biscuit, KeyPair};
+use std::time::Duration;
 
-use crate::domains::admin::{AdminAccount, BiscuitFact};
+use biscuit_auth::{AuthorizerLimits, Biscuit, KeyPair};
+
+use crate::{
+    domains::admin::{AdminAccount, BiscuitFact},
+    storage::Storage,
+};
 
 pub async fn validator(
     req: ServiceRequest,
     credentials: BearerAuth,
 ) -> Result<ServiceRequest, (Error, ServiceRequest)> {
     let root = req.app_data::<web::Data<KeyPair>>().unwrap();
-    if let Some(user) = Biscuit::from_base64(credentials.token(), |_| Ok(root.public()))
+    let storage = req.app_data::<web::Data<dyn Storage>>().unwrap();
+    match Biscuit::from_base64(credentials.token(), |_| Ok(root.public()))
         .ok()
         .and_then(|biscuit| authorize(&biscuit))
     {
-        req.extensions_mut().insert(user);
-        Ok(req)
-    } else {
-        Err((AuthenticationError::from(Config::default()).into(), req))
+        // the signature alone isn't enough, tokens of deleted or disabled accounts are refused
+        // before they expire
+        Some(user) if storage.is_admin_active(&user).await => {
+            req.extensions_mut().insert(user);
+            Ok(req)
+        }
+        _ => Err((AuthenticationError::from(Config::default()).into(), req)),
+    }
+}
+
+/// The default limits of biscuit allow 1ms of datalog evaluation, which valid tokens sometimes
+/// exceed when the server is busy.
+pub(crate) fn limits() -> AuthorizerLimits {
+    AuthorizerLimits {
+        max_time: Duration::from_millis(50),
+        ..Default::default()
     }
 }
 
 pub fn authorize(token: &Biscuit) -> Option<AdminAccount> {
     let mut authorizer = token.authorizer().ok()?;
 
+    authorizer.set_limits(limits());
     authorizer.set_time();
     authorizer.allow().map_err(|_| ()).ok()?;
     authorizer.authorize().map_err(|_| ()).ok()?;
@@ -0,0 +1,268 @@
+//! In-process cache of the public scores of leaderboards, by page, so that players refreshing the
+//! same leaderboard don't all reach the storage. It also notifies the live subscribers of a
+//! leaderboard when its scores change.
+//!
+//! Entries are invalidated by the handlers changing scores. Writes made directly to the storage,
+//! or by another server sharing the same database, are not seen until the next invalidation.
+
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroUsize,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use actix_web::{http::header::EntityTag, web::Bytes};
+use lru::LruCache;
+use prometheus::{IntCounterVec, Opts};
+use sha2::{Digest, Sha256};
+use tokio::sync::{watch, OnceCell};
+use uuid::Uuid;
+
+use crate::{
+    domains::{
+        live::PlayerRank,
+        score::{ModeratedScore, Score, SortDirection},
+    },
+    storage::Storage,
+};
+
+/// Pages kept in the cache, the least recently used are evicted first.
+const MAX_ENTRIES: usize = 10_000;
+
+/// Which scores of a leaderboard are cached together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Page {
+    /// All the scores, in the order of the storage.
+    All,
+    /// A page of the scores from the best, starting at 1.
+    Ranked { page: i64, per_page: i64 },
+}
+
+/// Scores of a page, with their public form already serialized.
+pub struct CachedScores {
+    pub scores: Vec<ModeratedScore>,
+    /// Scores in the leaderboard, not only in this page.
+    pub total: i64,
+    pub body: Bytes,
+    pub etag: EntityTag,
+    /// Order of the scores of the leaderboard, from its type.
+    direction: SortDirection,
+    ranking: OnceLock<Ranking>,
+}
+
+/// Scores from the best, shared by all the live subscribers of a leaderboard.
+pub struct Ranking {
+    pub scores: Vec<ModeratedScore>,
+    /// Rank of the best score of each player.
+    pub players: HashMap<Uuid, PlayerRank>,
+}
+
+impl Ranking {
+    fn new(scores: &[ModeratedScore], direction: SortDirection) -> Self {
+        let mut scores = scores.to_vec();
+        scores.sort_by(|a, b| direction.compare(&a.score, &b.score));
+        let mut players = HashMap::new();
+        let mut rank = 0;
+        for (index, score) in scores.iter().enumerate() {
+            // equal scores share the same rank
+            if index == 0 || scores[index - 1].score.total_cmp(&score.score).is_ne() {
+                rank = index + 1;
+            }
+            players.entry(score.player).or_insert(PlayerRank {
+                rank,
+                score: score.score,
+            });
+        }
+        Self { scores, players }
+    }
+}
+
+impl CachedScores {
+    fn new(scores: Vec<ModeratedScore>, total: i64, direction: SortDirection) -> Self {
+        let body = serde_json::to_vec(
+            &scores
+                .iter()
+                .map(|score| Score::from(score.clone()))
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        let mut hash = Sha256::new();
+        hash.update(&body);
+        hash.update(total.to_le_bytes());
+        let hash = hash.finalize();
+        Self {
+            scores,
+            total,
+            // weak as the body can be compressed differently
+            etag: EntityTag::new_weak(hex::encode(&hash[..16])),
+            body: body.into(),
+            direction,
+            ranking: OnceLock::new(),
+        }
+    }
+
+    /// The scores ranked from the best, computed on the first call.
+    pub fn ranking(&self) -> &Ranking {
+        self.ranking
+            .get_or_init(|| Ranking::new(&self.scores, self.direction))
+    }
+}
+
+/// Filled by the first request missing the page, the others wait for it instead of also reading
+/// the storage.
+type Slot = Arc<OnceCell<Arc<CachedScores>>>;
+
+struct Entries {
+    scores: LruCache<(Uuid, Page), Slot>,
+    /// Pages cached for each leaderboard, to invalidate them together.
+    pages: HashMap<Uuid, HashSet<Page>>,
+    subscriptions: HashMap<Uuid, watch::Sender<()>>,
+}
+
+impl Default for Entries {
+    fn default() -> Self {
+        Self {
+            scores: LruCache::new(NonZeroUsize::new(MAX_ENTRIES).unwrap()),
+            pages: HashMap::new(),
+            subscriptions: HashMap::new(),
+        }
+    }
+}
+
+impl Entries {
+    /// The slot of a page, and whether it was already cached.
+    fn slot(&mut self, leaderboard: Uuid, page: Page) -> (Slot, bool) {
+        if let Some(slot) = self.scores.get(&(leaderboard, page)) {
+            return (slot.clone(), true);
+        }
+        let slot = Slot::default();
+        if let Some(((evicted, evicted_page), _)) =
+            self.scores.push((leaderboard, page), slot.clone())
+        {
+            if let Some(pages) = self.pages.get_mut(&evicted) {
+                pages.remove(&evicted_page);
+                if pages.is_empty() {
+                    self.pages.remove(&evicted);
+                }
+            }
+        }
+        self.pages.entry(leaderboard).or_default().insert(page);
+        (slot, false)
+    }
+
+    /// Forget all the pages of a leaderboard. Requests already reading them from the storage
+    /// still get their result, but it isn't cached.
+    fn remove(&mut self, leaderboard: Uuid) {
+        for page in self.pages.remove(&leaderboard).into_iter().flatten() {
+            self.scores.pop(&(leaderboard, page));
+        }
+    }
+
+    fn notify(&mut self, leaderboard: Uuid) {
+        if let Some(sender) = self.subscriptions.get(&leaderboard) {
+            if sender.send(()).is_err() {
+                // nobody is listening anymore
+                self.subscriptions.remove(&leaderboard);
+            }
+        }
+    }
+}
+
+pub struct ScoreCache {
+    entries: Mutex<Entries>,
+    /// Reads of the cache, by `result`: `hit` or `miss`.
+    lookups: IntCounterVec,
+}
+
+impl Default for ScoreCache {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::default(),
+            lookups: IntCounterVec::new(
+                Opts::new(
+                    "jornet_score_cache_lookups_total",
+                    "Reads of the score cache",
+                ),
+                &["result"],
+            )
+            .unwrap(),
+        }
+    }
+}
+
+impl ScoreCache {
+    /// A page of the scores of a leaderboard, read from the storage if it's not cached.
+    pub async fn get(
+        &self,
+        storage: &dyn Storage,
+        leaderboard: Uuid,
+        page: Page,
+    ) -> Arc<CachedScores> {
+        let (slot, hit) = self.entries.lock().unwrap().slot(leaderboard, page);
+        self.lookups
+            .with_label_values(&[if hit { "hit" } else { "miss" }])
+            .inc();
+
+        slot.get_or_init(|| async {
+            let direction = storage
+                .get_leaderboard(leaderboard)
+                .await
+                .map(|leaderboard| leaderboard.score_type.sort_direction())
+                .unwrap_or_default();
+            Arc::new(match page {
+                Page::All => {
+                    let scores = storage.get_scores_for_moderation(leaderboard).await;
+                    let total = scores.len() as i64;
+                    CachedScores::new(scores, total, direction)
+                }
+                Page::Ranked { page, per_page } => CachedScores::new(
+                    storage
+                        .get_ranked_scores(
+                            leaderboard,
+                            direction,
+                            (page - 1).saturating_mul(per_page),
+                            per_page,
+                        )
+                        .await,
+                    storage.count_scores(leaderboard).await,
+                    direction,
+                ),
+            })
+        })
+        .await
+        .clone()
+    }
+
+    /// Forget the scores of a leaderboard, after they changed.
+    pub fn invalidate(&self, leaderboard: Uuid) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.remove(leaderboard);
+        entries.notify(leaderboard);
+    }
+
+    /// Forget the scores of all leaderboards, for changes that can affect several of them.
+    pub fn clear(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.scores.clear();
+        entries.pages.clear();
+        let leaderboards: Vec<_> = entries.subscriptions.keys().copied().collect();
+        for leaderboard in leaderboards {
+            entries.notify(leaderboard);
+        }
+    }
+
+    pub fn lookups(&self) -> &IntCounterVec {
+        &self.lookups
+    }
+
+    /// Get notified each time the scores of a leaderboard change.
+    pub fn subscribe(&self, leaderboard: Uuid) -> watch::Receiver<()> {
+        self.entries
+            .lock()
+            .unwrap()
+            .subscriptions
+            .entry(leaderboard)
+            .or_insert_with(|| watch::channel(()).0)
+            .subscribe()
+    }
+}
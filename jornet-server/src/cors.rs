@@ -0,0 +1,73 @@
+//! Cross-origin requests, allowed by the policy in [`CorsSettings`].
+//!
+//! The policy applies to every endpoint. The origins of a leaderboard are allowed on the paths
+//! containing its id, like `/api/v1/scores/{leaderboard_id}`.
+
+use std::{collections::HashMap, sync::Arc};
+
+use actix_cors::Cors;
+use actix_web::http::header::{self, HeaderValue};
+use uuid::Uuid;
+
+use crate::{configuration::CorsSettings, telemetry::REQUEST_ID};
+
+/// Whether `origin` can be used in the settings, as `scheme://host[:port]` with an optional `*.`
+/// before the host.
+pub fn valid_origin(origin: &str) -> bool {
+    let concrete = origin.replacen("://*.", "://", 1);
+    reqwest::Url::parse(&concrete)
+        .map(|url| url.origin().ascii_serialization() == concrete)
+        .unwrap_or(false)
+}
+
+fn matches(allowed: &str, origin: &str) -> bool {
+    match allowed.split_once("://*.") {
+        Some((scheme, domain)) => origin
+            .strip_prefix(scheme)
+            .and_then(|origin| origin.strip_prefix("://"))
+            .and_then(|host| host.strip_suffix(domain))
+            .is_some_and(|subdomain| subdomain.len() > 1 && subdomain.ends_with('.')),
+        None => allowed == origin,
+    }
+}
+
+pub fn cors(settings: &CorsSettings) -> Cors {
+    let cors = Cors::default()
+        .allow_any_header()
+        .allow_any_method()
+        .expose_headers([header::ETAG, header::RETRY_AFTER, REQUEST_ID]);
+    let cors = match settings.max_age_seconds {
+        Some(max_age) => cors.max_age(max_age),
+        None => cors,
+    };
+    let cors = if settings.allow_credentials {
+        cors.supports_credentials()
+    } else {
+        cors
+    };
+
+    if settings.allowed_origins.iter().any(|origin| origin == "*") {
+        return cors.allow_any_origin().send_wildcard();
+    }
+    let allowed = settings.allowed_origins.clone();
+    let by_leaderboard: Arc<HashMap<Uuid, Vec<String>>> = Arc::new(
+        settings
+            .leaderboard_origins
+            .iter()
+            .map(|origins| (origins.leaderboard, origins.origins.clone()))
+            .collect(),
+    );
+    cors.allowed_origin_fn(move |origin: &HeaderValue, request| {
+        let Ok(origin) = origin.to_str() else {
+            return false;
+        };
+        allowed.iter().any(|allowed| matches(allowed, origin))
+            || request
+                .uri
+                .path()
+                .split('/')
+                .filter_map(|segment| Uuid::parse_str(segment).ok())
+                .filter_map(|leaderboard| by_leaderboard.get(&leaderboard))
+                .any(|origins| origins.iter().any(|allowed| matches(allowed, origin)))
+    })
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use actix_web::{dev::HttpServiceFactory, web, HttpResponse, Responder};
 use actix_web_httpauth::middleware::HttpAuthentication;
 use biscuit_auth::{
@@ -5,20 +7,30 @@ use biscuit_auth::{
     Authorizer, Biscuit, KeyPair,
 };
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use serde_json::json;
 use time::{format_description::well_known::Rfc3339, Duration, OffsetDateTime};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::auth_admin::validator;
+use crate::{
+    auth_admin::validator,
+    cache::ScoreCache,
+    configuration::{OAuth, Settings},
+    storage::Storage,
+};
 
-use super::oauth::TOKEN_TTL;
+use super::{
+    audit::AuditAction,
+    oauth::{uuid_login_allowed, TOKEN_TTL},
+    organization::Organization,
+};
 
 #[derive(Serialize, Deserialize)]
 pub struct TokenReply {
     pub token: String,
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Serialize, ToSchema)]
 pub struct AdminAccount {
     pub id: Uuid,
 }
@@ -41,68 +53,219 @@ impl BiscuitFact for AdminAccount {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct GithubUser {
-    login: String,
-    id: u32,
+    pub login: String,
+    pub id: u32,
 }
 
 pub(crate) fn admin(kp: web::Data<KeyPair>) -> impl HttpServiceFactory {
     web::scope("api/v1/admin")
         .app_data(kp)
         .wrap(HttpAuthentication::bearer(validator))
+        .route("", web::delete().to(delete_account))
         .route("whoami", web::get().to(whoami))
+        .route("identities", web::get().to(get_identities))
+        .route("github", web::post().to(link_github))
+        .route("github", web::delete().to(unlink_github))
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct Identity<'a> {
     admin: &'a AdminAccount,
     github: Option<GithubUser>,
 }
 
+/// Get the current admin
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/whoami",
+    tag = "admin",
+    security(("admin" = [])),
+    responses((status = 200, description = "The admin and their GitHub account", body = Identity))
+)]
 async fn whoami(
     account: web::ReqData<AdminAccount>,
-    connection: web::Data<PgPool>,
+    storage: web::Data<dyn Storage>,
 ) -> impl Responder {
     HttpResponse::Ok().json(Identity {
         admin: &account,
-        github: account.has_github(&connection).await,
+        github: storage.get_admin_github(&account).await,
     })
 }
 
-impl AdminAccount {
-    pub async fn exist(&self, connection: &PgPool) -> bool {
-        sqlx::query!("SELECT id FROM admins WHERE id = $1", self.id)
-            .fetch_one(connection)
-            .await
-            .is_ok()
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct GithubCodeInput {
+    pub code: String,
+}
+
+/// Link a GitHub account
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/github",
+    tag = "admin",
+    security(("admin" = [])),
+    request_body = GithubCodeInput,
+    responses(
+        (status = 200, description = "The admin and their GitHub account", body = Identity),
+        (status = 401, description = "Invalid OAuth code"),
+        (status = 409, description = "A GitHub account is already linked to this admin, or \
+            this GitHub account to another admin"),
+    )
+)]
+async fn link_github(
+    account: web::ReqData<AdminAccount>,
+    config: web::Data<Settings>,
+    storage: web::Data<dyn Storage>,
+    code: web::Json<GithubCodeInput>,
+) -> impl Responder {
+    if storage.get_admin_github(&account).await.is_some() {
+        return HttpResponse::Conflict().finish();
     }
-    pub async fn has_github(&self, connection: &PgPool) -> Option<GithubUser> {
-        match sqlx::query!(
-            "SELECT id, login FROM admins_github WHERE admin_id = $1",
-            self.id
-        )
-        .fetch_one(connection)
-        .await
+    let Some(user) = GithubUser::from_oauth_code(&code.code, &config.github_admin_app).await else {
+        return HttpResponse::Unauthorized().finish();
+    };
+    if storage.github_exists(&user).await {
+        return HttpResponse::Conflict().finish();
+    }
+
+    if storage.link_github(&user, &account).await {
+        HttpResponse::Ok().json(Identity {
+            admin: &account,
+            github: Some(user),
+        })
+    } else {
+        HttpResponse::InternalServerError().finish()
+    }
+}
+
+/// A way for an admin to log in.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, ToSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum LinkedIdentity {
+    Uuid { id: Uuid },
+    Github { id: u32, login: String },
+}
+
+/// Get the ways to log in of the current admin
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/identities",
+    tag = "admin",
+    security(("admin" = [])),
+    responses((status = 200, description = "The linked identities", body = Vec<LinkedIdentity>))
+)]
+async fn get_identities(
+    account: web::ReqData<AdminAccount>,
+    config: web::Data<Settings>,
+    storage: web::Data<dyn Storage>,
+) -> impl Responder {
+    let identities = match storage.get_admin_github(&account).await {
+        Some(github) => vec![LinkedIdentity::Github {
+            id: github.id,
+            login: github.login,
+        }],
+        None if uuid_login_allowed(&config, storage.as_ref(), &account).await => {
+            vec![LinkedIdentity::Uuid { id: account.id }]
+        }
+        None => vec![],
+    };
+    HttpResponse::Ok().json(identities)
+}
+
+/// Unlink the GitHub account
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/github",
+    tag = "admin",
+    security(("admin" = [])),
+    responses(
+        (status = 200, description = "The admin without GitHub account", body = Identity),
+        (status = 404, description = "No GitHub account is linked"),
+        (status = 409, description = "The admin couldn't log in anymore"),
+    )
+)]
+async fn unlink_github(
+    account: web::ReqData<AdminAccount>,
+    config: web::Data<Settings>,
+    storage: web::Data<dyn Storage>,
+) -> impl Responder {
+    if storage.get_admin_github(&account).await.is_none() {
+        return HttpResponse::NotFound().finish();
+    }
+    // without GitHub, the account could only log in by UUID
+    if !uuid_login_allowed(&config, storage.as_ref(), &account).await {
+        return HttpResponse::Conflict().finish();
+    }
+
+    if storage.unlink_github(&account).await {
+        HttpResponse::Ok().json(Identity {
+            admin: &account,
+            github: None,
+        })
+    } else {
+        HttpResponse::InternalServerError().finish()
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeleteAccountQuery {
+    /// Admin receiving the organizations and leaderboards owned only by the deleted account.
+    /// They are deleted when not set.
+    pub transfer_to: Option<Uuid>,
+}
+
+/// Delete the current admin
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin",
+    tag = "admin",
+    security(("admin" = [])),
+    params(DeleteAccountQuery),
+    responses(
+        (status = 200, description = "The account was deleted"),
+        (status = 400, description = "Invalid recipient"),
+    )
+)]
+async fn delete_account(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    cache: web::Data<ScoreCache>,
+    query: web::Query<DeleteAccountQuery>,
+) -> impl Responder {
+    if let Some(recipient) = query.transfer_to {
+        let recipient = AdminAccount { id: recipient };
+        if recipient.id == account.id || !storage.is_admin_active(&recipient).await {
+            return HttpResponse::BadRequest().finish();
+        }
+        if Organization::personal(storage.get_ref(), recipient.id)
+            .await
+            .is_none()
         {
-            Ok(record) => Some(GithubUser {
-                login: record.login,
-                id: record.id as u32,
-            }),
-            _ => None,
+            return HttpResponse::InternalServerError().finish();
         }
     }
-    pub async fn create(&self, connection: &PgPool) -> bool {
-        sqlx::query!(
-            r#"
-            INSERT INTO admins (id) VALUES ($1)
-            "#,
-            self.id,
-        )
-        .execute(connection)
-        .await
-        .is_ok()
+
+    if storage.delete_admin(&account, query.transfer_to).await {
+        // leaderboards owned only by the account are deleted without a recipient
+        cache.clear();
+        storage
+            .record_audit(
+                account.id,
+                None,
+                AuditAction::AccountDelete,
+                Some(account.id),
+                json!({ "transfer_to": query.transfer_to }),
+            )
+            .await;
+        HttpResponse::Ok().finish()
+    } else {
+        HttpResponse::InternalServerError().finish()
     }
+}
+
+impl AdminAccount {
     pub fn create_biscuit(&self, root: &KeyPair) -> Biscuit {
         let mut builder = Biscuit::builder();
         builder
@@ -125,41 +288,41 @@ impl AdminAccount {
     }
 }
 
+#[derive(Deserialize)]
+struct GithubOauthResponse {
+    access_token: String,
+}
+
 impl GithubUser {
-    pub async fn exist(&self, connection: &PgPool) -> bool {
-        sqlx::query!(
-            "SELECT id FROM admins_github WHERE id = $1 AND login = $2",
-            self.id as i32,
-            self.login
-        )
-        .fetch_one(connection)
-        .await
-        .is_ok()
-    }
-    pub async fn create(&self, account: &AdminAccount, connection: &PgPool) -> bool {
-        sqlx::query!(
-            r#"
-            INSERT INTO admins_github (id, login, admin_id) VALUES ($1, $2, $3)
-            "#,
-            self.id as i64,
-            self.login,
-            account.id,
-        )
-        .fetch_one(connection)
-        .await
-        .is_ok()
-    }
-    pub async fn has_admin(&self, connection: &PgPool) -> Option<AdminAccount> {
-        sqlx::query!(
-            "SELECT admin_id FROM admins_github WHERE id = $1 AND login = $2",
-            self.id as i32,
-            self.login
-        )
-        .fetch_one(connection)
-        .await
-        .map(|record| AdminAccount {
-            id: record.admin_id,
-        })
-        .ok()
+    /// Exchange an OAuth code from GitHub for the corresponding user.
+    pub async fn from_oauth_code(code: &str, app: &OAuth) -> Option<GithubUser> {
+        let mut params = HashMap::new();
+        params.insert("client_id", app.client_id.as_str());
+        params.insert("client_secret", app.client_secret.as_str());
+        params.insert("code", code);
+
+        let client = reqwest::Client::new();
+
+        let github_bearer = client
+            .post("https://github.com/login/oauth/access_token")
+            .form(&params)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .ok()?
+            .json::<GithubOauthResponse>()
+            .await
+            .ok()?
+            .access_token;
+        client
+            .get("https://api.github.com/user")
+            .bearer_auth(github_bearer)
+            .header("user-agent", "jornet")
+            .send()
+            .await
+            .ok()?
+            .json::<GithubUser>()
+            .await
+            .ok()
     }
 }
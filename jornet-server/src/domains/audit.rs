@@ -0,0 +1,135 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::storage::Storage;
+
+use super::{
+    admin::AdminAccount,
+    organization::{Organization, Permission},
+};
+
+pub(crate) const DEFAULT_PAGE_SIZE: i64 = 50;
+pub(crate) const MAX_PAGE_SIZE: i64 = 200;
+
+/// A mutating admin action, as recorded in the audit log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    LeaderboardCreate,
+    LeaderboardDelete,
+    LeaderboardRotateKey,
+    ScoresDeleteAll,
+    ScoreDelete,
+    PlayerBan,
+    PlayerUnban,
+    OrganizationCreate,
+    OrganizationDelete,
+    MemberRoleChange,
+    MemberRemove,
+    InvitationCreate,
+    InvitationCancel,
+    InvitationAccept,
+    AccountDelete,
+    AdminDisable,
+    AdminEnable,
+    TransferRequest,
+    TransferCancel,
+    TransferAccept,
+    TransferDecline,
+    WebhookCreate,
+    WebhookDelete,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::LeaderboardCreate => "leaderboard_create",
+            AuditAction::LeaderboardDelete => "leaderboard_delete",
+            AuditAction::LeaderboardRotateKey => "leaderboard_rotate_key",
+            AuditAction::ScoresDeleteAll => "scores_delete_all",
+            AuditAction::ScoreDelete => "score_delete",
+            AuditAction::PlayerBan => "player_ban",
+            AuditAction::PlayerUnban => "player_unban",
+            AuditAction::OrganizationCreate => "organization_create",
+            AuditAction::OrganizationDelete => "organization_delete",
+            AuditAction::MemberRoleChange => "member_role_change",
+            AuditAction::MemberRemove => "member_remove",
+            AuditAction::InvitationCreate => "invitation_create",
+            AuditAction::InvitationCancel => "invitation_cancel",
+            AuditAction::InvitationAccept => "invitation_accept",
+            AuditAction::AccountDelete => "account_delete",
+            AuditAction::AdminDisable => "admin_disable",
+            AuditAction::AdminEnable => "admin_enable",
+            AuditAction::TransferRequest => "transfer_request",
+            AuditAction::TransferCancel => "transfer_cancel",
+            AuditAction::TransferAccept => "transfer_accept",
+            AuditAction::TransferDecline => "transfer_decline",
+            AuditAction::WebhookCreate => "webhook_create",
+            AuditAction::WebhookDelete => "webhook_delete",
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub timestamp: String,
+    pub actor: Uuid,
+    pub organization: Option<Uuid>,
+    pub action: String,
+    pub target: Option<Uuid>,
+    pub payload: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    pub actor: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub target: Option<Uuid>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+}
+
+/// Get the audit log of an organization
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations/{organization_id}/audit",
+    tag = "organizations",
+    security(("admin" = [])),
+    params(("organization_id" = Uuid, Path), AuditQuery),
+    responses(
+        (status = 200, description = "A page of the log, from the most recent action", body = AuditPage),
+        (status = 401, description = "Not allowed on this organization"),
+    )
+)]
+pub(crate) async fn get_audit_log(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    organization: web::Path<Uuid>,
+    query: web::Query<AuditQuery>,
+) -> impl Responder {
+    if !Organization::is_allowed(
+        storage.get_ref(),
+        *organization,
+        account.id,
+        Permission::ManageMembers,
+    )
+    .await
+    {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    HttpResponse::Ok().json(storage.get_audit_page(*organization, &query).await)
+}
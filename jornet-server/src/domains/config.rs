@@ -1,13 +1,21 @@
 use actix_web::{web, HttpResponse, Responder, Scope};
 use serde::Serialize;
+use utoipa::ToSchema;
 
 use crate::configuration::Settings;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct OauthConfig {
     github_app_id: String,
 }
 
+/// Get the OAuth applications of the server
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/oauth",
+    tag = "admin",
+    responses((status = 200, description = "Id of the GitHub application", body = OauthConfig))
+)]
 async fn get_oauth_config(config: web::Data<Settings>) -> impl Responder {
     HttpResponse::Ok().json(OauthConfig {
         github_app_id: config.github_admin_app.client_id.clone(),
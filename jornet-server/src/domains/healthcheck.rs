@@ -1,5 +1,142 @@
-use actix_web::{HttpResponse, Responder};
+//! Health of the server, for orchestrators.
+//!
+//! Liveness only tells that the server answers, while readiness also checks what it needs to
+//! serve requests: the database, its migrations and the biscuit private key. The checks are
+//! read-only, and the database errors are only logged as the endpoint is public.
 
+use std::time::Duration;
+
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
+use utoipa::ToSchema;
+
+use crate::{configuration::Settings, storage::Storage};
+
+/// How long the database has to answer a readiness check.
+const DATABASE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Check that the server answers
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    responses((status = 200, description = "The server is running"))
+)]
 pub(crate) async fn health_check() -> impl Responder {
     HttpResponse::Ok().finish()
 }
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct Readiness {
+    pub ready: bool,
+    pub checks: Checks,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct Checks {
+    pub database: Check,
+    pub migrations: Check,
+    pub biscuit_key: Check,
+}
+
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
+pub struct Check {
+    pub ok: bool,
+    /// What went wrong, or details on the checked state.
+    pub detail: Option<String>,
+}
+
+impl Check {
+    fn ok(detail: Option<String>) -> Self {
+        Self { ok: true, detail }
+    }
+
+    fn failed(detail: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+async fn check_database(storage: &dyn Storage) -> Check {
+    match tokio::time::timeout(DATABASE_TIMEOUT, storage.ping()).await {
+        Ok(Ok(())) => Check::ok(None),
+        Ok(Err(error)) => {
+            error!(%error, "the database failed the readiness check");
+            Check::failed("the database is unavailable")
+        }
+        Err(_) => Check::failed("timed out"),
+    }
+}
+
+async fn check_migrations(storage: &dyn Storage) -> Check {
+    let Some(expected) = storage.expected_schema_version() else {
+        return Check::ok(Some("no schema".to_string()));
+    };
+    match tokio::time::timeout(DATABASE_TIMEOUT, storage.schema_version()).await {
+        Ok(Ok(Some(version))) if version == expected => {
+            Check::ok(Some(format!("at version {}", expected)))
+        }
+        Ok(Ok(version)) => {
+            warn!(
+                ?version,
+                expected, "the database schema is not at the expected version"
+            );
+            if version.is_some_and(|version| version > expected) {
+                Check::failed("the schema is newer than the server")
+            } else {
+                Check::failed("migrations are pending")
+            }
+        }
+        Ok(Err(error)) => {
+            error!(%error, "the schema version failed the readiness check");
+            Check::failed("the schema version could not be read")
+        }
+        Err(_) => Check::failed("timed out"),
+    }
+}
+
+/// Check that the server can serve requests
+///
+/// `200 OK` when the server can serve requests, `503 Service Unavailable` otherwise, with the
+/// result of each check.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "The server is ready", body = Readiness),
+        (status = 503, description = "A check failed", body = Readiness),
+    )
+)]
+pub(crate) async fn readiness(
+    storage: web::Data<dyn Storage>,
+    config: web::Data<Settings>,
+) -> impl Responder {
+    let database = check_database(storage.get_ref()).await;
+    let migrations = if database.ok {
+        check_migrations(storage.get_ref()).await
+    } else {
+        Check::failed("the database is unreachable")
+    };
+    let biscuit_key = match config.load_keypair() {
+        Ok(_) => Check::ok(None),
+        Err(error) => Check::failed(error),
+    };
+
+    let readiness = Readiness {
+        ready: database.ok && migrations.ok && biscuit_key.ok,
+        checks: Checks {
+            database,
+            migrations,
+            biscuit_key,
+        },
+    };
+    if readiness.ready {
+        HttpResponse::Ok().json(readiness)
+    } else {
+        HttpResponse::ServiceUnavailable().json(readiness)
+    }
+}
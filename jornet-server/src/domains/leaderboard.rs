@@ -2,133 +2,609 @@ use actix_web::{dev::HttpServiceFactory, web, HttpResponse, Responder};
 use actix_web_httpauth::middleware::HttpAuthentication;
 use biscuit_auth::KeyPair;
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use serde_json::{json, Value};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::auth_admin::validator;
+use crate::{
+    auth_admin::validator,
+    cache::ScoreCache,
+    configuration::{Settings, UuidLogin},
+    storage::Storage,
+};
 
-use super::admin::AdminAccount;
+use super::{
+    admin::AdminAccount,
+    audit::AuditAction,
+    organization::{Organization, Permission, Role},
+    score::{ModeratedScore, ScoreType},
+    transfer::{cancel_transfer, request_transfer},
+    webhook::{
+        delete_webhook, get_deliveries, get_webhooks, register_webhook, WebhookEvent, Webhooks,
+    },
+};
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct LeaderboardInput {
     pub name: String,
+    /// `f32` when not set. It can't be changed once the leaderboard is created.
+    pub score_type: Option<ScoreType>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
 pub struct Leaderboard {
     pub id: Uuid,
     pub key: Uuid,
     pub name: String,
+    #[serde(default)]
+    pub score_type: ScoreType,
 }
 
-#[derive(Serialize, Debug)]
-struct LeaderboardWithScoreCount {
-    id: Uuid,
-    name: String,
-    scores: i64,
+/// Selects the organization of a leaderboard. When not set, the personal organization of the
+/// admin is used.
+#[derive(Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OrganizationQuery {
+    pub organization: Option<Uuid>,
 }
 
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct BanInput {
+    pub player: Uuid,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct Ban {
+    pub player: Uuid,
+    pub name: String,
+    pub banned_by: Uuid,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct LeaderboardWithScoreCount {
+    pub id: Uuid,
+    pub name: String,
+    pub organization: Uuid,
+    pub role: Role,
+    pub score_type: ScoreType,
+    pub scores: i64,
+}
+
+/// Create a leaderboard
+#[utoipa::path(
+    post,
+    path = "/api/v1/leaderboards",
+    tag = "leaderboards",
+    security(("admin" = [])),
+    params(OrganizationQuery),
+    request_body = LeaderboardInput,
+    responses(
+        (status = 200, description = "The new leaderboard, with the key used to sign its scores", body = Leaderboard),
+        (status = 401, description = "Not allowed on this organization"),
+        (status = 403, description = "Anonymous admins can't create more leaderboards"),
+    )
+)]
 async fn new_leaderboard(
     account: web::ReqData<AdminAccount>,
-    connection: web::Data<PgPool>,
+    config: web::Data<Settings>,
+    storage: web::Data<dyn Storage>,
+    organization: web::Query<OrganizationQuery>,
     leaderboard: web::Json<LeaderboardInput>,
 ) -> impl Responder {
+    let organization = match organization.organization {
+        Some(organization) => {
+            if !Organization::is_allowed(
+                storage.get_ref(),
+                organization,
+                account.id,
+                Permission::ManageLeaderboards,
+            )
+            .await
+            {
+                return HttpResponse::Unauthorized().finish();
+            }
+            organization
+        }
+        None => match Organization::personal(storage.get_ref(), account.id).await {
+            Some(organization) => organization,
+            None => return HttpResponse::InternalServerError().finish(),
+        },
+    };
+    if let UuidLogin::Anonymous(limits) = config.uuid_login {
+        if storage.get_admin_github(&account).await.is_none()
+            && storage.count_owned_leaderboards(account.id).await >= limits.max_leaderboards as i64
+        {
+            return HttpResponse::Forbidden().finish();
+        }
+    }
+
     let leaderboard = Leaderboard {
         name: leaderboard.name.clone(),
         id: Uuid::new_v4(),
         key: Uuid::new_v4(),
+        score_type: leaderboard.score_type.unwrap_or_default(),
     };
-    if leaderboard.create(&connection, account.id).await {
+    if storage.create_leaderboard(&leaderboard, organization).await {
+        storage
+            .record_audit(
+                account.id,
+                Some(organization),
+                AuditAction::LeaderboardCreate,
+                Some(leaderboard.id),
+                json!({ "name": leaderboard.name, "score_type": leaderboard.score_type }),
+            )
+            .await;
         HttpResponse::Ok().json(leaderboard)
     } else {
         HttpResponse::InternalServerError().finish()
     }
 }
 
+/// List the leaderboards of the current admin
+#[utoipa::path(
+    get,
+    path = "/api/v1/leaderboards",
+    tag = "leaderboards",
+    security(("admin" = [])),
+    params(OrganizationQuery),
+    responses(
+        (status = 200, description = "The leaderboards of the organizations of the admin", body = Vec<LeaderboardWithScoreCount>),
+    )
+)]
 async fn get_leaderboards(
     account: web::ReqData<AdminAccount>,
-    connection: web::Data<PgPool>,
+    storage: web::Data<dyn Storage>,
+    organization: web::Query<OrganizationQuery>,
+) -> impl Responder {
+    let leaderboards = storage.get_leaderboards(account.id).await;
+    match organization.organization {
+        Some(organization) => HttpResponse::Ok().json(
+            leaderboards
+                .into_iter()
+                .filter(|leaderboard| leaderboard.organization == organization)
+                .collect::<Vec<_>>(),
+        ),
+        None => HttpResponse::Ok().json(leaderboards),
+    }
+}
+
+/// Delete a leaderboard
+#[utoipa::path(
+    delete,
+    path = "/api/v1/leaderboards/{leaderboard_id}",
+    tag = "leaderboards",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "Whether the leaderboard was deleted", body = bool),
+        (status = 401, description = "Not allowed on this leaderboard"),
+        (status = 404, description = "Unknown leaderboard"),
+    )
+)]
+async fn delete_leaderboard(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    cache: web::Data<ScoreCache>,
+    leaderboard: web::Path<Uuid>,
+) -> impl Responder {
+    let Some(organization) = Leaderboard::allowed_organization(
+        storage.get_ref(),
+        *leaderboard,
+        account.id,
+        Permission::ManageLeaderboards,
+    )
+    .await
+    else {
+        return HttpResponse::Unauthorized().finish();
+    };
+    let Some(leaderboard) = storage.get_leaderboard(*leaderboard).await else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    let deleted = storage.delete_leaderboard(leaderboard.id).await;
+    if deleted {
+        cache.invalidate(leaderboard.id);
+        storage
+            .record_audit(
+                account.id,
+                Some(organization),
+                AuditAction::LeaderboardDelete,
+                Some(leaderboard.id),
+                json!({ "name": leaderboard.name }),
+            )
+            .await;
+    }
+    HttpResponse::Ok().json(deleted)
+}
+
+/// Replace the key of a leaderboard
+#[utoipa::path(
+    post,
+    path = "/api/v1/leaderboards/{leaderboard_id}/key",
+    tag = "leaderboards",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The leaderboard with its new key", body = Leaderboard),
+        (status = 401, description = "Not allowed on this leaderboard"),
+        (status = 404, description = "Unknown leaderboard"),
+    )
+)]
+async fn rotate_key(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    leaderboard: web::Path<Uuid>,
+) -> impl Responder {
+    let Some(organization) = Leaderboard::allowed_organization(
+        storage.get_ref(),
+        *leaderboard,
+        account.id,
+        Permission::ManageLeaderboards,
+    )
+    .await
+    else {
+        return HttpResponse::Unauthorized().finish();
+    };
+    let Some(mut leaderboard) = storage.get_leaderboard(*leaderboard).await else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    leaderboard.key = Uuid::new_v4();
+    if storage
+        .set_leaderboard_key(leaderboard.id, leaderboard.key)
+        .await
+    {
+        storage
+            .record_audit(
+                account.id,
+                Some(organization),
+                AuditAction::LeaderboardRotateKey,
+                Some(leaderboard.id),
+                Value::Null,
+            )
+            .await;
+        HttpResponse::Ok().json(leaderboard)
+    } else {
+        HttpResponse::InternalServerError().finish()
+    }
+}
+
+/// Get the scores of a leaderboard to moderate them
+#[utoipa::path(
+    get,
+    path = "/api/v1/leaderboards/{leaderboard_id}/scores",
+    tag = "leaderboards",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "All the scores", body = Vec<ModeratedScore>),
+        (status = 401, description = "Not allowed on this leaderboard"),
+    )
+)]
+async fn get_scores(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    leaderboard: web::Path<Uuid>,
 ) -> impl Responder {
-    HttpResponse::Ok().json(Leaderboard::get_all(&connection, account.id).await)
+    if Leaderboard::allowed_organization(
+        storage.get_ref(),
+        *leaderboard,
+        account.id,
+        Permission::View,
+    )
+    .await
+    .is_some()
+    {
+        HttpResponse::Ok().json(storage.get_scores_for_moderation(*leaderboard).await)
+    } else {
+        HttpResponse::Unauthorized().finish()
+    }
 }
 
+/// Delete all the scores of a leaderboard
+#[utoipa::path(
+    delete,
+    path = "/api/v1/leaderboards/{leaderboard_id}/scores",
+    tag = "leaderboards",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "Whether the scores were deleted", body = bool),
+        (status = 401, description = "Not allowed on this leaderboard"),
+    )
+)]
 async fn delete_all_scores(
     account: web::ReqData<AdminAccount>,
-    connection: web::Data<PgPool>,
+    storage: web::Data<dyn Storage>,
+    cache: web::Data<ScoreCache>,
+    webhooks: web::Data<Webhooks>,
     leaderboard: web::Path<Uuid>,
 ) -> impl Responder {
-    if Leaderboard::get_owner(&connection, &leaderboard).await == Some(account.id) {
-        HttpResponse::Ok().json(Leaderboard::delete_all_scores(&connection, &leaderboard).await)
+    if let Some(organization) = Leaderboard::allowed_organization(
+        storage.get_ref(),
+        *leaderboard,
+        account.id,
+        Permission::ManageLeaderboards,
+    )
+    .await
+    {
+        let deleted = storage.delete_all_scores(*leaderboard).await;
+        if let Some(count) = deleted {
+            cache.invalidate(*leaderboard);
+            storage
+                .record_audit(
+                    account.id,
+                    Some(organization),
+                    AuditAction::ScoresDeleteAll,
+                    Some(*leaderboard),
+                    json!({ "scores": count }),
+                )
+                .await;
+            webhooks
+                .trigger(
+                    storage.get_ref(),
+                    *leaderboard,
+                    WebhookEvent::LeaderboardReset,
+                    json!({ "scores": count }),
+                )
+                .await;
+        }
+        HttpResponse::Ok().json(deleted.is_some())
     } else {
         HttpResponse::Unauthorized().finish()
     }
 }
 
+/// Delete a score
+#[utoipa::path(
+    delete,
+    path = "/api/v1/leaderboards/{leaderboard_id}/scores/{score_id}",
+    tag = "leaderboards",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path), ("score_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The score was deleted", body = bool),
+        (status = 401, description = "Not allowed on this leaderboard"),
+        (status = 404, description = "Unknown score"),
+    )
+)]
+async fn delete_score(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    cache: web::Data<ScoreCache>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (leaderboard, score) = path.into_inner();
+    let Some(organization) = Leaderboard::allowed_organization(
+        storage.get_ref(),
+        leaderboard,
+        account.id,
+        Permission::ModerateScores,
+    )
+    .await
+    else {
+        return HttpResponse::Unauthorized().finish();
+    };
+
+    match storage.delete_score(leaderboard, score).await {
+        Some(deleted) => {
+            cache.invalidate(leaderboard);
+            storage
+                .record_audit(
+                    account.id,
+                    Some(organization),
+                    AuditAction::ScoreDelete,
+                    Some(leaderboard),
+                    json!({ "score": deleted.id, "player": deleted.player, "value": deleted.score }),
+                )
+                .await;
+            HttpResponse::Ok().json(true)
+        }
+        None => HttpResponse::NotFound().finish(),
+    }
+}
+
+/// List the players banned from a leaderboard
+#[utoipa::path(
+    get,
+    path = "/api/v1/leaderboards/{leaderboard_id}/bans",
+    tag = "leaderboards",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The banned players", body = Vec<Ban>),
+        (status = 401, description = "Not allowed on this leaderboard"),
+    )
+)]
+async fn get_bans(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    leaderboard: web::Path<Uuid>,
+) -> impl Responder {
+    if Leaderboard::allowed_organization(
+        storage.get_ref(),
+        *leaderboard,
+        account.id,
+        Permission::View,
+    )
+    .await
+    .is_some()
+    {
+        HttpResponse::Ok().json(storage.get_bans(*leaderboard).await)
+    } else {
+        HttpResponse::Unauthorized().finish()
+    }
+}
+
+/// Ban a player from a leaderboard, removing their scores
+#[utoipa::path(
+    post,
+    path = "/api/v1/leaderboards/{leaderboard_id}/bans",
+    tag = "leaderboards",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path)),
+    request_body = BanInput,
+    responses(
+        (status = 200, description = "The player was banned", body = bool),
+        (status = 401, description = "Not allowed on this leaderboard"),
+    )
+)]
+async fn ban_player(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    cache: web::Data<ScoreCache>,
+    webhooks: web::Data<Webhooks>,
+    leaderboard: web::Path<Uuid>,
+    ban: web::Json<BanInput>,
+) -> impl Responder {
+    let Some(organization) = Leaderboard::allowed_organization(
+        storage.get_ref(),
+        *leaderboard,
+        account.id,
+        Permission::ModerateScores,
+    )
+    .await
+    else {
+        return HttpResponse::Unauthorized().finish();
+    };
+
+    match storage
+        .ban_player(*leaderboard, ban.player, account.id)
+        .await
+    {
+        Some(removed) => {
+            cache.invalidate(*leaderboard);
+            storage
+                .record_audit(
+                    account.id,
+                    Some(organization),
+                    AuditAction::PlayerBan,
+                    Some(*leaderboard),
+                    json!({ "player": ban.player, "scores": removed }),
+                )
+                .await;
+            let name = storage
+                .get_player(ban.player)
+                .await
+                .map(|player| player.name);
+            webhooks
+                .trigger(
+                    storage.get_ref(),
+                    *leaderboard,
+                    WebhookEvent::PlayerBan,
+                    json!({
+                        "player": { "id": ban.player, "name": name },
+                        "scores": removed,
+                    }),
+                )
+                .await;
+            HttpResponse::Ok().json(true)
+        }
+        None => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+/// Lift the ban of a player
+#[utoipa::path(
+    delete,
+    path = "/api/v1/leaderboards/{leaderboard_id}/bans/{player_id}",
+    tag = "leaderboards",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path), ("player_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "Whether the player was banned", body = bool),
+        (status = 401, description = "Not allowed on this leaderboard"),
+    )
+)]
+async fn unban_player(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (leaderboard, player) = path.into_inner();
+    let Some(organization) = Leaderboard::allowed_organization(
+        storage.get_ref(),
+        leaderboard,
+        account.id,
+        Permission::ModerateScores,
+    )
+    .await
+    else {
+        return HttpResponse::Unauthorized().finish();
+    };
+
+    let unbanned = storage.unban_player(leaderboard, player).await;
+    if unbanned {
+        storage
+            .record_audit(
+                account.id,
+                Some(organization),
+                AuditAction::PlayerUnban,
+                Some(leaderboard),
+                json!({ "player": player }),
+            )
+            .await;
+    }
+    HttpResponse::Ok().json(unbanned)
+}
+
 pub(crate) fn leaderboard(kp: web::Data<KeyPair>) -> impl HttpServiceFactory {
     web::scope("api/v1/leaderboards")
         .app_data(kp)
         .wrap(HttpAuthentication::bearer(validator))
         .route("", web::post().to(new_leaderboard))
         .route("", web::get().to(get_leaderboards))
+        .route("{leaderboard_id}", web::delete().to(delete_leaderboard))
+        .route("{leaderboard_id}/key", web::post().to(rotate_key))
+        .route("{leaderboard_id}/scores", web::get().to(get_scores))
         .route(
             "{leaderboard_id}/scores",
             web::delete().to(delete_all_scores),
         )
-}
-
-impl Leaderboard {
-    async fn get_all(connection: &PgPool, owner: Uuid) -> Vec<LeaderboardWithScoreCount> {
-        sqlx::query!(
-            "SELECT leaderboards.id, name, count(scores.leaderboard) FROM leaderboards LEFT JOIN scores ON leaderboards.id = scores.leaderboard WHERE owner = $1 GROUP BY leaderboards.id;",
-            owner
+        .route(
+            "{leaderboard_id}/scores/{score_id}",
+            web::delete().to(delete_score),
         )
-        .fetch_all(connection)
-        .await
-        .unwrap()
-        .iter()
-        .map(|r| LeaderboardWithScoreCount {
-            id: r.id,
-            name: r.name.clone(),
-            scores: r.count.unwrap(),
-        })
-        .collect()
-    }
-
-    pub async fn get_key(connection: &PgPool, id: Uuid) -> Option<Uuid> {
-        sqlx::query!("SELECT key FROM leaderboards WHERE id = $1;", id)
-            .fetch_one(connection)
-            .await
-            .map(|r| r.key)
-            .ok()
-    }
-
-    pub async fn get_owner(connection: &PgPool, id: &Uuid) -> Option<Uuid> {
-        sqlx::query!("SELECT owner FROM leaderboards WHERE id = $1;", id)
-            .fetch_one(connection)
-            .await
-            .map(|r| r.owner)
-            .ok()
-    }
-
-    pub async fn create(&self, connection: &PgPool, owner: Uuid) -> bool {
-        sqlx::query!(
-            r#"
-            INSERT INTO leaderboards (id, name, owner, key) VALUES ($1, $2, $3, $4)
-            "#,
-            self.id,
-            self.name,
-            owner,
-            self.key,
+        .route("{leaderboard_id}/bans", web::get().to(get_bans))
+        .route("{leaderboard_id}/bans", web::post().to(ban_player))
+        .route(
+            "{leaderboard_id}/bans/{player_id}",
+            web::delete().to(unban_player),
         )
-        .execute(connection)
-        .await
-        .is_ok()
-    }
+        .route(
+            "{leaderboard_id}/transfer",
+            web::post().to(request_transfer),
+        )
+        .route(
+            "{leaderboard_id}/transfer",
+            web::delete().to(cancel_transfer),
+        )
+        .route("{leaderboard_id}/webhooks", web::get().to(get_webhooks))
+        .route(
+            "{leaderboard_id}/webhooks",
+            web::post().to(register_webhook),
+        )
+        .route(
+            "{leaderboard_id}/webhooks/{webhook_id}",
+            web::delete().to(delete_webhook),
+        )
+        .route(
+            "{leaderboard_id}/webhooks/{webhook_id}/deliveries",
+            web::get().to(get_deliveries),
+        )
+}
 
-    pub async fn delete_all_scores(connection: &PgPool, leaderboard: &Uuid) -> bool {
-        sqlx::query!("DELETE FROM scores WHERE leaderboard = $1", leaderboard)
-            .execute(connection)
+impl Leaderboard {
+    /// Get the organization of a leaderboard, if the admin is allowed to act on it.
+    pub async fn allowed_organization(
+        storage: &dyn Storage,
+        id: Uuid,
+        admin: Uuid,
+        permission: Permission,
+    ) -> Option<Uuid> {
+        let organization = storage.get_leaderboard_organization(id).await?;
+        Organization::is_allowed(storage, organization, admin, permission)
             .await
-            .is_ok()
+            .then_some(organization)
     }
 }
@@ -0,0 +1,178 @@
+//! Live updates of a leaderboard, streamed as Server-Sent Events.
+//!
+//! Scores are ranked from the best, the highest or the shortest for durations. A `top` event with
+//! the best scores is sent when subscribing, then each time they change. When subscribing as a
+//! player, a `rank` event with the rank of their best score is sent the same way, `null` until
+//! they have one.
+
+use std::{collections::VecDeque, convert::Infallible, time::Duration};
+
+use actix_web::{
+    http::header::{CacheControl, CacheDirective, ContentEncoding},
+    web::{self, Bytes},
+    HttpRequest, HttpResponse, Responder,
+};
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::watch,
+    time::{interval, Interval},
+};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    cache::{Page, ScoreCache},
+    rate_limit::RateLimits,
+    storage::Storage,
+};
+
+use super::score::{Score, ScoreValue};
+
+pub(crate) const DEFAULT_TOP: usize = 10;
+pub(crate) const MAX_TOP: usize = 100;
+/// Comments are sent when nothing changes, to keep the connection open through proxies.
+const HEARTBEAT: Duration = Duration::from_secs(15);
+
+#[derive(Deserialize, Serialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LiveQuery {
+    /// Number of scores in the `top` events, 10 by default.
+    pub top: Option<usize>,
+    /// Player receiving `rank` events.
+    pub player: Option<Uuid>,
+}
+
+/// Rank of the best score of a player, from 1. Equal scores share the same rank.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, ToSchema)]
+pub struct PlayerRank {
+    pub rank: usize,
+    #[schema(value_type = f64)]
+    pub score: ScoreValue,
+}
+
+struct Subscription {
+    storage: web::Data<dyn Storage>,
+    cache: web::Data<ScoreCache>,
+    leaderboard: Uuid,
+    top: usize,
+    player: Option<Uuid>,
+    changes: watch::Receiver<()>,
+    heartbeat: Interval,
+    last_top: Option<Vec<Score>>,
+    last_rank: Option<Option<PlayerRank>>,
+    pending: VecDeque<Bytes>,
+}
+
+impl Subscription {
+    async fn next(mut self) -> Option<(Result<Bytes, Infallible>, Self)> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some((Ok(event), self));
+            }
+            tokio::select! {
+                changed = self.changes.changed() => {
+                    changed.ok()?;
+                    self.update().await;
+                }
+                _ = self.heartbeat.tick() => self.pending.push_back(Bytes::from_static(b":\n\n")),
+            }
+        }
+    }
+
+    /// Queue the events for what changed since the last update.
+    async fn update(&mut self) {
+        let cached = self
+            .cache
+            .get(self.storage.get_ref(), self.leaderboard, Page::All)
+            .await;
+        // ranked once for all the subscribers, until the scores change
+        let ranking = cached.ranking();
+
+        let top: Vec<Score> = ranking
+            .scores
+            .iter()
+            .take(self.top)
+            .map(|score| Score::from(score.clone()))
+            .collect();
+        if self.last_top.as_ref() != Some(&top) {
+            self.pending.push_back(event("top", &top));
+            self.last_top = Some(top);
+        }
+
+        if let Some(player) = self.player {
+            let rank = ranking.players.get(&player).copied();
+            if self.last_rank != Some(rank) {
+                self.pending.push_back(event("rank", &rank));
+                self.last_rank = Some(rank);
+            }
+        }
+    }
+}
+
+fn event<T: Serialize>(name: &str, data: &T) -> Bytes {
+    format!(
+        "event: {}\ndata: {}\n\n",
+        name,
+        serde_json::to_string(data).unwrap()
+    )
+    .into()
+}
+
+/// Follow the scores of a leaderboard
+///
+/// A stream of Server-Sent Events. A `top` event with the best scores, as a list of `Score`, is
+/// sent when subscribing, then each time they change. With `player`, a `rank` event with a
+/// `PlayerRank`, or `null` until the player has a score, is sent the same way.
+#[utoipa::path(
+    get,
+    path = "/api/v1/scores/{leaderboard_id}/live",
+    tag = "scores",
+    params(("leaderboard_id" = Uuid, Path), LiveQuery),
+    responses(
+        (status = 200, description = "The events", content_type = "text/event-stream", body = String),
+        (status = 404, description = "Unknown leaderboard"),
+        (status = 429, description = "Too many reads from this IP"),
+    )
+)]
+pub(crate) async fn live_scores(
+    storage: web::Data<dyn Storage>,
+    cache: web::Data<ScoreCache>,
+    rate_limits: web::Data<RateLimits>,
+    request: HttpRequest,
+    leaderboard: web::Path<Uuid>,
+    query: web::Query<LiveQuery>,
+) -> impl Responder {
+    if let Err(response) = rate_limits.check_ip(&rate_limits.reads, &request) {
+        return response;
+    }
+    if storage.get_leaderboard(*leaderboard).await.is_none() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let mut changes = cache.subscribe(*leaderboard);
+    // send the current state right away
+    changes.mark_changed();
+    let mut heartbeat = interval(HEARTBEAT);
+    heartbeat.reset();
+
+    let subscription = Subscription {
+        storage,
+        cache,
+        leaderboard: *leaderboard,
+        top: query.top.unwrap_or(DEFAULT_TOP).clamp(1, MAX_TOP),
+        player: query.player,
+        changes,
+        heartbeat,
+        last_top: None,
+        last_rank: None,
+        pending: VecDeque::new(),
+    };
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+        // compression would buffer the events
+        .insert_header(ContentEncoding::Identity)
+        .streaming(stream::unfold(subscription, Subscription::next))
+}
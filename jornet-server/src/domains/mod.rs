@@ -1,7 +1,14 @@
 pub mod admin;
+pub mod audit;
 pub mod config;
 pub mod healthcheck;
 pub mod leaderboard;
+pub mod live;
 pub mod oauth;
+pub mod organization;
 pub mod player;
 pub mod score;
+pub mod superadmin;
+pub mod transfer;
+pub mod v2;
+pub mod webhook;
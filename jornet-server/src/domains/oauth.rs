@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use actix_web::{web, HttpResponse, Responder, Scope};
 use biscuit_auth::{
     // builder::{Fact, Term},
@@ -8,16 +6,21 @@ use biscuit_auth::{
     KeyPair,
 };
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::configuration::Settings;
+use crate::{
+    auth_admin::limits,
+    configuration::{Settings, UuidLogin},
+    storage::Storage,
+};
 
 use super::admin::{AdminAccount, GithubUser};
 
 pub const TOKEN_TTL: i64 = 600;
 
-#[derive(Serialize, Deserialize)]
+/// A biscuit token for the admin endpoints, valid for 10 minutes.
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct TokenReply {
     pub token: String,
 }
@@ -40,26 +43,78 @@ impl BiscuitFact for AdminAccount {
     }
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct UuidInput {
     uuid: Uuid,
 }
 
+/// Log in with a UUID
+#[utoipa::path(
+    post,
+    path = "/oauth/by_uuid",
+    tag = "oauth",
+    request_body = UuidInput,
+    responses(
+        (status = 200, description = "A token for the admin", body = TokenReply),
+        (status = 401, description = "Unknown admin"),
+        (status = 403, description = "Login by UUID is disabled or not allowed for this admin, or the admin is disabled"),
+    )
+)]
 async fn by_uuid(
     root: web::Data<KeyPair>,
-    connection: web::Data<PgPool>,
+    config: web::Data<Settings>,
+    storage: web::Data<dyn Storage>,
     uuid: web::Json<UuidInput>,
 ) -> impl Responder {
+    if config.uuid_login == UuidLogin::Disabled {
+        return HttpResponse::Forbidden().finish();
+    }
+
     let account = AdminAccount { id: uuid.uuid };
-    match (
-        account.exist(&connection).await,
-        account.has_github(&connection).await,
-    ) {
-        (_, Some(_)) => return HttpResponse::InternalServerError().finish(),
-        (false, _) => {
-            account.create(&connection).await;
+    // accounts linked to GitHub log in with it
+    if storage.get_admin_github(&account).await.is_some() {
+        return HttpResponse::Forbidden().finish();
+    }
+    if !storage.admin_exists(&account).await {
+        // only dev mode lets the client pick the UUID of a new account
+        if config.uuid_login != UuidLogin::Dev {
+            return HttpResponse::Unauthorized().finish();
         }
-        (true, _) => (),
+        storage.create_admin(&account).await;
+    } else if !uuid_login_allowed(&config, storage.as_ref(), &account).await
+        || !storage.is_admin_active(&account).await
+    {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let biscuit = account.create_biscuit(root.as_ref());
+    HttpResponse::Ok().json(TokenReply {
+        token: biscuit.to_base64().unwrap(),
+    })
+}
+
+/// Create an anonymous admin
+#[utoipa::path(
+    post,
+    path = "/oauth/anonymous",
+    tag = "oauth",
+    responses(
+        (status = 200, description = "A token for the new admin", body = TokenReply),
+        (status = 403, description = "Login by UUID is disabled"),
+    )
+)]
+async fn anonymous(
+    root: web::Data<KeyPair>,
+    config: web::Data<Settings>,
+    storage: web::Data<dyn Storage>,
+) -> impl Responder {
+    if config.uuid_login == UuidLogin::Disabled {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let account = AdminAccount { id: Uuid::new_v4() };
+    if !storage.create_anonymous_admin(&account).await {
+        return HttpResponse::InternalServerError().finish();
     }
 
     let biscuit = account.create_biscuit(root.as_ref());
@@ -67,9 +122,25 @@ async fn by_uuid(
         token: biscuit.to_base64().unwrap(),
     })
 }
+
+/// Whether the login mode lets an account log in by UUID when it's not linked to GitHub. Outside
+/// of dev mode, only the accounts created by `/oauth/anonymous` can.
+pub(crate) async fn uuid_login_allowed(
+    config: &Settings,
+    storage: &dyn Storage,
+    account: &AdminAccount,
+) -> bool {
+    match config.uuid_login {
+        UuidLogin::Dev => true,
+        UuidLogin::Anonymous(_) => storage.is_admin_anonymous(account).await,
+        UuidLogin::Disabled => false,
+    }
+}
+
 pub fn authorize(token: &Biscuit) -> Option<AdminAccount> {
     let mut authorizer = token.authorizer().ok()?;
 
+    authorizer.set_limits(limits());
     authorizer.set_time();
     authorizer.allow().map_err(|_| ()).ok()?;
     authorizer.authorize().map_err(|_| ()).ok()?;
@@ -77,59 +148,45 @@ pub fn authorize(token: &Biscuit) -> Option<AdminAccount> {
     AdminAccount::from_authorizer(&mut authorizer)
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct OauthCode {
     code: String,
 }
 
-#[derive(Deserialize)]
-pub struct GithubOauthResponse {
-    access_token: String,
-}
-
+/// Log in with GitHub
+#[utoipa::path(
+    get,
+    path = "/oauth/callback",
+    tag = "oauth",
+    params(OauthCode),
+    responses(
+        (status = 200, description = "A token for the admin", body = TokenReply),
+        (status = 401, description = "Invalid OAuth code"),
+        (status = 403, description = "The admin is disabled"),
+    )
+)]
 async fn oauth_callback(
     code: web::Query<OauthCode>,
     config: web::Data<Settings>,
-    connection: web::Data<PgPool>,
+    storage: web::Data<dyn Storage>,
     root: web::Data<KeyPair>,
 ) -> impl Responder {
-    let mut params = HashMap::new();
-    params.insert("client_id", &config.github_admin_app.client_id);
-    params.insert("client_secret", &config.github_admin_app.client_secret);
-    params.insert("code", &code.code);
-
-    let client = reqwest::Client::new();
-
-    let github_bearer = client
-        .post("https://github.com/login/oauth/access_token")
-        .form(&params)
-        .header("Accept", "application/json")
-        .send()
-        .await
-        .unwrap()
-        .json::<GithubOauthResponse>()
-        .await
-        .unwrap()
-        .access_token;
-    let user = client
-        .get("https://api.github.com/user")
-        .bearer_auth(github_bearer)
-        .header("user-agent", "jornet")
-        .send()
-        .await
-        .unwrap()
-        .json::<GithubUser>()
-        .await
-        .unwrap();
-
-    let admin = if user.exist(&connection).await {
-        user.has_admin(&connection).await.unwrap()
+    let Some(user) = GithubUser::from_oauth_code(&code.code, &config.github_admin_app).await else {
+        return HttpResponse::Unauthorized().finish();
+    };
+
+    let admin = if storage.github_exists(&user).await {
+        storage.get_github_admin(&user).await.unwrap()
     } else {
         let account = AdminAccount { id: Uuid::new_v4() };
-        account.create(&connection).await;
-        user.create(&account, &connection).await;
+        storage.create_admin(&account).await;
+        storage.link_github(&user, &account).await;
         account
     };
+    if !storage.is_admin_active(&admin).await {
+        return HttpResponse::Forbidden().finish();
+    }
 
     let biscuit = admin.create_biscuit(&root);
     HttpResponse::Ok().json(TokenReply {
@@ -140,6 +197,7 @@ async fn oauth_callback(
 pub(crate) fn oauth() -> Scope {
     web::scope("oauth")
         .route("by_uuid", web::post().to(by_uuid))
+        .route("anonymous", web::post().to(anonymous))
         .route("callback", web::get().to(oauth_callback))
 }
 
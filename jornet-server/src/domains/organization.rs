@@ -0,0 +1,653 @@
+use actix_web::{dev::HttpServiceFactory, web, HttpResponse, Responder};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use biscuit_auth::KeyPair;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{auth_admin::validator, storage::Storage};
+
+use super::{
+    admin::AdminAccount,
+    audit::{get_audit_log, AuditAction},
+};
+
+/// Role of an admin in an organization, from the least to the most privileged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,
+    Moderator,
+    Admin,
+    Owner,
+}
+
+/// Actions on an organization and its leaderboards, each needing a minimum [`Role`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Permission {
+    /// See the organization, its members and its leaderboards.
+    View,
+    /// Remove scores and ban players from leaderboards.
+    ModerateScores,
+    /// Create, delete and reset leaderboards.
+    ManageLeaderboards,
+    /// Invite and remove members, and change their roles.
+    ManageMembers,
+    /// Delete the organization and manage its owners.
+    ManageOrganization,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Moderator => "moderator",
+            Role::Admin => "admin",
+            Role::Owner => "owner",
+        }
+    }
+
+    pub fn parse(role: &str) -> Option<Role> {
+        match role {
+            "viewer" => Some(Role::Viewer),
+            "moderator" => Some(Role::Moderator),
+            "admin" => Some(Role::Admin),
+            "owner" => Some(Role::Owner),
+            _ => None,
+        }
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        *self
+            >= match permission {
+                Permission::View => Role::Viewer,
+                Permission::ModerateScores => Role::Moderator,
+                Permission::ManageLeaderboards | Permission::ManageMembers => Role::Admin,
+                Permission::ManageOrganization => Role::Owner,
+            }
+    }
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct OrganizationInput {
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub personal: bool,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct OrganizationWithRole {
+    pub id: Uuid,
+    pub name: String,
+    pub personal: bool,
+    pub role: Role,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct Member {
+    pub admin: Uuid,
+    pub role: Role,
+    pub github: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct RoleInput {
+    pub role: Role,
+}
+
+/// An invitation to join an organization, either for a GitHub login or for an email address.
+///
+/// Invitations by GitHub login can only be accepted by the admin with that login. Jornet doesn't
+/// send emails, the inviter shares the invitation id with the email address, and anyone holding
+/// it can accept it.
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct InvitationInput {
+    pub role: Role,
+    pub github_login: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct Invitation {
+    pub id: Uuid,
+    pub organization: Uuid,
+    pub role: Role,
+    pub github_login: Option<String>,
+    pub email: Option<String>,
+    pub invited_by: Uuid,
+}
+
+/// Create an organization
+#[utoipa::path(
+    post,
+    path = "/api/v1/organizations",
+    tag = "organizations",
+    security(("admin" = [])),
+    request_body = OrganizationInput,
+    responses(
+        (status = 200, description = "The new organization, owned by the current admin", body = Organization),
+    )
+)]
+async fn new_organization(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    organization: web::Json<OrganizationInput>,
+) -> impl Responder {
+    let organization = Organization {
+        id: Uuid::new_v4(),
+        name: organization.name.clone(),
+        personal: false,
+    };
+    if storage.create_organization(&organization, account.id).await {
+        storage
+            .record_audit(
+                account.id,
+                Some(organization.id),
+                AuditAction::OrganizationCreate,
+                Some(organization.id),
+                json!({ "name": organization.name }),
+            )
+            .await;
+        HttpResponse::Ok().json(organization)
+    } else {
+        HttpResponse::InternalServerError().finish()
+    }
+}
+
+/// List the organizations of the current admin
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations",
+    tag = "organizations",
+    security(("admin" = [])),
+    responses(
+        (status = 200, description = "The organizations, with the role of the admin", body = Vec<OrganizationWithRole>),
+    )
+)]
+async fn get_organizations(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+) -> impl Responder {
+    HttpResponse::Ok().json(storage.get_organizations(account.id).await)
+}
+
+/// Delete an organization and its leaderboards
+#[utoipa::path(
+    delete,
+    path = "/api/v1/organizations/{organization_id}",
+    tag = "organizations",
+    security(("admin" = [])),
+    params(("organization_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "Whether the organization was deleted", body = bool),
+        (status = 401, description = "Not allowed on this organization"),
+        (status = 404, description = "Unknown organization"),
+        (status = 409, description = "Personal organizations and organizations with leaderboards can't be deleted"),
+    )
+)]
+async fn delete_organization(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    organization: web::Path<Uuid>,
+) -> impl Responder {
+    if !Organization::is_allowed(
+        storage.get_ref(),
+        *organization,
+        account.id,
+        Permission::ManageOrganization,
+    )
+    .await
+    {
+        return HttpResponse::Unauthorized().finish();
+    }
+    match storage.get_organization(*organization).await {
+        None => HttpResponse::NotFound().finish(),
+        Some(organization) if organization.personal => HttpResponse::Conflict().finish(),
+        Some(organization) => {
+            if storage
+                .count_leaderboards_in_organization(organization.id)
+                .await
+                > 0
+            {
+                HttpResponse::Conflict().finish()
+            } else {
+                let deleted = storage.delete_organization(organization.id).await;
+                if deleted {
+                    storage
+                        .record_audit(
+                            account.id,
+                            Some(organization.id),
+                            AuditAction::OrganizationDelete,
+                            Some(organization.id),
+                            json!({ "name": organization.name }),
+                        )
+                        .await;
+                }
+                HttpResponse::Ok().json(deleted)
+            }
+        }
+    }
+}
+
+/// List the members of an organization
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations/{organization_id}/members",
+    tag = "organizations",
+    security(("admin" = [])),
+    params(("organization_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The members", body = Vec<Member>),
+        (status = 401, description = "Not allowed on this organization"),
+    )
+)]
+async fn get_members(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    organization: web::Path<Uuid>,
+) -> impl Responder {
+    if Organization::is_allowed(
+        storage.get_ref(),
+        *organization,
+        account.id,
+        Permission::View,
+    )
+    .await
+    {
+        HttpResponse::Ok().json(storage.get_members(*organization).await)
+    } else {
+        HttpResponse::Unauthorized().finish()
+    }
+}
+
+/// Change the role of a member
+#[utoipa::path(
+    put,
+    path = "/api/v1/organizations/{organization_id}/members/{admin_id}",
+    tag = "organizations",
+    security(("admin" = [])),
+    params(("organization_id" = Uuid, Path), ("admin_id" = Uuid, Path)),
+    request_body = RoleInput,
+    responses(
+        (status = 200, description = "Whether the role was changed", body = bool),
+        (status = 401, description = "Not allowed on this organization"),
+        (status = 404, description = "Not a member"),
+        (status = 409, description = "The last owner can't be demoted"),
+    )
+)]
+async fn set_member_role(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    path: web::Path<(Uuid, Uuid)>,
+    role: web::Json<RoleInput>,
+) -> impl Responder {
+    let (organization, admin) = path.into_inner();
+    let Some(own_role) = storage.get_role(organization, account.id).await else {
+        return HttpResponse::Unauthorized().finish();
+    };
+    if !own_role.can(Permission::ManageMembers) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    let Some(current) = storage.get_role(organization, admin).await else {
+        return HttpResponse::NotFound().finish();
+    };
+    // only owners can make or unmake other owners
+    if (current == Role::Owner || role.role == Role::Owner)
+        && !own_role.can(Permission::ManageOrganization)
+    {
+        return HttpResponse::Unauthorized().finish();
+    }
+    if current == Role::Owner
+        && role.role != Role::Owner
+        && storage.count_owners(organization).await <= 1
+    {
+        return HttpResponse::Conflict().finish();
+    }
+
+    let updated = storage.set_role(organization, admin, role.role).await;
+    if updated {
+        storage
+            .record_audit(
+                account.id,
+                Some(organization),
+                AuditAction::MemberRoleChange,
+                Some(admin),
+                json!({ "from": current, "to": role.role }),
+            )
+            .await;
+    }
+    HttpResponse::Ok().json(updated)
+}
+
+/// Remove a member, or leave the organization
+#[utoipa::path(
+    delete,
+    path = "/api/v1/organizations/{organization_id}/members/{admin_id}",
+    tag = "organizations",
+    security(("admin" = [])),
+    params(("organization_id" = Uuid, Path), ("admin_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "Whether the member was removed", body = bool),
+        (status = 401, description = "Not allowed on this organization"),
+        (status = 404, description = "Not a member"),
+        (status = 409, description = "The last owner can't be removed"),
+    )
+)]
+async fn remove_member(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (organization, admin) = path.into_inner();
+    let Some(own_role) = storage.get_role(organization, account.id).await else {
+        return HttpResponse::Unauthorized().finish();
+    };
+    let Some(current) = storage.get_role(organization, admin).await else {
+        return HttpResponse::NotFound().finish();
+    };
+    // members can always leave, removing someone else needs the permission
+    if admin != account.id
+        && (!own_role.can(Permission::ManageMembers)
+            || (current == Role::Owner && !own_role.can(Permission::ManageOrganization)))
+    {
+        return HttpResponse::Unauthorized().finish();
+    }
+    if current == Role::Owner && storage.count_owners(organization).await <= 1 {
+        return HttpResponse::Conflict().finish();
+    }
+
+    let removed = storage.remove_member(organization, admin).await;
+    if removed {
+        storage
+            .record_audit(
+                account.id,
+                Some(organization),
+                AuditAction::MemberRemove,
+                Some(admin),
+                json!({ "role": current }),
+            )
+            .await;
+    }
+    HttpResponse::Ok().json(removed)
+}
+
+/// Invite an admin to an organization
+#[utoipa::path(
+    post,
+    path = "/api/v1/organizations/{organization_id}/invitations",
+    tag = "organizations",
+    security(("admin" = [])),
+    params(("organization_id" = Uuid, Path)),
+    request_body = InvitationInput,
+    responses(
+        (status = 200, description = "The invitation", body = Invitation),
+        (status = 401, description = "Not allowed on this organization"),
+        (status = 400, description = "Neither or both of `github_login` and `email` are set"),
+    )
+)]
+async fn invite(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    organization: web::Path<Uuid>,
+    invitation: web::Json<InvitationInput>,
+) -> impl Responder {
+    let Some(own_role) = storage.get_role(*organization, account.id).await else {
+        return HttpResponse::Unauthorized().finish();
+    };
+    if !own_role.can(Permission::ManageMembers)
+        || (invitation.role == Role::Owner && !own_role.can(Permission::ManageOrganization))
+    {
+        return HttpResponse::Unauthorized().finish();
+    }
+    if invitation.github_login.is_some() == invitation.email.is_some() {
+        return HttpResponse::BadRequest().finish();
+    }
+
+    let invitation = Invitation {
+        id: Uuid::new_v4(),
+        organization: *organization,
+        role: invitation.role,
+        github_login: invitation.github_login.clone(),
+        email: invitation.email.clone(),
+        invited_by: account.id,
+    };
+    if storage.create_invitation(&invitation).await {
+        storage
+            .record_audit(
+                account.id,
+                Some(invitation.organization),
+                AuditAction::InvitationCreate,
+                Some(invitation.id),
+                json!({
+                    "role": invitation.role,
+                    "github_login": invitation.github_login,
+                    "email": invitation.email,
+                }),
+            )
+            .await;
+        HttpResponse::Ok().json(invitation)
+    } else {
+        HttpResponse::InternalServerError().finish()
+    }
+}
+
+/// List the invitations of an organization
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations/{organization_id}/invitations",
+    tag = "organizations",
+    security(("admin" = [])),
+    params(("organization_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The pending invitations", body = Vec<Invitation>),
+        (status = 401, description = "Not allowed on this organization"),
+    )
+)]
+async fn get_invitations(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    organization: web::Path<Uuid>,
+) -> impl Responder {
+    if Organization::is_allowed(
+        storage.get_ref(),
+        *organization,
+        account.id,
+        Permission::ManageMembers,
+    )
+    .await
+    {
+        HttpResponse::Ok().json(
+            storage
+                .get_invitations_for_organization(*organization)
+                .await,
+        )
+    } else {
+        HttpResponse::Unauthorized().finish()
+    }
+}
+
+/// Cancel an invitation
+#[utoipa::path(
+    delete,
+    path = "/api/v1/organizations/{organization_id}/invitations/{invitation_id}",
+    tag = "organizations",
+    security(("admin" = [])),
+    params(("organization_id" = Uuid, Path), ("invitation_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "Whether the invitation was cancelled", body = bool),
+        (status = 401, description = "Not allowed on this organization"),
+    )
+)]
+async fn cancel_invitation(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (organization, invitation) = path.into_inner();
+    if Organization::is_allowed(
+        storage.get_ref(),
+        organization,
+        account.id,
+        Permission::ManageMembers,
+    )
+    .await
+    {
+        let deleted = storage.delete_invitation(organization, invitation).await;
+        if deleted {
+            storage
+                .record_audit(
+                    account.id,
+                    Some(organization),
+                    AuditAction::InvitationCancel,
+                    Some(invitation),
+                    Value::Null,
+                )
+                .await;
+        }
+        HttpResponse::Ok().json(deleted)
+    } else {
+        HttpResponse::Unauthorized().finish()
+    }
+}
+
+/// List the invitations to the GitHub login of the current admin
+#[utoipa::path(
+    get,
+    path = "/api/v1/invitations",
+    tag = "organizations",
+    security(("admin" = [])),
+    responses(
+        (status = 200, description = "The pending invitations", body = Vec<Invitation>),
+    )
+)]
+async fn get_own_invitations(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+) -> impl Responder {
+    match storage.get_admin_github(&account).await {
+        Some(github) => {
+            HttpResponse::Ok().json(storage.get_invitations_for_github(&github.login).await)
+        }
+        None => HttpResponse::Ok().json(Vec::<Invitation>::new()),
+    }
+}
+
+/// Accept an invitation
+#[utoipa::path(
+    post,
+    path = "/api/v1/invitations/{invitation_id}/accept",
+    tag = "organizations",
+    security(("admin" = [])),
+    params(("invitation_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The organization joined", body = Organization),
+        (status = 401, description = "The invitation is for another GitHub login"),
+        (status = 404, description = "Unknown invitation"),
+    )
+)]
+async fn accept_invitation(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    invitation: web::Path<Uuid>,
+) -> impl Responder {
+    let Some(invitation) = storage.get_invitation(*invitation).await else {
+        return HttpResponse::NotFound().finish();
+    };
+    if let Some(login) = invitation.github_login.as_ref() {
+        match storage.get_admin_github(&account).await {
+            Some(github) if github.login.eq_ignore_ascii_case(login) => (),
+            _ => return HttpResponse::Unauthorized().finish(),
+        }
+    }
+
+    if storage.accept_invitation(&invitation, account.id).await {
+        storage
+            .record_audit(
+                account.id,
+                Some(invitation.organization),
+                AuditAction::InvitationAccept,
+                Some(invitation.id),
+                json!({ "role": invitation.role }),
+            )
+            .await;
+        HttpResponse::Ok().json(storage.get_organization(invitation.organization).await)
+    } else {
+        HttpResponse::InternalServerError().finish()
+    }
+}
+
+pub(crate) fn organization(kp: web::Data<KeyPair>) -> impl HttpServiceFactory {
+    web::scope("api/v1/organizations")
+        .app_data(kp)
+        .wrap(HttpAuthentication::bearer(validator))
+        .route("", web::post().to(new_organization))
+        .route("", web::get().to(get_organizations))
+        .route("{organization_id}", web::delete().to(delete_organization))
+        .route("{organization_id}/members", web::get().to(get_members))
+        .route(
+            "{organization_id}/members/{admin_id}",
+            web::put().to(set_member_role),
+        )
+        .route(
+            "{organization_id}/members/{admin_id}",
+            web::delete().to(remove_member),
+        )
+        .route("{organization_id}/invitations", web::post().to(invite))
+        .route(
+            "{organization_id}/invitations",
+            web::get().to(get_invitations),
+        )
+        .route(
+            "{organization_id}/invitations/{invitation_id}",
+            web::delete().to(cancel_invitation),
+        )
+        .route("{organization_id}/audit", web::get().to(get_audit_log))
+}
+
+pub(crate) fn invitation(kp: web::Data<KeyPair>) -> impl HttpServiceFactory {
+    web::scope("api/v1/invitations")
+        .app_data(kp)
+        .wrap(HttpAuthentication::bearer(validator))
+        .route("", web::get().to(get_own_invitations))
+        .route("{invitation_id}/accept", web::post().to(accept_invitation))
+}
+
+impl Organization {
+    /// Get the personal organization of an admin, creating it if needed. It shares its id with
+    /// the admin.
+    pub async fn personal(storage: &dyn Storage, admin: Uuid) -> Option<Uuid> {
+        if storage.get_role(admin, admin).await.is_some() {
+            return Some(admin);
+        }
+        storage
+            .create_organization(
+                &Organization {
+                    id: admin,
+                    name: "Personal".to_string(),
+                    personal: true,
+                },
+                admin,
+            )
+            .await
+            .then_some(admin)
+    }
+
+    pub async fn is_allowed(
+        storage: &dyn Storage,
+        organization: Uuid,
+        admin: Uuid,
+        permission: Permission,
+    ) -> bool {
+        storage
+            .get_role(organization, admin)
+            .await
+            .map(|role| role.can(permission))
+            .unwrap_or(false)
+    }
+}
@@ -1,81 +1,74 @@
-use actix_cors::Cors;
-use actix_web::{dev::HttpServiceFactory, web, HttpResponse, Responder};
+use actix_web::{dev::HttpServiceFactory, web, HttpRequest, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::random_name::random_name;
+use crate::{metrics::Metrics, random_name::random_name, rate_limit::RateLimits, storage::Storage};
 
-#[derive(Serialize, Deserialize)]
+/// A player, with the key signing their scores. The key is only returned when the player is
+/// created and must be kept by the game.
+#[derive(Serialize, Deserialize, Clone, ToSchema)]
 pub struct Player {
     pub id: Uuid,
     pub name: String,
     pub key: Uuid,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub struct PlayerInput {
+    /// A random name is picked when not set.
     pub name: Option<String>,
 }
 
+/// Create a player
+#[utoipa::path(
+    post,
+    path = "/api/v1/players",
+    tag = "players",
+    request_body = PlayerInput,
+    responses(
+        (status = 200, description = "The new player", body = Player),
+        (status = 429, description = "Too many players created from this IP"),
+    )
+)]
 async fn create_player(
-    connection: web::Data<PgPool>,
+    storage: web::Data<dyn Storage>,
+    rate_limits: web::Data<RateLimits>,
+    metrics: web::Data<Metrics>,
+    request: HttpRequest,
     player: web::Json<PlayerInput>,
 ) -> impl Responder {
-    let player = Player {
-        name: player.name.clone().unwrap_or_else(random_name),
-        id: Uuid::new_v4(),
-        key: Uuid::new_v4(),
-    };
-
-    if player.save(&connection).await {
-        HttpResponse::Ok().json(player)
-    } else {
-        HttpResponse::InternalServerError().finish()
+    if let Err(response) = rate_limits.check_ip(&rate_limits.player_creation, &request) {
+        return response;
+    }
+    match Player::create(storage.get_ref(), &metrics, player.into_inner()).await {
+        Some(player) => HttpResponse::Ok().json(player),
+        None => HttpResponse::InternalServerError().finish(),
     }
-}
-
-pub(crate) fn player() -> impl HttpServiceFactory {
-    let cors = Cors::default()
-        .allow_any_header()
-        .allow_any_origin()
-        .allow_any_method()
-        .send_wildcard()
-        .max_age(3600);
-    web::scope("api/v1/players")
-        .wrap(cors)
-        .route("", web::post().to(create_player))
 }
 
 impl Player {
-    pub async fn save(&self, connection: &PgPool) -> bool {
-        sqlx::query!(
-            r#"
-            INSERT INTO players (id, name, key) VALUES ($1, $2, $3)
-            "#,
-            self.id,
-            self.name,
-            self.key,
-        )
-        .execute(connection)
-        .await
-        .is_ok()
-    }
+    /// Create a player with a new key.
+    pub(crate) async fn create(
+        storage: &dyn Storage,
+        metrics: &Metrics,
+        input: PlayerInput,
+    ) -> Option<Player> {
+        let player = Player {
+            name: input.name.unwrap_or_else(random_name),
+            id: Uuid::new_v4(),
+            key: Uuid::new_v4(),
+        };
 
-    pub async fn get(id: Uuid, connection: &PgPool) -> Option<Player> {
-        sqlx::query!(
-            r#"
-            SELECT id, name, key FROM players WHERE id = $1
-            "#,
-            id,
-        )
-        .fetch_one(connection)
-        .await
-        .map(|r| Player {
-            id: r.id,
-            name: r.name.clone(),
-            key: r.key,
-        })
-        .ok()
+        if storage.create_player(&player).await {
+            metrics.player_created();
+            Some(player)
+        } else {
+            None
+        }
     }
 }
+
+pub(crate) fn player() -> impl HttpServiceFactory {
+    web::scope("api/v1/players").route("", web::post().to(create_player))
+}
@@ -1,171 +1,603 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::{
+    cmp::Ordering,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use actix_cors::Cors;
-use actix_web::{dev::HttpServiceFactory, web, HttpResponse, Responder};
+use actix_web::{
+    dev::HttpServiceFactory,
+    http::header::{self, CacheControl, CacheDirective, ETag, IfNoneMatch},
+    web, HttpRequest, HttpResponse, Responder,
+};
 use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
 use sha2::Sha256;
-use sqlx::PgPool;
-use time::{format_description::well_known::Rfc3339, UtcOffset};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use super::{leaderboard::Leaderboard, player::Player};
+use crate::{
+    cache::{Page, ScoreCache},
+    metrics::Metrics,
+    rate_limit::{too_many_requests, RateLimits},
+    storage::Storage,
+};
 
-#[derive(Serialize)]
-struct Score {
-    score: f32,
-    meta: Option<String>,
-    timestamp: String,
-    player: String,
+use super::{leaderboard::Leaderboard, live::live_scores, player::Player, webhook::Webhooks};
+
+/// What happened to a submitted score.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubmissionOutcome {
+    Accepted,
+    /// The signature doesn't match the keys of the player and leaderboard.
+    BadMac,
+    /// The same score was already sent.
+    Duplicate,
+    /// Unknown player or leaderboard, banned player, or storage failure.
+    Rejected,
+    /// Over the rate limit of the player.
+    Throttled,
+}
+
+impl SubmissionOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubmissionOutcome::Accepted => "accepted",
+            SubmissionOutcome::BadMac => "bad_mac",
+            SubmissionOutcome::Duplicate => "duplicate",
+            SubmissionOutcome::Rejected => "rejected",
+            SubmissionOutcome::Throttled => "throttled",
+        }
+    }
+}
+
+/// Type of the scores of a leaderboard, chosen when it's created.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreType {
+    /// 32 bits floats, the type of the leaderboards created before the others were added.
+    #[default]
+    F32,
+    I64,
+    F64,
+    /// A duration in milliseconds, as an integer of at least 0. The shortest ranks first.
+    DurationMs,
+}
+
+impl ScoreType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScoreType::F32 => "f32",
+            ScoreType::I64 => "i64",
+            ScoreType::F64 => "f64",
+            ScoreType::DurationMs => "duration_ms",
+        }
+    }
+
+    pub fn parse(score_type: &str) -> Option<ScoreType> {
+        match score_type {
+            "f32" => Some(ScoreType::F32),
+            "i64" => Some(ScoreType::I64),
+            "f64" => Some(ScoreType::F64),
+            "duration_ms" => Some(ScoreType::DurationMs),
+            _ => None,
+        }
+    }
+
+    /// Which scores rank first: durations are times to beat, the other types are points.
+    pub fn sort_direction(&self) -> SortDirection {
+        match self {
+            ScoreType::DurationMs => SortDirection::Ascending,
+            ScoreType::F32 | ScoreType::I64 | ScoreType::F64 => SortDirection::Descending,
+        }
+    }
 }
 
-#[derive(Deserialize, Serialize)]
+/// Order of the scores of a leaderboard, from the best.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortDirection {
+    /// The highest score is the best.
+    #[default]
+    Descending,
+    /// The lowest score is the best.
+    Ascending,
+}
+
+impl SortDirection {
+    /// Compare two scores, the best first.
+    pub fn compare(self, a: &ScoreValue, b: &ScoreValue) -> Ordering {
+        match self {
+            SortDirection::Descending => b.total_cmp(a),
+            SortDirection::Ascending => a.total_cmp(b),
+        }
+    }
+}
+
+/// Integers up to this value, and their opposites, are exact as `f32`.
+pub const MAX_EXACT_F32: i64 = 1 << 24;
+/// Integers up to this value, and their opposites, are exact as `f64`.
+pub const MAX_EXACT_F64: i64 = 1 << 53;
+
+/// A score, sent and returned as a JSON number.
+///
+/// Scores read from a request are an `I64` or a `F64` until converted to the type of their
+/// leaderboard with [`ScoreValue::with_type`]. Scores are compared by their value, whatever their
+/// variant.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ScoreValue {
+    I64(i64),
+    F64(f64),
+    F32(f32),
+}
+
+impl ScoreValue {
+    /// The score in the type of a leaderboard, if it can be converted without losing precision.
+    /// Floats sent to an `f32` leaderboard are rounded, as they always were.
+    pub fn with_type(self, score_type: ScoreType) -> Option<ScoreValue> {
+        let value = match (score_type, self) {
+            (ScoreType::F32, ScoreValue::F32(value)) => ScoreValue::F32(value),
+            (ScoreType::F32, ScoreValue::F64(value)) => ScoreValue::F32(value as f32),
+            (ScoreType::F32, ScoreValue::I64(value)) => {
+                if value.unsigned_abs() > MAX_EXACT_F32 as u64 {
+                    return None;
+                }
+                ScoreValue::F32(value as f32)
+            }
+            (ScoreType::F64, ScoreValue::F32(value)) => ScoreValue::F64(value as f64),
+            (ScoreType::F64, ScoreValue::F64(value)) => ScoreValue::F64(value),
+            (ScoreType::F64, ScoreValue::I64(value)) => {
+                if value.unsigned_abs() > MAX_EXACT_F64 as u64 {
+                    return None;
+                }
+                ScoreValue::F64(value as f64)
+            }
+            (ScoreType::I64 | ScoreType::DurationMs, ScoreValue::I64(value)) => {
+                ScoreValue::I64(value)
+            }
+            (ScoreType::I64 | ScoreType::DurationMs, float) => {
+                let value = float.as_f64();
+                if value.fract() != 0.0 || value.abs() > MAX_EXACT_F64 as f64 {
+                    return None;
+                }
+                ScoreValue::I64(value as i64)
+            }
+        };
+        let valid = match (score_type, value) {
+            (ScoreType::DurationMs, ScoreValue::I64(value)) => value >= 0,
+            (_, value) => value.as_f64().is_finite(),
+        };
+        valid.then_some(value)
+    }
+
+    /// The score as stored: a float, and for integers their exact value.
+    pub fn as_f64(self) -> f64 {
+        match self {
+            ScoreValue::I64(value) => value as f64,
+            ScoreValue::F64(value) => value,
+            ScoreValue::F32(value) => value as f64,
+        }
+    }
+
+    pub fn exact(self) -> Option<i64> {
+        match self {
+            ScoreValue::I64(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// A score read from the storage, in the type of its leaderboard.
+    pub fn from_stored(score_type: ScoreType, score: f64, exact: Option<i64>) -> ScoreValue {
+        match (score_type, exact) {
+            (ScoreType::I64 | ScoreType::DurationMs, Some(exact)) => ScoreValue::I64(exact),
+            (ScoreType::I64 | ScoreType::DurationMs, None) => ScoreValue::I64(score as i64),
+            (ScoreType::F64, _) => ScoreValue::F64(score),
+            (ScoreType::F32, _) => ScoreValue::F32(score as f32),
+        }
+    }
+
+    /// The closest integer.
+    pub fn round(self) -> i64 {
+        match self {
+            ScoreValue::I64(value) => value,
+            float => float.as_f64().round() as i64,
+        }
+    }
+
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (ScoreValue::I64(a), ScoreValue::I64(b)) => a.cmp(b),
+            (a, b) => a.as_f64().total_cmp(&b.as_f64()),
+        }
+    }
+
+    /// The score in a signature: in 4 bytes in little endian for `f32`, and for the other types
+    /// in 8 bytes in little endian followed by the name of the type in UTF-8.
+    fn signed_bytes(&self, score_type: ScoreType) -> Vec<u8> {
+        let mut bytes = match self {
+            ScoreValue::I64(value) => value.to_le_bytes().to_vec(),
+            ScoreValue::F64(value) => value.to_le_bytes().to_vec(),
+            ScoreValue::F32(value) => value.to_le_bytes().to_vec(),
+        };
+        if score_type != ScoreType::F32 {
+            bytes.extend_from_slice(score_type.as_str().as_bytes());
+        }
+        bytes
+    }
+}
+
+impl PartialEq for ScoreValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.total_cmp(other) == Ordering::Equal
+    }
+}
+
+impl PartialOrd for ScoreValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.total_cmp(other))
+    }
+}
+
+impl From<f32> for ScoreValue {
+    fn from(value: f32) -> Self {
+        ScoreValue::F32(value)
+    }
+}
+
+impl From<f64> for ScoreValue {
+    fn from(value: f64) -> Self {
+        ScoreValue::F64(value)
+    }
+}
+
+impl From<i64> for ScoreValue {
+    fn from(value: i64) -> Self {
+        ScoreValue::I64(value)
+    }
+}
+
+#[derive(Serialize, PartialEq, ToSchema)]
+pub struct Score {
+    #[schema(value_type = f64)]
+    pub score: ScoreValue,
+    pub meta: Option<String>,
+    pub timestamp: String,
+    pub player: String,
+}
+
+/// A score as seen by the admins of its leaderboard, with the ids needed to moderate it.
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct ModeratedScore {
+    pub id: Uuid,
+    #[schema(value_type = f64)]
+    pub score: ScoreValue,
+    pub meta: Option<String>,
+    pub timestamp: String,
+    pub player: Uuid,
+    pub player_name: String,
+}
+
+impl From<ModeratedScore> for Score {
+    fn from(score: ModeratedScore) -> Self {
+        Score {
+            score: score.score,
+            meta: score.meta,
+            timestamp: score.timestamp,
+            player: score.player_name,
+        }
+    }
+}
+
+/// A score sent by a game, signed with the key of its player.
+///
+/// `k` is the hex encoded HMAC-SHA256, keyed with the 16 bytes of the player key, of the
+/// concatenation of:
+/// 1. `timestamp`, as 8 bytes in little endian,
+/// 2. the 16 bytes of the leaderboard key,
+/// 3. the 16 bytes of the player id,
+/// 4. `score`, in the type of the leaderboard: for `f32`, as a 32 bits float in 4 bytes in little
+///    endian. For the other types, as a 64 bits integer or float in 8 bytes in little endian,
+///    followed by the name of the type (`i64`, `f64` or `duration_ms`) in UTF-8,
+/// 5. `meta` in UTF-8, when set.
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
 pub struct ScoreInput {
-    pub score: f32,
+    #[schema(value_type = f64)]
+    pub score: ScoreValue,
     pub player: Uuid,
     pub meta: Option<String>,
+    /// Unix timestamp, in seconds, of when the score was signed.
     pub timestamp: u64,
+    /// Signature of the score.
     pub k: String,
 }
 
 impl ScoreInput {
-    pub fn verify_mac(&self, key: Uuid, leaderboard_key: Uuid) -> bool {
+    fn mac(&self, key: Uuid, leaderboard_key: Uuid, score_type: ScoreType) -> Hmac<Sha256> {
         let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).unwrap();
         mac.update(&self.timestamp.to_le_bytes());
         mac.update(leaderboard_key.as_bytes());
         mac.update(self.player.as_bytes());
-        mac.update(&self.score.to_le_bytes());
+        mac.update(&self.score.signed_bytes(score_type));
         if let Some(meta) = self.meta.as_ref() {
             mac.update(meta.as_bytes());
         }
-        mac.verify_slice(hex::decode(&self.k).unwrap().as_slice())
-            .is_ok()
+        mac
     }
 
+    /// Check the signature of a score already converted to the type of its leaderboard.
+    pub fn verify_mac(&self, key: Uuid, leaderboard_key: Uuid, score_type: ScoreType) -> bool {
+        let mac = self.mac(key, leaderboard_key, score_type);
+        hex::decode(&self.k).is_ok_and(|k| mac.verify_slice(&k).is_ok())
+    }
+
+    /// A signed score for an `f32` leaderboard.
     pub fn new(score: f32, player: Player, meta: Option<String>, leaderboard_key: Uuid) -> Self {
+        Self::with_type(score.into(), ScoreType::F32, player, meta, leaderboard_key)
+    }
+
+    /// A signed score for a leaderboard of any type. `score` must already be in that type.
+    pub fn with_type(
+        score: ScoreValue,
+        score_type: ScoreType,
+        player: Player,
+        meta: Option<String>,
+        leaderboard_key: Uuid,
+    ) -> Self {
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .expect("Time went backwards")
             .as_secs();
 
-        let mut mac = Hmac::<Sha256>::new_from_slice(player.key.as_bytes()).unwrap();
-        mac.update(&timestamp.to_le_bytes());
-        mac.update(leaderboard_key.as_bytes());
-        mac.update(player.id.as_bytes());
-        mac.update(&score.to_le_bytes());
-        if let Some(meta) = meta.as_ref() {
-            mac.update(meta.as_bytes());
-        }
-
-        let hmac = hex::encode(&mac.finalize().into_bytes()[..]);
-        Self {
+        let mut input = Self {
             score,
             player: player.id,
             meta,
             timestamp,
-            k: hmac,
+            k: String::new(),
+        };
+        let mac = input.mac(player.key, leaderboard_key, score_type);
+        input.k = hex::encode(&mac.finalize().into_bytes()[..]);
+        input
+    }
+}
+
+/// Why a submitted score was not saved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubmissionError {
+    UnknownPlayer,
+    UnknownLeaderboard,
+    /// The score can't be converted exactly to the type of the leaderboard.
+    InvalidScore,
+    /// The signature doesn't match the keys of the player and leaderboard.
+    BadMac,
+    /// The player is banned from the leaderboard.
+    Banned,
+    /// The same score was already sent.
+    Duplicate,
+    /// Over the rate limit of the player, to retry after the duration.
+    Throttled(Duration),
+    /// The storage failed to save the score.
+    Failed,
+}
+
+impl SubmissionError {
+    pub fn outcome(&self) -> SubmissionOutcome {
+        match self {
+            SubmissionError::BadMac => SubmissionOutcome::BadMac,
+            SubmissionError::Duplicate => SubmissionOutcome::Duplicate,
+            SubmissionError::Throttled(_) => SubmissionOutcome::Throttled,
+            SubmissionError::UnknownPlayer
+            | SubmissionError::UnknownLeaderboard
+            | SubmissionError::InvalidScore
+            | SubmissionError::Banned
+            | SubmissionError::Failed => SubmissionOutcome::Rejected,
+        }
+    }
+}
+
+/// A score sent by a game, in the format of one version of the API. Versions only differ in how
+/// scores are signed, they are all saved as a [`ScoreInput`].
+pub(crate) trait SignedScore {
+    fn to_input(&self) -> ScoreInput;
+    /// Check the signature, with `score` already converted to the type of the leaderboard.
+    fn verify_mac(&self, score: &ScoreInput, key: Uuid, leaderboard: &Leaderboard) -> bool;
+}
+
+impl SignedScore for ScoreInput {
+    fn to_input(&self) -> ScoreInput {
+        self.clone()
+    }
+
+    fn verify_mac(&self, score: &ScoreInput, key: Uuid, leaderboard: &Leaderboard) -> bool {
+        score.verify_mac(key, leaderboard.key, leaderboard.score_type)
+    }
+}
+
+/// Services used when a score is submitted.
+pub(crate) struct Submission<'a> {
+    pub storage: &'a dyn Storage,
+    pub cache: &'a ScoreCache,
+    pub webhooks: &'a Webhooks,
+    pub rate_limits: &'a RateLimits,
+    pub metrics: &'a Metrics,
+}
+
+impl Submission<'_> {
+    /// Check and save a score sent by a game, then notify the cache and webhooks.
+    pub(crate) async fn submit(
+        &self,
+        leaderboard: Uuid,
+        signed: &impl SignedScore,
+    ) -> Result<(), SubmissionError> {
+        let result = self.save(leaderboard, signed).await;
+        self.metrics.score_submitted(match &result {
+            Ok(()) => SubmissionOutcome::Accepted,
+            Err(error) => error.outcome(),
+        });
+        result
+    }
+
+    async fn save(
+        &self,
+        leaderboard: Uuid,
+        signed: &impl SignedScore,
+    ) -> Result<(), SubmissionError> {
+        let input = signed.to_input();
+        let player = self
+            .storage
+            .get_player(input.player)
+            .await
+            .ok_or(SubmissionError::UnknownPlayer)?;
+        let details = self
+            .storage
+            .get_leaderboard(leaderboard)
+            .await
+            .ok_or(SubmissionError::UnknownLeaderboard)?;
+        let score = ScoreInput {
+            score: input
+                .score
+                .with_type(details.score_type)
+                .ok_or(SubmissionError::InvalidScore)?,
+            ..input
+        };
+        if !signed.verify_mac(&score, player.key, &details) {
+            return Err(SubmissionError::BadMac);
         }
+        // only signed submissions count, so that nobody can throttle another player
+        self.rate_limits
+            .score_submission
+            .check((leaderboard, player.id))
+            .map_err(SubmissionError::Throttled)?;
+
+        let previous_best = self
+            .webhooks
+            .best_score_if_watched(self.storage, &details)
+            .await;
+        Score::save(&score, self.storage, leaderboard).await?;
+        self.cache.invalidate(leaderboard);
+        self.webhooks
+            .score_saved(self.storage, &details, &player, &score, previous_best)
+            .await;
+        Ok(())
     }
 }
 
+/// Submit a score
+#[utoipa::path(
+    post,
+    path = "/api/v1/scores/{leaderboard_id}",
+    tag = "scores",
+    params(("leaderboard_id" = Uuid, Path)),
+    request_body = ScoreInput,
+    responses(
+        (status = 200, description = "The score was saved"),
+        (status = 429, description = "Too many scores sent by this player, retry after the \
+            `Retry-After` header"),
+        (status = 500, description = "Unknown player or leaderboard, invalid signature, banned \
+            player or duplicate score"),
+    )
+)]
 async fn save_score(
-    connection: web::Data<PgPool>,
+    storage: web::Data<dyn Storage>,
+    cache: web::Data<ScoreCache>,
+    webhooks: web::Data<Webhooks>,
+    rate_limits: web::Data<RateLimits>,
+    metrics: web::Data<Metrics>,
     score: web::Json<ScoreInput>,
     leaderboard: web::Path<Uuid>,
 ) -> impl Responder {
-    if let Some(player) = Player::get(score.player, &connection).await {
-        if let Some(leaderboard_key) = Leaderboard::get_key(&connection, *leaderboard).await {
-            if score.verify_mac(player.key, leaderboard_key)
-                && Score::save(&score, &connection, &leaderboard).await
-            {
-                HttpResponse::Ok().json(())
-            } else {
-                HttpResponse::InternalServerError().finish()
-            }
-        } else {
-            HttpResponse::InternalServerError().finish()
-        }
-    } else {
-        HttpResponse::InternalServerError().finish()
+    let submission = Submission {
+        storage: storage.get_ref(),
+        cache: &cache,
+        webhooks: &webhooks,
+        rate_limits: &rate_limits,
+        metrics: &metrics,
+    };
+    // games already shipped only check for a success, and expect `null`
+    match submission.submit(*leaderboard, &*score).await {
+        Ok(()) => HttpResponse::Ok().json(()),
+        Err(SubmissionError::Throttled(retry_after)) => too_many_requests(retry_after),
+        Err(_) => HttpResponse::InternalServerError().finish(),
     }
 }
 
-async fn get_scores(connection: web::Data<PgPool>, leaderboard: web::Path<Uuid>) -> impl Responder {
-    HttpResponse::Ok().json(Score::get_all(&connection, &leaderboard).await)
+/// Get the scores of a leaderboard
+///
+/// Scores of a leaderboard. They are returned with an `ETag`, and a request with a matching
+/// `If-None-Match` gets an empty `304 Not Modified` instead.
+#[utoipa::path(
+    get,
+    path = "/api/v1/scores/{leaderboard_id}",
+    tag = "scores",
+    params(("leaderboard_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "Scores of the leaderboard", body = Vec<Score>),
+        (status = 304, description = "Not modified since the `If-None-Match` header"),
+        (status = 429, description = "Too many reads from this IP"),
+    )
+)]
+async fn get_scores(
+    storage: web::Data<dyn Storage>,
+    cache: web::Data<ScoreCache>,
+    rate_limits: web::Data<RateLimits>,
+    request: HttpRequest,
+    leaderboard: web::Path<Uuid>,
+    if_none_match: Option<web::Header<IfNoneMatch>>,
+) -> impl Responder {
+    if let Err(response) = rate_limits.check_ip(&rate_limits.reads, &request) {
+        return response;
+    }
+    let scores = cache.get(storage.get_ref(), *leaderboard, Page::All).await;
+    // clients must check for changes every time
+    let cache_control = CacheControl(vec![CacheDirective::NoCache]);
+
+    let not_modified = match if_none_match.map(web::Header::into_inner) {
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(etags)) => etags.iter().any(|etag| etag.weak_eq(&scores.etag)),
+        None => false,
+    };
+    if not_modified {
+        HttpResponse::NotModified()
+            .insert_header(ETag(scores.etag.clone()))
+            .insert_header(cache_control)
+            .finish()
+    } else {
+        HttpResponse::Ok()
+            .insert_header(ETag(scores.etag.clone()))
+            .insert_header(cache_control)
+            .content_type(header::ContentType::json())
+            .body(scores.body.clone())
+    }
 }
 
 pub(crate) fn score() -> impl HttpServiceFactory {
-    let cors = Cors::default()
-        .allow_any_header()
-        .allow_any_origin()
-        .allow_any_method()
-        .send_wildcard()
-        .max_age(3600);
     web::scope("api/v1/scores")
-        .wrap(cors)
         .route("{leaderboard_id}", web::post().to(save_score))
         .route("{leaderboard_id}", web::get().to(get_scores))
+        .route("{leaderboard_id}/live", web::get().to(live_scores))
 }
 
 impl Score {
-    pub async fn get_all(connection: &PgPool, leaderboard: &Uuid) -> Vec<Score> {
-        sqlx::query!(
-            "SELECT score, meta, timestamp, players.name FROM scores, players WHERE leaderboard = $1 and scores.player = players.id",
-            leaderboard
-        )
-        .fetch_all(connection)
-        .await
-        .unwrap()
-        .iter()
-        .map(|r| Score {
-            score: r.score,
-            meta: r.meta.clone(),
-            player: r.name.clone(),
-            timestamp: r
-                .timestamp
-                .assume_offset(UtcOffset::UTC)
-                .format(&Rfc3339)
-                .unwrap(),
-        })
-        .collect()
-    }
-
-    pub async fn save(score: &ScoreInput, connection: &PgPool, leaderboard: &Uuid) -> bool {
-        if sqlx::query!("SELECT id FROM leaderboards WHERE id = $1", leaderboard)
-            .fetch_one(connection)
-            .await
-            .is_err()
-        {
-            return false;
-        }
-
-        if sqlx::query!(
-            "SELECT id FROM scores WHERE leaderboard = $1 AND player = $2 AND score = $3 AND timestamp = TO_TIMESTAMP($4)",
-            leaderboard,
-            score.player,
-            score.score,
-            score.timestamp as f64
-        )
-            .fetch_one(connection)
-            .await
-            .is_ok()
-        {
-            return false;
-        }
-
-        sqlx::query!(
-                r#"
-            INSERT INTO scores (id, leaderboard, score, player, meta, timestamp) VALUES ($1, $2, $3, $4, $5, TO_TIMESTAMP($6))
-            "#,
-                Uuid::new_v4(),
-                leaderboard,
-                score.score,
-                score.player,
-                score.meta,
-                score.timestamp as f64
-            )
-            .execute(connection)
-            .await
-        .is_ok()
+    /// Save a score, unless its player is banned from the leaderboard or it was already sent.
+    pub async fn save(
+        score: &ScoreInput,
+        storage: &dyn Storage,
+        leaderboard: Uuid,
+    ) -> Result<(), SubmissionError> {
+        if storage.get_leaderboard(leaderboard).await.is_none() {
+            return Err(SubmissionError::UnknownLeaderboard);
+        }
+
+        if storage.is_banned(leaderboard, score.player).await {
+            return Err(SubmissionError::Banned);
+        }
+
+        if storage.score_exists(leaderboard, score).await {
+            return Err(SubmissionError::Duplicate);
+        }
+
+        if storage.insert_score(leaderboard, score).await {
+            Ok(())
+        } else {
+            Err(SubmissionError::Failed)
+        }
     }
 }
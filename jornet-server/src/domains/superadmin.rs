@@ -0,0 +1,134 @@
+use actix_web::{dev::HttpServiceFactory, web, HttpResponse, Responder};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use biscuit_auth::KeyPair;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{auth_admin::validator, configuration::Settings, storage::Storage};
+
+use super::{admin::AdminAccount, audit::AuditAction};
+
+/// An admin account, as seen by the operators of the server.
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct AdminSummary {
+    pub id: Uuid,
+    pub github: Option<String>,
+    pub disabled: bool,
+}
+
+/// List the admins of the server
+#[utoipa::path(
+    get,
+    path = "/api/v1/superadmin/admins",
+    tag = "superadmin",
+    security(("admin" = [])),
+    responses(
+        (status = 200, description = "All the admins", body = Vec<AdminSummary>),
+        (status = 401, description = "Not a superadmin"),
+    )
+)]
+async fn get_admins(
+    account: web::ReqData<AdminAccount>,
+    config: web::Data<Settings>,
+    storage: web::Data<dyn Storage>,
+) -> impl Responder {
+    if !config.superadmins.contains(&account.id) {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    HttpResponse::Ok().json(storage.get_admins().await)
+}
+
+/// Disable an admin
+#[utoipa::path(
+    post,
+    path = "/api/v1/superadmin/admins/{admin_id}/disable",
+    tag = "superadmin",
+    security(("admin" = [])),
+    params(("admin_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The admin was disabled"),
+        (status = 401, description = "Not a superadmin"),
+        (status = 404, description = "Unknown admin"),
+        (status = 409, description = "The admin is a superadmin"),
+    )
+)]
+async fn disable_admin(
+    account: web::ReqData<AdminAccount>,
+    config: web::Data<Settings>,
+    storage: web::Data<dyn Storage>,
+    admin_id: web::Path<Uuid>,
+) -> impl Responder {
+    set_disabled(&account, &config, storage.get_ref(), *admin_id, true).await
+}
+
+/// Enable an admin
+#[utoipa::path(
+    post,
+    path = "/api/v1/superadmin/admins/{admin_id}/enable",
+    tag = "superadmin",
+    security(("admin" = [])),
+    params(("admin_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The admin was enabled"),
+        (status = 401, description = "Not a superadmin"),
+        (status = 404, description = "Unknown admin"),
+        (status = 409, description = "The admin is a superadmin"),
+    )
+)]
+async fn enable_admin(
+    account: web::ReqData<AdminAccount>,
+    config: web::Data<Settings>,
+    storage: web::Data<dyn Storage>,
+    admin_id: web::Path<Uuid>,
+) -> impl Responder {
+    set_disabled(&account, &config, storage.get_ref(), *admin_id, false).await
+}
+
+async fn set_disabled(
+    account: &AdminAccount,
+    config: &Settings,
+    storage: &dyn Storage,
+    admin_id: Uuid,
+    disabled: bool,
+) -> HttpResponse {
+    if !config.superadmins.contains(&account.id) {
+        return HttpResponse::Unauthorized().finish();
+    }
+    // superadmins come from the configuration and can't be locked out from here
+    if config.superadmins.contains(&admin_id) {
+        return HttpResponse::Conflict().finish();
+    }
+
+    if !storage
+        .set_admin_disabled(&AdminAccount { id: admin_id }, disabled)
+        .await
+    {
+        return HttpResponse::NotFound().finish();
+    }
+    storage
+        .record_audit(
+            account.id,
+            None,
+            if disabled {
+                AuditAction::AdminDisable
+            } else {
+                AuditAction::AdminEnable
+            },
+            Some(admin_id),
+            Value::Null,
+        )
+        .await;
+    HttpResponse::Ok().finish()
+}
+
+pub(crate) fn superadmin(kp: web::Data<KeyPair>) -> impl HttpServiceFactory {
+    web::scope("api/v1/superadmin")
+        .app_data(kp)
+        .wrap(HttpAuthentication::bearer(validator))
+        .route("admins", web::get().to(get_admins))
+        .route("admins/{admin_id}/disable", web::post().to(disable_admin))
+        .route("admins/{admin_id}/enable", web::post().to(enable_admin))
+}
@@ -0,0 +1,312 @@
+use actix_web::{dev::HttpServiceFactory, web, HttpResponse, Responder};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use biscuit_auth::KeyPair;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{auth_admin::validator, storage::Storage};
+
+use super::{
+    admin::AdminAccount,
+    audit::AuditAction,
+    leaderboard::Leaderboard,
+    organization::{Organization, Permission},
+};
+
+/// State of a leaderboard transfer. Only pending transfers can be accepted, declined or
+/// cancelled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Cancelled,
+}
+
+impl TransferStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferStatus::Pending => "pending",
+            TransferStatus::Accepted => "accepted",
+            TransferStatus::Declined => "declined",
+            TransferStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(status: &str) -> Option<TransferStatus> {
+        match status {
+            "pending" => Some(TransferStatus::Pending),
+            "accepted" => Some(TransferStatus::Accepted),
+            "declined" => Some(TransferStatus::Declined),
+            "cancelled" => Some(TransferStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct TransferInput {
+    pub to: Uuid,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct Transfer {
+    pub id: Uuid,
+    pub leaderboard: Uuid,
+    pub from_organization: Uuid,
+    pub to_admin: Uuid,
+    pub requested_by: Uuid,
+    pub status: TransferStatus,
+}
+
+/// Offer a leaderboard to another admin
+#[utoipa::path(
+    post,
+    path = "/api/v1/leaderboards/{leaderboard_id}/transfer",
+    tag = "transfers",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path)),
+    request_body = TransferInput,
+    responses(
+        (status = 200, description = "The pending transfer", body = Transfer),
+        (status = 401, description = "Not allowed on this leaderboard"),
+        (status = 400, description = "Invalid recipient"),
+        (status = 409, description = "A transfer is already pending"),
+    )
+)]
+pub(crate) async fn request_transfer(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    leaderboard: web::Path<Uuid>,
+    transfer: web::Json<TransferInput>,
+) -> impl Responder {
+    let Some(organization) = Leaderboard::allowed_organization(
+        storage.get_ref(),
+        *leaderboard,
+        account.id,
+        Permission::ManageOrganization,
+    )
+    .await
+    else {
+        return HttpResponse::Unauthorized().finish();
+    };
+    // leaderboards of a personal organization can't be transferred to its owner
+    let recipient = AdminAccount { id: transfer.to };
+    if recipient.id == organization || !storage.is_admin_active(&recipient).await {
+        return HttpResponse::BadRequest().finish();
+    }
+    if storage
+        .get_pending_transfer_for_leaderboard(*leaderboard)
+        .await
+        .is_some()
+    {
+        return HttpResponse::Conflict().finish();
+    }
+
+    let transfer = Transfer {
+        id: Uuid::new_v4(),
+        leaderboard: *leaderboard,
+        from_organization: organization,
+        to_admin: transfer.to,
+        requested_by: account.id,
+        status: TransferStatus::Pending,
+    };
+    if storage.create_transfer(&transfer).await {
+        storage
+            .record_audit(
+                account.id,
+                Some(organization),
+                AuditAction::TransferRequest,
+                Some(transfer.leaderboard),
+                json!({ "transfer": transfer.id, "to": transfer.to_admin }),
+            )
+            .await;
+        HttpResponse::Ok().json(transfer)
+    } else {
+        HttpResponse::InternalServerError().finish()
+    }
+}
+
+/// Cancel the pending transfer of a leaderboard
+#[utoipa::path(
+    delete,
+    path = "/api/v1/leaderboards/{leaderboard_id}/transfer",
+    tag = "transfers",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The transfer was cancelled"),
+        (status = 401, description = "Not allowed on this leaderboard"),
+        (status = 404, description = "No pending transfer"),
+    )
+)]
+pub(crate) async fn cancel_transfer(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    leaderboard: web::Path<Uuid>,
+) -> impl Responder {
+    let Some(organization) = Leaderboard::allowed_organization(
+        storage.get_ref(),
+        *leaderboard,
+        account.id,
+        Permission::ManageOrganization,
+    )
+    .await
+    else {
+        return HttpResponse::Unauthorized().finish();
+    };
+    let Some(transfer) = storage
+        .get_pending_transfer_for_leaderboard(*leaderboard)
+        .await
+    else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    if storage
+        .resolve_transfer(&transfer, TransferStatus::Cancelled)
+        .await
+    {
+        storage
+            .record_audit(
+                account.id,
+                Some(organization),
+                AuditAction::TransferCancel,
+                Some(transfer.leaderboard),
+                json!({ "transfer": transfer.id }),
+            )
+            .await;
+        HttpResponse::Ok().finish()
+    } else {
+        HttpResponse::InternalServerError().finish()
+    }
+}
+
+/// List the transfers offered to the current admin
+#[utoipa::path(
+    get,
+    path = "/api/v1/transfers",
+    tag = "transfers",
+    security(("admin" = [])),
+    responses(
+        (status = 200, description = "The pending transfers", body = Vec<Transfer>),
+    )
+)]
+async fn get_own_transfers(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+) -> impl Responder {
+    HttpResponse::Ok().json(storage.get_pending_transfers_for_admin(account.id).await)
+}
+
+/// Accept a transfer, moving the leaderboard to the personal organization of the admin
+#[utoipa::path(
+    post,
+    path = "/api/v1/transfers/{transfer_id}/accept",
+    tag = "transfers",
+    security(("admin" = [])),
+    params(("transfer_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The transferred leaderboard", body = Leaderboard),
+        (status = 401, description = "The transfer is for another admin"),
+        (status = 404, description = "Unknown transfer"),
+        (status = 409, description = "The transfer isn't pending"),
+    )
+)]
+async fn accept_transfer(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    transfer: web::Path<Uuid>,
+) -> impl Responder {
+    let Some(transfer) = storage.get_transfer(*transfer).await else {
+        return HttpResponse::NotFound().finish();
+    };
+    if transfer.to_admin != account.id {
+        return HttpResponse::Unauthorized().finish();
+    }
+    if transfer.status != TransferStatus::Pending {
+        return HttpResponse::Conflict().finish();
+    }
+    let Some(organization) = Organization::personal(storage.get_ref(), account.id).await else {
+        return HttpResponse::InternalServerError().finish();
+    };
+
+    match storage.accept_transfer(&transfer, organization).await {
+        Some(true) => {
+            for organization in [transfer.from_organization, organization] {
+                storage
+                    .record_audit(
+                        account.id,
+                        Some(organization),
+                        AuditAction::TransferAccept,
+                        Some(transfer.leaderboard),
+                        json!({ "transfer": transfer.id, "from": transfer.from_organization }),
+                    )
+                    .await;
+            }
+            HttpResponse::Ok().json(storage.get_leaderboard(transfer.leaderboard).await)
+        }
+        // the leaderboard was deleted or moved since the transfer was requested
+        Some(false) => HttpResponse::Conflict().finish(),
+        None => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+/// Decline a transfer
+#[utoipa::path(
+    post,
+    path = "/api/v1/transfers/{transfer_id}/decline",
+    tag = "transfers",
+    security(("admin" = [])),
+    params(("transfer_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The transfer was declined"),
+        (status = 401, description = "The transfer is for another admin"),
+        (status = 404, description = "Unknown transfer"),
+        (status = 409, description = "The transfer isn't pending"),
+    )
+)]
+async fn decline_transfer(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    transfer: web::Path<Uuid>,
+) -> impl Responder {
+    let Some(transfer) = storage.get_transfer(*transfer).await else {
+        return HttpResponse::NotFound().finish();
+    };
+    if transfer.to_admin != account.id {
+        return HttpResponse::Unauthorized().finish();
+    }
+    if transfer.status != TransferStatus::Pending {
+        return HttpResponse::Conflict().finish();
+    }
+
+    if storage
+        .resolve_transfer(&transfer, TransferStatus::Declined)
+        .await
+    {
+        storage
+            .record_audit(
+                account.id,
+                Some(transfer.from_organization),
+                AuditAction::TransferDecline,
+                Some(transfer.leaderboard),
+                json!({ "transfer": transfer.id }),
+            )
+            .await;
+        HttpResponse::Ok().finish()
+    } else {
+        HttpResponse::InternalServerError().finish()
+    }
+}
+
+pub(crate) fn transfer(kp: web::Data<KeyPair>) -> impl HttpServiceFactory {
+    web::scope("api/v1/transfers")
+        .app_data(kp)
+        .wrap(HttpAuthentication::bearer(validator))
+        .route("", web::get().to(get_own_transfers))
+        .route("{transfer_id}/accept", web::post().to(accept_transfer))
+        .route("{transfer_id}/decline", web::post().to(decline_transfer))
+}
@@ -0,0 +1,410 @@
+//! Version 2 of the API used by games, under `/api/v2`.
+//!
+//! It shares the domain logic of version 1, which is kept unchanged for the games already
+//! shipped. The differences are:
+//! - errors are answered with their HTTP status and an [`ApiError`] describing them,
+//! - scores are integers, signed as such whatever the type of the leaderboard,
+//! - scores are read by pages, from the best.
+
+use std::time::Duration;
+
+use actix_web::{
+    dev::HttpServiceFactory,
+    error::InternalError,
+    http::{
+        header::{self, CacheControl, CacheDirective, ETag, IfNoneMatch},
+        StatusCode,
+    },
+    web, HttpRequest, HttpResponse, Responder, ResponseError,
+};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    cache::{Page, ScoreCache},
+    metrics::Metrics,
+    rate_limit::{retry_after_seconds, RateLimits},
+    storage::Storage,
+};
+
+use super::{
+    leaderboard::Leaderboard,
+    player::{Player, PlayerInput},
+    score::{ModeratedScore, ScoreInput, SignedScore, Submission, SubmissionError},
+    webhook::Webhooks,
+};
+
+pub(crate) const DEFAULT_PAGE_SIZE: i64 = 20;
+pub(crate) const MAX_PAGE_SIZE: i64 = 100;
+
+/// What went wrong, for the game to act on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The body, path or query of the request could not be read.
+    InvalidRequest,
+    UnknownPlayer,
+    UnknownLeaderboard,
+    /// The signature doesn't match the keys of the player and leaderboard.
+    InvalidSignature,
+    PlayerBanned,
+    /// The same score was already sent.
+    DuplicateScore,
+    /// The score can't be saved exactly in the type of the leaderboard.
+    ScoreOutOfRange,
+    /// Too many requests, retry after the `Retry-After` header.
+    RateLimited,
+    Internal,
+}
+
+impl ErrorCode {
+    fn status(&self) -> StatusCode {
+        match self {
+            ErrorCode::InvalidRequest => StatusCode::BAD_REQUEST,
+            ErrorCode::UnknownPlayer | ErrorCode::UnknownLeaderboard => StatusCode::NOT_FOUND,
+            ErrorCode::InvalidSignature | ErrorCode::PlayerBanned => StatusCode::FORBIDDEN,
+            ErrorCode::DuplicateScore => StatusCode::CONFLICT,
+            ErrorCode::ScoreOutOfRange => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Body of the responses of version 2 for errors.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiError {
+    pub error: ErrorCode,
+    /// Details for the developers of the game, not meant to be shown to players.
+    pub message: String,
+}
+
+impl ApiError {
+    fn new(error: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            error,
+            message: message.into(),
+        }
+    }
+
+    fn response(&self) -> HttpResponse {
+        HttpResponse::build(self.error.status()).json(self)
+    }
+}
+
+impl From<SubmissionError> for ApiError {
+    fn from(error: SubmissionError) -> Self {
+        match error {
+            SubmissionError::UnknownPlayer => {
+                ApiError::new(ErrorCode::UnknownPlayer, "no player with this id")
+            }
+            SubmissionError::UnknownLeaderboard => {
+                ApiError::new(ErrorCode::UnknownLeaderboard, "no leaderboard with this id")
+            }
+            SubmissionError::InvalidScore => ApiError::new(
+                ErrorCode::ScoreOutOfRange,
+                "the score can't be saved exactly in the type of the leaderboard",
+            ),
+            SubmissionError::BadMac => ApiError::new(
+                ErrorCode::InvalidSignature,
+                "the signature doesn't match the keys of the player and leaderboard",
+            ),
+            SubmissionError::Banned => ApiError::new(
+                ErrorCode::PlayerBanned,
+                "the player is banned from this leaderboard",
+            ),
+            SubmissionError::Duplicate => {
+                ApiError::new(ErrorCode::DuplicateScore, "this score was already sent")
+            }
+            SubmissionError::Throttled(_) => ApiError::new(
+                ErrorCode::RateLimited,
+                "too many scores sent by this player",
+            ),
+            SubmissionError::Failed => {
+                ApiError::new(ErrorCode::Internal, "the score was not saved")
+            }
+        }
+    }
+}
+
+fn rate_limited(retry_after: Duration, message: &str) -> HttpResponse {
+    HttpResponse::TooManyRequests()
+        .insert_header((header::RETRY_AFTER, retry_after_seconds(retry_after)))
+        .json(ApiError::new(ErrorCode::RateLimited, message))
+}
+
+/// Answer requests that can't be read with an [`ApiError`], instead of the plain text of actix.
+fn invalid_request(error: impl ResponseError + 'static) -> actix_web::Error {
+    let response = ApiError::new(ErrorCode::InvalidRequest, error.to_string()).response();
+    InternalError::from_response(error, response).into()
+}
+
+/// A score sent by a game, signed with the key of its player.
+///
+/// `k` is signed like the `k` of `ScoreInput`, with `score` as a 64 bits integer in 8 bytes in
+/// little endian, not followed by the name of a type.
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct ScoreInputV2 {
+    /// Must be exact in the type of the leaderboard: between -2^24 and 2^24 for `f32`, -2^53 and
+    /// 2^53 for `f64`, and at least 0 for `duration_ms`.
+    pub score: i64,
+    pub player: Uuid,
+    pub meta: Option<String>,
+    /// Unix timestamp, in seconds, of when the score was signed.
+    pub timestamp: u64,
+    /// Signature of the score.
+    pub k: String,
+}
+
+impl ScoreInputV2 {
+    fn mac(key: Uuid, leaderboard_key: Uuid, score: &ScoreInputV2) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).unwrap();
+        mac.update(&score.timestamp.to_le_bytes());
+        mac.update(leaderboard_key.as_bytes());
+        mac.update(score.player.as_bytes());
+        mac.update(&score.score.to_le_bytes());
+        if let Some(meta) = score.meta.as_ref() {
+            mac.update(meta.as_bytes());
+        }
+        mac
+    }
+
+    /// A signed score, as a game would send it.
+    pub fn new(
+        score: i64,
+        player: &Player,
+        meta: Option<String>,
+        timestamp: u64,
+        leaderboard_key: Uuid,
+    ) -> Self {
+        let mut input = Self {
+            score,
+            player: player.id,
+            meta,
+            timestamp,
+            k: String::new(),
+        };
+        let mac = Self::mac(player.key, leaderboard_key, &input);
+        input.k = hex::encode(&mac.finalize().into_bytes()[..]);
+        input
+    }
+}
+
+impl SignedScore for ScoreInputV2 {
+    fn to_input(&self) -> ScoreInput {
+        ScoreInput {
+            score: self.score.into(),
+            player: self.player,
+            meta: self.meta.clone(),
+            timestamp: self.timestamp,
+            k: self.k.clone(),
+        }
+    }
+
+    fn verify_mac(&self, _score: &ScoreInput, key: Uuid, leaderboard: &Leaderboard) -> bool {
+        let mac = Self::mac(key, leaderboard.key, self);
+        hex::decode(&self.k).is_ok_and(|k| mac.verify_slice(&k).is_ok())
+    }
+}
+
+/// A score of a leaderboard.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct ScoreV2 {
+    /// Scores of `f32` and `f64` leaderboards are rounded.
+    pub score: i64,
+    pub player: String,
+    pub meta: Option<String>,
+    pub timestamp: String,
+}
+
+impl From<&ModeratedScore> for ScoreV2 {
+    fn from(score: &ModeratedScore) -> Self {
+        ScoreV2 {
+            score: score.score.round(),
+            player: score.player_name.clone(),
+            meta: score.meta.clone(),
+            timestamp: score.timestamp.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScoreQuery {
+    /// From 1, the first by default.
+    pub page: Option<i64>,
+    /// 20 by default, up to 100.
+    pub per_page: Option<i64>,
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct ScorePage {
+    pub scores: Vec<ScoreV2>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+}
+
+/// Create a player
+#[utoipa::path(
+    post,
+    path = "/api/v2/players",
+    tag = "v2",
+    request_body = PlayerInput,
+    responses(
+        (status = 201, description = "The new player", body = Player),
+        (status = 400, description = "Invalid body", body = ApiError),
+        (status = 429, description = "Too many players created from this IP", body = ApiError),
+        (status = 500, description = "The player was not saved", body = ApiError),
+    )
+)]
+async fn create_player(
+    storage: web::Data<dyn Storage>,
+    rate_limits: web::Data<RateLimits>,
+    metrics: web::Data<Metrics>,
+    request: HttpRequest,
+    player: web::Json<PlayerInput>,
+) -> impl Responder {
+    if let Err(retry_after) = rate_limits.retry_after_ip(&rate_limits.player_creation, &request) {
+        return rate_limited(retry_after, "too many players created from this IP");
+    }
+    match Player::create(storage.get_ref(), &metrics, player.into_inner()).await {
+        Some(player) => HttpResponse::Created().json(player),
+        None => ApiError::new(ErrorCode::Internal, "the player was not saved").response(),
+    }
+}
+
+/// Submit a score
+#[utoipa::path(
+    post,
+    path = "/api/v2/scores/{leaderboard_id}",
+    tag = "v2",
+    params(("leaderboard_id" = Uuid, Path)),
+    request_body = ScoreInputV2,
+    responses(
+        (status = 204, description = "The score was saved"),
+        (status = 400, description = "Invalid body or leaderboard id", body = ApiError),
+        (status = 403, description = "Invalid signature, or banned player", body = ApiError),
+        (status = 404, description = "Unknown player or leaderboard", body = ApiError),
+        (status = 409, description = "The score was already sent", body = ApiError),
+        (status = 422, description = "The score is out of range", body = ApiError),
+        (status = 429, description = "Too many scores sent by this player", body = ApiError),
+        (status = 500, description = "The score was not saved", body = ApiError),
+    )
+)]
+async fn save_score(
+    storage: web::Data<dyn Storage>,
+    cache: web::Data<ScoreCache>,
+    webhooks: web::Data<Webhooks>,
+    rate_limits: web::Data<RateLimits>,
+    metrics: web::Data<Metrics>,
+    score: web::Json<ScoreInputV2>,
+    leaderboard: web::Path<Uuid>,
+) -> impl Responder {
+    let submission = Submission {
+        storage: storage.get_ref(),
+        cache: &cache,
+        webhooks: &webhooks,
+        rate_limits: &rate_limits,
+        metrics: &metrics,
+    };
+    match submission.submit(*leaderboard, &*score).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(SubmissionError::Throttled(retry_after)) => {
+            rate_limited(retry_after, "too many scores sent by this player")
+        }
+        Err(error) => ApiError::from(error).response(),
+    }
+}
+
+/// Get the scores of a leaderboard
+///
+/// A page of the scores of a leaderboard, from the best. They are returned with an `ETag`, and a
+/// request with a matching `If-None-Match` gets an empty `304 Not Modified` instead.
+#[utoipa::path(
+    get,
+    path = "/api/v2/scores/{leaderboard_id}",
+    tag = "v2",
+    params(("leaderboard_id" = Uuid, Path), ScoreQuery),
+    responses(
+        (status = 200, description = "A page of the scores", body = ScorePage),
+        (status = 304, description = "Not modified since the `If-None-Match` header"),
+        (status = 400, description = "Invalid leaderboard id or query", body = ApiError),
+        (status = 404, description = "Unknown leaderboard", body = ApiError),
+        (status = 429, description = "Too many reads from this IP", body = ApiError),
+    )
+)]
+async fn get_scores(
+    storage: web::Data<dyn Storage>,
+    cache: web::Data<ScoreCache>,
+    rate_limits: web::Data<RateLimits>,
+    request: HttpRequest,
+    leaderboard: web::Path<Uuid>,
+    query: web::Query<ScoreQuery>,
+    if_none_match: Option<web::Header<IfNoneMatch>>,
+) -> impl Responder {
+    if let Err(retry_after) = rate_limits.retry_after_ip(&rate_limits.reads, &request) {
+        return rate_limited(retry_after, "too many reads from this IP");
+    }
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query
+        .per_page
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let cached = cache
+        .get(
+            storage.get_ref(),
+            *leaderboard,
+            Page::Ranked { page, per_page },
+        )
+        .await;
+    // a leaderboard with scores exists, only check the others
+    if cached.total == 0 && storage.get_leaderboard(*leaderboard).await.is_none() {
+        return ApiError::new(ErrorCode::UnknownLeaderboard, "no leaderboard with this id")
+            .response();
+    }
+    // the tag of a page also changes with the total of scores
+    let etag = ETag(cached.etag.clone());
+    let cache_control = CacheControl(vec![CacheDirective::NoCache]);
+
+    let not_modified = match if_none_match.map(web::Header::into_inner) {
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(etags)) => etags.iter().any(|etag| etag.weak_eq(&cached.etag)),
+        None => false,
+    };
+    if not_modified {
+        return HttpResponse::NotModified()
+            .insert_header(etag)
+            .insert_header(cache_control)
+            .finish();
+    }
+
+    let scores = cached.scores.iter().map(ScoreV2::from).collect();
+
+    HttpResponse::Ok()
+        .insert_header(etag)
+        .insert_header(cache_control)
+        .json(ScorePage {
+            scores,
+            page,
+            per_page,
+            total: cached.total,
+        })
+}
+
+pub(crate) fn v2(max_payload_bytes: usize) -> impl HttpServiceFactory {
+    web::scope("api/v2")
+        .app_data(
+            web::JsonConfig::default()
+                .limit(max_payload_bytes)
+                .error_handler(|error, _| invalid_request(error)),
+        )
+        .app_data(web::PathConfig::default().error_handler(|error, _| invalid_request(error)))
+        .app_data(web::QueryConfig::default().error_handler(|error, _| invalid_request(error)))
+        .route("players", web::post().to(create_player))
+        .route("scores/{leaderboard_id}", web::post().to(save_score))
+        .route("scores/{leaderboard_id}", web::get().to(get_scores))
+}
@@ -0,0 +1,796 @@
+//! Webhooks notifying other services of what happens on a leaderboard.
+//!
+//! Admins register URLs on a leaderboard for some [`WebhookEvent`]s. Each event is posted as JSON
+//! to these URLs, with the headers:
+//! - `X-Jornet-Event`: the event,
+//! - `X-Jornet-Delivery`: id of the delivery, the same for all its attempts,
+//! - `X-Jornet-Timestamp`: unix timestamp of the attempt,
+//! - `X-Jornet-Signature`: `sha256=` followed by the hex encoded HMAC-SHA256 of
+//!   `{timestamp}.{body}`, keyed with the secret of the webhook.
+//!
+//! Deliveries run in the background. An attempt succeeds when the receiver answers with a 2xx
+//! status, otherwise it's retried as set in [`WebhookSettings`]. Each delivery is logged with the
+//! result of its last attempt.
+//!
+//! URLs must resolve to public addresses, both when registered and on each attempt, so that
+//! webhooks can't reach the network of the server. Redirects are not followed.
+//!
+//! On [`Webhooks::shutdown`], attempts in flight are given time to complete. Deliveries waiting for
+//! a retry stay pending, and are resumed on the next start.
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use actix_web::{web, HttpResponse, Responder};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect::Policy,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tokio::{
+    sync::{mpsc, watch},
+    task::{JoinHandle, JoinSet},
+};
+use tracing::{info, info_span, warn, Instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{configuration::WebhookSettings, storage::Storage};
+
+use super::{
+    admin::AdminAccount,
+    audit::AuditAction,
+    leaderboard::Leaderboard,
+    organization::Permission,
+    player::Player,
+    score::{ScoreInput, ScoreValue},
+};
+
+/// How long a receiver has to answer an attempt.
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest wait before retrying an attempt, whatever the backoff.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+/// Deliveries returned in the log of a webhook.
+const DELIVERY_LOG_SIZE: i64 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A score was saved.
+    Score,
+    /// A score is better than all the previous ones.
+    NewLeader,
+    /// A player was banned from the leaderboard.
+    PlayerBan,
+    /// All the scores of the leaderboard were deleted.
+    LeaderboardReset,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::Score => "score",
+            WebhookEvent::NewLeader => "new_leader",
+            WebhookEvent::PlayerBan => "player_ban",
+            WebhookEvent::LeaderboardReset => "leaderboard_reset",
+        }
+    }
+
+    pub fn parse(event: &str) -> Option<WebhookEvent> {
+        match event {
+            "score" => Some(WebhookEvent::Score),
+            "new_leader" => Some(WebhookEvent::NewLeader),
+            "player_ban" => Some(WebhookEvent::PlayerBan),
+            "leaderboard_reset" => Some(WebhookEvent::LeaderboardReset),
+            _ => None,
+        }
+    }
+
+    /// Events of a webhook, as they are stored.
+    pub fn join(events: &[WebhookEvent]) -> String {
+        events
+            .iter()
+            .map(|event| event.as_str())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    pub fn split(events: &str) -> Vec<WebhookEvent> {
+        events.split(',').filter_map(WebhookEvent::parse).collect()
+    }
+}
+
+/// Body of the requests sent to a webhook.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// `{ "event": ..., "leaderboard": { "id": ..., "name": ... }, "data": ... }`
+    #[default]
+    Jornet,
+    /// A message for a Discord webhook.
+    Discord,
+}
+
+impl WebhookFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookFormat::Jornet => "jornet",
+            WebhookFormat::Discord => "discord",
+        }
+    }
+
+    pub fn parse(format: &str) -> Option<WebhookFormat> {
+        match format {
+            "jornet" => Some(WebhookFormat::Jornet),
+            "discord" => Some(WebhookFormat::Discord),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct WebhookInput {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    #[serde(default)]
+    pub format: WebhookFormat,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub leaderboard: Uuid,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub format: WebhookFormat,
+    /// Only sent to the admin registering the webhook.
+    #[serde(skip)]
+    pub secret: String,
+    pub created_by: Uuid,
+}
+
+/// A webhook as returned when it's registered, the only time its secret is shown.
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct RegisteredWebhook {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// Not attempted yet, or waiting for a retry.
+    Pending,
+    Delivered,
+    /// All attempts failed.
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(status: &str) -> Option<DeliveryStatus> {
+        match status {
+            "pending" => Some(DeliveryStatus::Pending),
+            "delivered" => Some(DeliveryStatus::Delivered),
+            "failed" => Some(DeliveryStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// An event sent to a webhook, with the result of its last attempt.
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct Delivery {
+    pub id: Uuid,
+    pub webhook: Uuid,
+    pub event: WebhookEvent,
+    /// The body sent to the webhook.
+    pub payload: Value,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    /// Status of the last response received.
+    pub response_status: Option<i32>,
+    /// Why the last attempt failed.
+    pub error: Option<String>,
+    pub created_at: String,
+    pub last_attempt_at: Option<String>,
+}
+
+impl Webhook {
+    fn payload(&self, leaderboard: &Leaderboard, event: WebhookEvent, data: &Value) -> Value {
+        match self.format {
+            WebhookFormat::Jornet => json!({
+                "event": event,
+                "leaderboard": { "id": leaderboard.id, "name": leaderboard.name },
+                "data": data,
+            }),
+            WebhookFormat::Discord => {
+                let content = match event {
+                    WebhookEvent::Score => format!(
+                        "New score on {}: {} by {}",
+                        leaderboard.name, data["score"], data["player"]["name"]
+                    ),
+                    WebhookEvent::NewLeader => format!(
+                        "New #1 on {}: {} by {}",
+                        leaderboard.name, data["score"], data["player"]["name"]
+                    ),
+                    WebhookEvent::PlayerBan => format!(
+                        "A player was banned from {}, {} of their scores were removed",
+                        leaderboard.name, data["scores"]
+                    ),
+                    WebhookEvent::LeaderboardReset => {
+                        format!(
+                            "All {} scores of {} were deleted",
+                            data["scores"], leaderboard.name
+                        )
+                    }
+                };
+                json!({ "content": content })
+            }
+        }
+    }
+}
+
+/// A delivery to attempt, with what's needed to send it.
+struct Job {
+    delivery: Delivery,
+    url: String,
+    secret: String,
+}
+
+/// Sends the webhook deliveries in the background.
+pub struct Webhooks {
+    settings: WebhookSettings,
+    jobs: mpsc::UnboundedSender<Job>,
+    stopping: watch::Sender<bool>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Webhooks {
+    /// Start sending deliveries, beginning with the ones that were still pending when the server
+    /// stopped. Must be called from within a Tokio runtime.
+    pub fn start(storage: Arc<dyn Storage>, settings: WebhookSettings) -> Self {
+        let (jobs, mut receiver) = mpsc::unbounded_channel::<Job>();
+        let mut client = reqwest::Client::builder()
+            .timeout(TIMEOUT)
+            .redirect(Policy::none());
+        if !settings.allow_private_addresses {
+            // checked again when connecting, in case the host now resolves elsewhere
+            client = client.dns_resolver(Arc::new(PublicResolver));
+        }
+        let client = client.build().unwrap();
+
+        let pending_jobs = jobs.clone();
+        let pending_storage = storage.clone();
+        tokio::spawn(async move {
+            for delivery in pending_storage.get_pending_deliveries().await {
+                if let Some(webhook) = pending_storage.get_webhook(delivery.webhook).await {
+                    let _ = pending_jobs.send(Job {
+                        delivery,
+                        url: webhook.url,
+                        secret: webhook.secret,
+                    });
+                }
+            }
+        });
+
+        let (stopping, _) = watch::channel(false);
+        let mut stop = stopping.subscribe();
+        let worker = tokio::spawn(async move {
+            let mut deliveries = JoinSet::new();
+            loop {
+                let job = tokio::select! {
+                    Some(job) = receiver.recv() => job,
+                    _ = stop.wait_for(|stopping| *stopping) => break,
+                };
+                while deliveries.try_join_next().is_some() {}
+                let span = info_span!(
+                    "webhook_delivery",
+                    delivery = %job.delivery.id,
+                    webhook = %job.delivery.webhook,
+                    event = job.delivery.event.as_str(),
+                );
+                deliveries.spawn(
+                    deliver(job, storage.clone(), client.clone(), settings, stop.clone())
+                        .instrument(span),
+                );
+            }
+            while deliveries.join_next().await.is_some() {}
+        });
+
+        Self {
+            settings,
+            jobs,
+            stopping,
+            worker: Mutex::new(Some(worker)),
+        }
+    }
+
+    /// Whether deliveries can be sent to this URL.
+    pub async fn accepts_url(&self, url: &str) -> bool {
+        accepts_url(url, self.settings.allow_private_addresses).await
+    }
+
+    /// Stop sending deliveries, waiting up to `timeout` for the attempts in flight. Deliveries
+    /// not sent yet stay pending.
+    pub async fn shutdown(&self, timeout: Duration) {
+        self.stopping.send_replace(true);
+        let Some(worker) = self.worker.lock().unwrap().take() else {
+            return;
+        };
+        if tokio::time::timeout(timeout, worker).await.is_err() {
+            warn!("webhook attempts still in flight after the shutdown timeout");
+        }
+    }
+
+    /// Queue the deliveries of an event to the webhooks of the leaderboard registered for it.
+    pub async fn trigger(
+        &self,
+        storage: &dyn Storage,
+        leaderboard: Uuid,
+        event: WebhookEvent,
+        data: Value,
+    ) {
+        let webhooks: Vec<_> = storage
+            .get_webhooks(leaderboard)
+            .await
+            .into_iter()
+            .filter(|webhook| webhook.events.contains(&event))
+            .collect();
+        if webhooks.is_empty() {
+            return;
+        }
+        let Some(leaderboard) = storage.get_leaderboard(leaderboard).await else {
+            return;
+        };
+
+        for webhook in webhooks {
+            let delivery = Delivery {
+                id: Uuid::new_v4(),
+                webhook: webhook.id,
+                event,
+                payload: webhook.payload(&leaderboard, event, &data),
+                status: DeliveryStatus::Pending,
+                attempts: 0,
+                response_status: None,
+                error: None,
+                created_at: String::new(),
+                last_attempt_at: None,
+            };
+            if storage.create_delivery(&delivery).await {
+                let _ = self.jobs.send(Job {
+                    delivery,
+                    url: webhook.url,
+                    secret: webhook.secret,
+                });
+            }
+        }
+    }
+
+    /// Best score of the leaderboard, if a webhook needs to know when it's beaten.
+    pub async fn best_score_if_watched(
+        &self,
+        storage: &dyn Storage,
+        leaderboard: &Leaderboard,
+    ) -> Option<Option<ScoreValue>> {
+        if storage
+            .get_webhooks(leaderboard.id)
+            .await
+            .iter()
+            .any(|webhook| webhook.events.contains(&WebhookEvent::NewLeader))
+        {
+            Some(
+                storage
+                    .get_best_score(leaderboard.id, leaderboard.score_type.sort_direction())
+                    .await,
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Trigger the events for a saved score. `previous_best` is the best score before it was
+    /// saved, from [`Self::best_score_if_watched`].
+    pub async fn score_saved(
+        &self,
+        storage: &dyn Storage,
+        leaderboard: &Leaderboard,
+        player: &Player,
+        score: &ScoreInput,
+        previous_best: Option<Option<ScoreValue>>,
+    ) {
+        let data = json!({
+            "player": { "id": player.id, "name": player.name },
+            "score": score.score,
+            "meta": score.meta,
+        });
+        if let Some(previous_best) = previous_best {
+            let direction = leaderboard.score_type.sort_direction();
+            if previous_best
+                .map(|best| direction.compare(&score.score, &best).is_lt())
+                .unwrap_or(true)
+            {
+                let mut data = data.clone();
+                data["previous_best"] = json!(previous_best);
+                self.trigger(storage, leaderboard.id, WebhookEvent::NewLeader, data)
+                    .await;
+            }
+        }
+        self.trigger(storage, leaderboard.id, WebhookEvent::Score, data)
+            .await;
+    }
+}
+
+/// Whether an address is reachable from the internet. Loopback, private, link-local and the
+/// other special purpose ranges are not.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                // "this network", 0.0.0.0/8
+                || first == 0
+                // shared address space, 100.64.0.0/10
+                || (first == 100 && second & 0xc0 == 64))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+/// Resolve the host of a URL, with its port.
+async fn resolve(url: &reqwest::Url) -> Option<Vec<SocketAddr>> {
+    // IPv6 addresses are between brackets
+    let host = url
+        .host_str()?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    let port = url.port_or_known_default()?;
+    tokio::net::lookup_host((host, port))
+        .await
+        .ok()
+        .map(|addresses| addresses.collect())
+}
+
+/// An HTTP or HTTPS URL, with a host resolving only to public addresses unless
+/// `allow_private_addresses` is set.
+async fn accepts_url(url: &str, allow_private_addresses: bool) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return false;
+    }
+    if allow_private_addresses {
+        return true;
+    }
+    match resolve(&url).await {
+        Some(addresses) => {
+            !addresses.is_empty() && addresses.iter().all(|address| is_public(address.ip()))
+        }
+        None => false,
+    }
+}
+
+/// Resolves the hosts of the deliveries, keeping only their public addresses.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses: Vec<_> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| is_public(address.ip()))
+                .collect();
+            if addresses.is_empty() {
+                return Err("no public address for this host".into());
+            }
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+fn sign(secret: &str, timestamp: u64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Attempt a delivery until it succeeds or runs out of attempts, logging each result.
+async fn deliver(
+    mut job: Job,
+    storage: Arc<dyn Storage>,
+    client: reqwest::Client,
+    settings: WebhookSettings,
+    mut stop: watch::Receiver<bool>,
+) {
+    let body = job.delivery.payload.to_string();
+    loop {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_secs();
+        if !accepts_url(&job.url, settings.allow_private_addresses).await {
+            let delivery = &mut job.delivery;
+            delivery.attempts += 1;
+            delivery.response_status = None;
+            delivery.error = Some("URL not allowed".to_string());
+            delivery.status = DeliveryStatus::Failed;
+            storage.update_delivery(delivery).await;
+            warn!(
+                attempts = delivery.attempts,
+                "webhook delivery failed, its URL doesn't resolve to a public address"
+            );
+            return;
+        }
+        let result = client
+            .post(&job.url)
+            .header("Content-Type", "application/json")
+            .header("X-Jornet-Event", job.delivery.event.as_str())
+            .header("X-Jornet-Delivery", job.delivery.id.to_string())
+            .header("X-Jornet-Timestamp", timestamp.to_string())
+            .header("X-Jornet-Signature", sign(&job.secret, timestamp, &body))
+            .body(body.clone())
+            .send()
+            .await;
+
+        let delivery = &mut job.delivery;
+        delivery.attempts += 1;
+        match result {
+            Ok(response) => {
+                delivery.response_status = Some(response.status().as_u16() as i32);
+                delivery.error =
+                    (!response.status().is_success()).then(|| "unsuccessful status".to_string());
+            }
+            Err(error) => {
+                delivery.response_status = None;
+                delivery.error = Some(error.to_string());
+            }
+        }
+        delivery.status = if delivery.error.is_none() {
+            DeliveryStatus::Delivered
+        } else if delivery.attempts as u32 >= settings.max_attempts {
+            DeliveryStatus::Failed
+        } else {
+            DeliveryStatus::Pending
+        };
+        storage.update_delivery(delivery).await;
+
+        match (&delivery.error, delivery.status) {
+            (None, _) => info!(attempts = delivery.attempts, "webhook delivered"),
+            (Some(error), DeliveryStatus::Failed) => {
+                warn!(attempts = delivery.attempts, %error, "webhook delivery failed")
+            }
+            (Some(error), _) => {
+                info!(attempts = delivery.attempts, %error, "webhook attempt failed")
+            }
+        }
+        if delivery.status != DeliveryStatus::Pending {
+            return;
+        }
+        let backoff = Duration::from_millis(
+            settings
+                .retry_delay_ms
+                .saturating_mul(1 << (delivery.attempts - 1).clamp(0, 16)),
+        )
+        .min(MAX_RETRY_DELAY);
+        tokio::select! {
+            _ = tokio::time::sleep(backoff) => (),
+            // the delivery is still pending, it will be retried on the next start
+            _ = stop.wait_for(|stopping| *stopping) => return,
+        }
+    }
+}
+
+/// Register a webhook on a leaderboard
+#[utoipa::path(
+    post,
+    path = "/api/v1/leaderboards/{leaderboard_id}/webhooks",
+    tag = "webhooks",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path)),
+    request_body = WebhookInput,
+    responses(
+        (status = 200, description = "The webhook, with the secret signing its deliveries", body = RegisteredWebhook),
+        (status = 401, description = "Not allowed on this leaderboard"),
+        (status = 400, description = "Invalid URL or not resolving to a public address, or no events"),
+    )
+)]
+pub(crate) async fn register_webhook(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    webhooks: web::Data<Webhooks>,
+    leaderboard: web::Path<Uuid>,
+    webhook: web::Json<WebhookInput>,
+) -> impl Responder {
+    let Some(organization) = Leaderboard::allowed_organization(
+        storage.get_ref(),
+        *leaderboard,
+        account.id,
+        Permission::ManageLeaderboards,
+    )
+    .await
+    else {
+        return HttpResponse::Unauthorized().finish();
+    };
+    if webhook.events.is_empty() || !webhooks.accepts_url(&webhook.url).await {
+        return HttpResponse::BadRequest().finish();
+    }
+
+    let mut secret = [0; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+    let webhook = Webhook {
+        id: Uuid::new_v4(),
+        leaderboard: *leaderboard,
+        url: webhook.url.clone(),
+        events: webhook.events.clone(),
+        format: webhook.format,
+        secret: hex::encode(secret),
+        created_by: account.id,
+    };
+    if storage.create_webhook(&webhook).await {
+        storage
+            .record_audit(
+                account.id,
+                Some(organization),
+                AuditAction::WebhookCreate,
+                Some(webhook.leaderboard),
+                json!({ "webhook": webhook.id, "url": webhook.url }),
+            )
+            .await;
+        HttpResponse::Ok().json(RegisteredWebhook {
+            secret: webhook.secret.clone(),
+            webhook,
+        })
+    } else {
+        HttpResponse::InternalServerError().finish()
+    }
+}
+
+/// List the webhooks of a leaderboard
+#[utoipa::path(
+    get,
+    path = "/api/v1/leaderboards/{leaderboard_id}/webhooks",
+    tag = "webhooks",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The webhooks, without their secrets", body = Vec<Webhook>),
+        (status = 401, description = "Not allowed on this leaderboard"),
+    )
+)]
+pub(crate) async fn get_webhooks(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    leaderboard: web::Path<Uuid>,
+) -> impl Responder {
+    if Leaderboard::allowed_organization(
+        storage.get_ref(),
+        *leaderboard,
+        account.id,
+        Permission::View,
+    )
+    .await
+    .is_some()
+    {
+        HttpResponse::Ok().json(storage.get_webhooks(*leaderboard).await)
+    } else {
+        HttpResponse::Unauthorized().finish()
+    }
+}
+
+/// Delete a webhook
+#[utoipa::path(
+    delete,
+    path = "/api/v1/leaderboards/{leaderboard_id}/webhooks/{webhook_id}",
+    tag = "webhooks",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path), ("webhook_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The webhook was deleted", body = bool),
+        (status = 401, description = "Not allowed on this leaderboard"),
+        (status = 404, description = "Unknown webhook"),
+    )
+)]
+pub(crate) async fn delete_webhook(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (leaderboard, webhook) = path.into_inner();
+    let Some(organization) = Leaderboard::allowed_organization(
+        storage.get_ref(),
+        leaderboard,
+        account.id,
+        Permission::ManageLeaderboards,
+    )
+    .await
+    else {
+        return HttpResponse::Unauthorized().finish();
+    };
+
+    if storage.delete_webhook(leaderboard, webhook).await {
+        storage
+            .record_audit(
+                account.id,
+                Some(organization),
+                AuditAction::WebhookDelete,
+                Some(leaderboard),
+                json!({ "webhook": webhook }),
+            )
+            .await;
+        HttpResponse::Ok().json(true)
+    } else {
+        HttpResponse::NotFound().finish()
+    }
+}
+
+/// Get the last deliveries of a webhook
+#[utoipa::path(
+    get,
+    path = "/api/v1/leaderboards/{leaderboard_id}/webhooks/{webhook_id}/deliveries",
+    tag = "webhooks",
+    security(("admin" = [])),
+    params(("leaderboard_id" = Uuid, Path), ("webhook_id" = Uuid, Path)),
+    responses(
+        (status = 200, description = "The last 100 deliveries", body = Vec<Delivery>),
+        (status = 401, description = "Not allowed on this leaderboard"),
+        (status = 404, description = "Unknown webhook"),
+    )
+)]
+pub(crate) async fn get_deliveries(
+    account: web::ReqData<AdminAccount>,
+    storage: web::Data<dyn Storage>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let (leaderboard, webhook) = path.into_inner();
+    if Leaderboard::allowed_organization(
+        storage.get_ref(),
+        leaderboard,
+        account.id,
+        Permission::View,
+    )
+    .await
+    .is_none()
+    {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    match storage.get_webhook(webhook).await {
+        Some(webhook) if webhook.leaderboard == leaderboard => {
+            HttpResponse::Ok().json(storage.get_deliveries(webhook.id, DELIVERY_LOG_SIZE).await)
+        }
+        _ => HttpResponse::NotFound().finish(),
+    }
+}
@@ -1,56 +1,195 @@
-use std::{net::TcpListener, path::PathBuf, str::FromStr};
+use std::{net::TcpListener, sync::Arc, time::Duration};
 
-use actix_files::NamedFile;
 use actix_web::{
     dev::Server,
-    middleware::{Compress, Logger},
+    http::KeepAlive,
+    middleware::{from_fn, Compress, Condition},
     web::{self, Data},
-    App, HttpRequest, HttpServer, Result,
+    App, HttpServer, Result,
 };
-use configuration::get_configuration;
+use cache::ScoreCache;
+use configuration::Settings;
+use domains::webhook::Webhooks;
+use metrics::Metrics;
+use rate_limit::RateLimits;
 use sqlx::PgPool;
+use storage::{MemoryStorage, PostgresStorage, Storage};
+use tls::CertificateResolver;
 
 pub mod auth_admin;
+pub mod cache;
 pub mod configuration;
+pub mod cors;
 pub mod domains;
+pub mod metrics;
+pub mod openapi;
 pub mod random_name;
+pub mod rate_limit;
+pub mod spa;
+pub mod storage;
+pub mod telemetry;
+pub mod tls;
 
-async fn spa(req: HttpRequest) -> Result<NamedFile> {
-    let path: PathBuf = req.match_info().query("filename").parse().unwrap();
-    let mut static_path = PathBuf::from_str("static").unwrap();
-    if path.extension().is_some() {
-        static_path.push(path);
-    } else {
-        static_path.push("index.html");
+pub fn run(
+    listener: TcpListener,
+    connection_pool: PgPool,
+    configuration: Settings,
+) -> Result<Server, std::io::Error> {
+    run_with_storage(
+        listener,
+        Arc::new(PostgresStorage::new(connection_pool)),
+        configuration,
+    )
+}
+
+/// Run the server without any database. The storage can be cloned before to seed it or inspect
+/// it while the server is running.
+pub fn run_in_memory(
+    listener: TcpListener,
+    storage: MemoryStorage,
+    configuration: Settings,
+) -> Result<Server, std::io::Error> {
+    run_with_storage(listener, Arc::new(storage), configuration)
+}
+
+pub fn run_with_storage(
+    listener: TcpListener,
+    storage: Arc<dyn Storage>,
+    configuration: Settings,
+) -> Result<Server, std::io::Error> {
+    build(listener, storage, configuration, true).map(|(server, _)| server)
+}
+
+/// Run the server until it receives SIGTERM or SIGINT, then stop accepting connections, and let
+/// the webhook attempts in flight and the requests in flight complete, each within the shutdown
+/// timeout.
+pub async fn serve(
+    listener: TcpListener,
+    storage: Arc<dyn Storage>,
+    configuration: Settings,
+) -> Result<(), std::io::Error> {
+    let shutdown_timeout = Duration::from_secs(configuration.server.shutdown_timeout_seconds);
+    let (server, webhooks) = build(listener, storage, configuration, false)?;
+    let handle = server.handle();
+    let server = tokio::spawn(server);
+
+    shutdown_signal().await?;
+    handle.pause().await;
+    // database connections opened by the workers close with them, webhooks must be done before
+    webhooks.shutdown(shutdown_timeout).await;
+    handle.stop(true).await;
+    server.await?
+}
+
+async fn shutdown_signal() -> Result<(), std::io::Error> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            _ = terminate.recv() => Ok(()),
+            result = tokio::signal::ctrl_c() => result,
+        }
     }
-    Ok(NamedFile::open(static_path)?)
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await
 }
 
-pub fn run(listener: TcpListener, connection_pool: PgPool) -> Result<Server, std::io::Error> {
-    let config = Data::new(get_configuration());
+fn build(
+    listener: TcpListener,
+    storage: Arc<dyn Storage>,
+    configuration: Settings,
+    handle_signals: bool,
+) -> Result<(Server, Data<Webhooks>), std::io::Error> {
+    let server_settings = configuration.server;
+    let config = Data::new(configuration);
     let root = Data::new(config.get_keypair());
-    let connection = Data::new(connection_pool);
+    let webhooks = Data::new(Webhooks::start(storage.clone(), config.webhooks));
+    let storage: Data<dyn Storage> = Data::from(storage);
+    let cache = Data::new(ScoreCache::default());
+    let metrics = Data::new(Metrics::new(&cache));
+    let rate_limits = Data::new(RateLimits::new(&config.rate_limits));
+    let json_config = web::JsonConfig::default().limit(server_settings.max_payload_bytes);
+    let payload_config = web::PayloadConfig::new(server_settings.max_payload_bytes);
+
+    let tls = config.tls.clone();
+    let redirect = tls
+        .as_ref()
+        .is_some_and(|tls| tls.redirect_http_port.is_some());
+    let host = config.application_host.clone();
 
+    let app_webhooks = webhooks.clone();
     let server = HttpServer::new(move || {
+        let webhooks = app_webhooks.clone();
         App::new()
-            .app_data(connection.clone())
+            .app_data(json_config.clone())
+            .app_data(payload_config.clone())
+            .app_data(storage.clone())
+            .app_data(cache.clone())
+            .app_data(webhooks.clone())
+            .app_data(rate_limits.clone())
+            .app_data(metrics.clone())
             .app_data(root.clone())
             .app_data(config.clone())
-            .wrap(Logger::default())
             .wrap(Compress::default())
+            .wrap(cors::cors(&config.cors))
+            .wrap(Condition::new(redirect, from_fn(tls::redirect_to_https)))
+            .wrap(from_fn(metrics::track))
+            .wrap(from_fn(telemetry::trace_request))
             .route(
                 "/health_check",
                 web::get().to(domains::healthcheck::health_check),
             )
+            .route(
+                "/health/live",
+                web::get().to(domains::healthcheck::health_check),
+            )
+            .route(
+                "/health/ready",
+                web::get().to(domains::healthcheck::readiness),
+            )
+            .route("/metrics", web::get().to(metrics::metrics))
+            .route("/api/v1/openapi.json", web::get().to(openapi::openapi))
             .service(domains::config::config(config.clone()))
             .service(domains::oauth::oauth())
             .service(domains::admin::admin(root.clone()))
             .service(domains::leaderboard::leaderboard(root.clone()))
+            .service(domains::organization::organization(root.clone()))
+            .service(domains::organization::invitation(root.clone()))
             .service(domains::player::player())
             .service(domains::score::score())
-            .route("/{filename:.*}", web::get().to(spa))
+            .service(domains::superadmin::superadmin(root.clone()))
+            .service(domains::transfer::transfer(root.clone()))
+            .service(domains::v2::v2(server_settings.max_payload_bytes))
+            .route("/{filename:.*}", web::get().to(spa::spa))
     })
-    .listen(listener)?
-    .run();
-    Ok(server)
+    .keep_alive(match server_settings.keep_alive_seconds {
+        0 => KeepAlive::Disabled,
+        seconds => KeepAlive::Timeout(Duration::from_secs(seconds)),
+    })
+    .shutdown_timeout(server_settings.shutdown_timeout_seconds);
+    let server = match server_settings.workers {
+        Some(workers) => server.workers(workers),
+        None => server,
+    };
+    let server = if handle_signals {
+        server
+    } else {
+        server.disable_signals()
+    };
+    let server = match tls {
+        None => server.listen(listener)?,
+        Some(tls) => {
+            let resolver =
+                Arc::new(CertificateResolver::load(&tls).map_err(std::io::Error::other)?);
+            tls::reload_on_hangup(resolver.clone());
+            let server = server.listen_rustls_0_23(listener, resolver.server_config())?;
+            match tls.redirect_http_port {
+                Some(port) => server.bind((host.as_str(), port))?,
+                None => server,
+            }
+        }
+    };
+    Ok((server.run(), webhooks))
 }
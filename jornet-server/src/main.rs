@@ -1,11 +1,17 @@
 use std::net::TcpListener;
 
-use jornet_server::{configuration::get_configuration, run};
-use sqlx::PgPool;
+use jornet_server::{configuration::load_configuration, serve, storage, telemetry};
 
 #[tokio::main]
 async fn main() -> std::io::Result<()> {
-    let configuration = get_configuration();
+    let configuration = load_configuration().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(1)
+    });
+    let _telemetry = telemetry::init(&configuration.logging);
+    for warning in &configuration.warnings {
+        tracing::warn!("{}", warning);
+    }
 
     let address = format!(
         "{}:{}",
@@ -13,9 +19,9 @@ async fn main() -> std::io::Result<()> {
     );
     let listener = TcpListener::bind(&address)?;
 
-    let connection_pool = PgPool::connect(&configuration.database.connection_string())
+    let storage = storage::connect(&configuration)
         .await
-        .expect("Failed to connect to Postgres.");
+        .expect("Failed to connect to the storage.");
 
-    run(listener, connection_pool)?.await
+    serve(listener, storage, configuration).await
 }
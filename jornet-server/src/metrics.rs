@@ -0,0 +1,193 @@
+//! Prometheus metrics of the server, served in the text format on `/metrics`.
+//!
+//! Requests are counted per method, route pattern and status. The storage pool is read when the
+//! metrics are scraped. When a token is set in [`MetricsSettings`](crate::configuration::MetricsSettings), scrapers must send it as a
+//! bearer token.
+
+use std::time::Instant;
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header,
+    http::Method,
+    middleware::Next,
+    web, Error, HttpRequest, HttpResponse, Responder,
+};
+use prometheus::{
+    HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+use subtle::ConstantTimeEq;
+
+use crate::{
+    cache::ScoreCache, configuration::Settings, domains::score::SubmissionOutcome, storage::Storage,
+};
+
+pub struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    request_duration: HistogramVec,
+    score_submissions: IntCounterVec,
+    player_creations: IntCounter,
+    pool_connections: IntGauge,
+    pool_idle: IntGauge,
+}
+
+impl Metrics {
+    /// Create the metrics, including the ones kept by the score cache.
+    pub fn new(cache: &ScoreCache) -> Self {
+        let registry = Registry::new();
+        let requests = IntCounterVec::new(
+            Opts::new("jornet_http_requests_total", "HTTP requests handled"),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "jornet_http_request_duration_seconds",
+                "Time to answer HTTP requests, until the headers are sent",
+            ),
+            &["method", "route"],
+        )
+        .unwrap();
+        let score_submissions = IntCounterVec::new(
+            Opts::new("jornet_score_submissions_total", "Scores submitted"),
+            &["outcome"],
+        )
+        .unwrap();
+        let player_creations =
+            IntCounter::new("jornet_player_creations_total", "Players created").unwrap();
+        let pool_connections = IntGauge::new(
+            "jornet_db_pool_connections",
+            "Connections open in the database pool",
+        )
+        .unwrap();
+        let pool_idle = IntGauge::new(
+            "jornet_db_pool_idle_connections",
+            "Idle connections in the database pool",
+        )
+        .unwrap();
+
+        registry.register(Box::new(requests.clone())).unwrap();
+        registry
+            .register(Box::new(request_duration.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(score_submissions.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(player_creations.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(pool_connections.clone()))
+            .unwrap();
+        registry.register(Box::new(pool_idle.clone())).unwrap();
+        registry
+            .register(Box::new(cache.lookups().clone()))
+            .unwrap();
+
+        Self {
+            registry,
+            requests,
+            request_duration,
+            score_submissions,
+            player_creations,
+            pool_connections,
+            pool_idle,
+        }
+    }
+
+    pub fn score_submitted(&self, outcome: SubmissionOutcome) {
+        self.score_submissions
+            .with_label_values(&[outcome.as_str()])
+            .inc();
+    }
+
+    pub fn player_created(&self) {
+        self.player_creations.inc();
+    }
+
+    fn render(&self, storage: &dyn Storage) -> String {
+        if let Some(stats) = storage.pool_stats() {
+            self.pool_connections.set(stats.connections as i64);
+            self.pool_idle.set(stats.idle as i64);
+        }
+        TextEncoder::new()
+            .encode_to_string(&self.registry.gather())
+            .unwrap()
+    }
+}
+
+/// Label of a request method. Methods are free text sent by the client, the ones not served by the
+/// API are grouped so that the labels stay bounded.
+fn method_label(method: &Method) -> &'static str {
+    match *method {
+        Method::GET => "GET",
+        Method::HEAD => "HEAD",
+        Method::POST => "POST",
+        Method::PUT => "PUT",
+        Method::DELETE => "DELETE",
+        Method::PATCH => "PATCH",
+        Method::OPTIONS => "OPTIONS",
+        _ => "other",
+    }
+}
+
+/// Middleware counting and timing the requests.
+pub async fn track(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let metrics = request.app_data::<web::Data<Metrics>>().cloned();
+    let method = method_label(request.method());
+    let start = Instant::now();
+
+    let response = next.call(request).await;
+
+    if let Some(metrics) = metrics {
+        let (route, status) = match &response {
+            Ok(response) => (
+                response.request().match_pattern(),
+                response.status().as_u16(),
+            ),
+            Err(error) => (None, error.as_response_error().status_code().as_u16()),
+        };
+        // unmatched paths are not used as labels, they are unbounded
+        let route = route.unwrap_or_else(|| "unmatched".to_string());
+        metrics
+            .requests
+            .with_label_values(&[method, route.as_str(), &status.to_string()])
+            .inc();
+        metrics
+            .request_duration
+            .with_label_values(&[method, route.as_str()])
+            .observe(start.elapsed().as_secs_f64());
+    }
+
+    response
+}
+
+pub(crate) async fn metrics(
+    metrics: web::Data<Metrics>,
+    config: web::Data<Settings>,
+    storage: web::Data<dyn Storage>,
+    request: HttpRequest,
+) -> impl Responder {
+    if let Some(token) = &config.metrics.token {
+        let authorized = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            // compared in constant time, to not leak how much of the token was right
+            .map(|sent| bool::from(sent.as_bytes().ct_eq(token.as_bytes())))
+            .unwrap_or(false);
+        if !authorized {
+            return HttpResponse::Unauthorized().finish();
+        }
+    }
+
+    HttpResponse::Ok()
+        .content_type(prometheus::TEXT_FORMAT)
+        .body(metrics.render(storage.get_ref()))
+}
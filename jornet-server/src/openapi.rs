@@ -0,0 +1,131 @@
+//! OpenAPI document of the HTTP API, generated from the handlers and the types they exchange.
+//!
+//! It's served at `/api/v1/openapi.json`, and a copy is kept in `openapi.json` at the root of the
+//! crate for client developers. The `openapi` test fails when that copy is outdated, run it with
+//! `UPDATE_OPENAPI=1` to write it again.
+
+use actix_web::{HttpResponse, Responder};
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use crate::domains::{
+    admin, audit, config, healthcheck, leaderboard, live, oauth, organization, player, score,
+    superadmin, transfer, v2, webhook,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Jornet",
+        version = "1.0.0",
+        description = "Leaderboards for games.
+
+Games create a player with `POST /api/v1/players` and keep its key. Scores are then sent to \
+`POST /api/v1/scores/{leaderboard_id}`, signed with the keys of the player and of the \
+leaderboard as described in `ScoreInput`.
+
+Version 2, under `/api/v2`, answers errors with an `ApiError`, takes integer scores and returns \
+scores by pages. Version 1 is kept unchanged for the games already shipped.
+
+Admins get a token from one of the `/oauth` endpoints, and send it as a bearer token to the \
+other endpoints. Tokens are valid for 10 minutes.",
+        license(name = "MIT OR Apache-2.0")
+    ),
+    paths(
+        player::create_player,
+        score::save_score,
+        score::get_scores,
+        live::live_scores,
+        oauth::by_uuid,
+        oauth::anonymous,
+        oauth::oauth_callback,
+        config::get_oauth_config,
+        admin::whoami,
+        admin::get_identities,
+        admin::link_github,
+        admin::unlink_github,
+        admin::delete_account,
+        leaderboard::new_leaderboard,
+        leaderboard::get_leaderboards,
+        leaderboard::delete_leaderboard,
+        leaderboard::rotate_key,
+        leaderboard::get_scores,
+        leaderboard::delete_all_scores,
+        leaderboard::delete_score,
+        leaderboard::get_bans,
+        leaderboard::ban_player,
+        leaderboard::unban_player,
+        transfer::request_transfer,
+        transfer::cancel_transfer,
+        transfer::get_own_transfers,
+        transfer::accept_transfer,
+        transfer::decline_transfer,
+        webhook::register_webhook,
+        webhook::get_webhooks,
+        webhook::delete_webhook,
+        webhook::get_deliveries,
+        organization::new_organization,
+        organization::get_organizations,
+        organization::delete_organization,
+        organization::get_members,
+        organization::set_member_role,
+        organization::remove_member,
+        organization::invite,
+        organization::get_invitations,
+        organization::cancel_invitation,
+        organization::get_own_invitations,
+        organization::accept_invitation,
+        audit::get_audit_log,
+        superadmin::get_admins,
+        superadmin::disable_admin,
+        superadmin::enable_admin,
+        healthcheck::health_check,
+        healthcheck::readiness,
+        v2::create_player,
+        v2::save_score,
+        v2::get_scores,
+    ),
+    // only sent in the live events, not referenced by a handler
+    components(schemas(live::PlayerRank)),
+    modifiers(&AdminToken),
+    tags(
+        (name = "players", description = "Players of the games"),
+        (name = "scores", description = "Scores sent and read by the games"),
+        (name = "v2", description = "Version 2 of the API used by games"),
+        (name = "oauth", description = "Login of the admins"),
+        (name = "admin", description = "Account of the current admin"),
+        (name = "leaderboards", description = "Leaderboards and their moderation"),
+        (name = "transfers", description = "Leaderboards moving to another admin"),
+        (name = "webhooks", description = "Notifications sent to other services"),
+        (name = "organizations", description = "Admins sharing leaderboards"),
+        (name = "superadmin", description = "Operators of the server"),
+        (name = "health", description = "Health of the server, for orchestrators"),
+    )
+)]
+pub struct ApiDoc;
+
+struct AdminToken;
+
+impl Modify for AdminToken {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        openapi
+            .components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(
+                "admin",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("biscuit")
+                        .description(Some("Token returned by the `/oauth` endpoints"))
+                        .build(),
+                ),
+            );
+    }
+}
+
+pub(crate) async fn openapi() -> impl Responder {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
@@ -0,0 +1,160 @@
+//! Token bucket rate limiting of the public endpoints.
+//!
+//! Each key (an IP address, or a player on a leaderboard) has a bucket holding up to `burst`
+//! requests, refilled by `per_minute` requests every minute. A request with an empty bucket is
+//! answered with `429 Too Many Requests` and a `Retry-After` header.
+//!
+//! Buckets are kept in memory, so each server counts the requests it receives on its own. The
+//! least recently used are forgotten first once there are too many.
+
+use std::{
+    hash::Hash,
+    net::IpAddr,
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use actix_web::{http::header, HttpRequest, HttpResponse};
+use ipnet::IpNet;
+use lru::LruCache;
+use uuid::Uuid;
+
+use crate::configuration::{parse_proxy, RateLimit, RateLimitSettings};
+
+/// Buckets kept by a limiter before the least recently used are forgotten.
+const MAX_BUCKETS: usize = 100_000;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+pub struct RateLimiter<K> {
+    limit: Option<RateLimit>,
+    buckets: Mutex<LruCache<K, Bucket>>,
+}
+
+impl<K: Hash + Eq> RateLimiter<K> {
+    pub fn new(limit: Option<RateLimit>) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(LruCache::new(NonZeroUsize::new(MAX_BUCKETS).unwrap())),
+        }
+    }
+
+    /// Take a token from the bucket of `key`, or get how long to wait until there is one.
+    pub fn check(&self, key: K) -> Result<(), Duration> {
+        let Some(limit) = self.limit else {
+            return Ok(());
+        };
+        let burst = limit.burst as f64;
+        let per_second = limit.per_minute as f64 / 60.0;
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.get_or_insert_mut(key, || Bucket {
+            tokens: burst,
+            updated: now,
+        });
+        bucket.tokens = (bucket.tokens
+            + now.duration_since(bucket.updated).as_secs_f64() * per_second)
+            .min(burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else if per_second > 0.0 {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / per_second))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+}
+
+/// The rate limiters of the server, from its [`RateLimitSettings`].
+pub struct RateLimits {
+    pub player_creation: RateLimiter<IpAddr>,
+    /// Keyed by leaderboard and player.
+    pub score_submission: RateLimiter<(Uuid, Uuid)>,
+    pub reads: RateLimiter<IpAddr>,
+    trusted_proxies: Vec<IpNet>,
+}
+
+impl RateLimits {
+    pub fn new(settings: &RateLimitSettings) -> Self {
+        Self {
+            player_creation: RateLimiter::new(settings.player_creation_per_ip),
+            score_submission: RateLimiter::new(settings.score_submission_per_player),
+            reads: RateLimiter::new(settings.reads_per_ip),
+            trusted_proxies: settings
+                .trusted_proxies
+                .iter()
+                .filter_map(|proxy| parse_proxy(proxy))
+                .collect(),
+        }
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|proxy| proxy.contains(&ip))
+    }
+
+    /// Address of the client sending the request. Behind trusted proxies, it's the right-most
+    /// address of `X-Forwarded-For` they didn't add, as the client can set the others.
+    pub fn client_ip(&self, request: &HttpRequest) -> Option<IpAddr> {
+        let mut client = request.peer_addr()?.ip();
+        let forwarded: Vec<&str> = request
+            .headers()
+            .get_all("X-Forwarded-For")
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect();
+        for hop in forwarded.into_iter().rev() {
+            if !self.is_trusted(client) {
+                break;
+            }
+            match hop.trim().parse() {
+                Ok(hop) => client = hop,
+                // past this, the addresses can't be trusted to be in order
+                Err(_) => break,
+            }
+        }
+        Some(client)
+    }
+
+    /// Check a limit keyed by the address of the client.
+    pub fn check_ip(
+        &self,
+        limiter: &RateLimiter<IpAddr>,
+        request: &HttpRequest,
+    ) -> Result<(), HttpResponse> {
+        self.retry_after_ip(limiter, request)
+            .map_err(too_many_requests)
+    }
+
+    /// Like [`Self::check_ip`], but returning how long to wait instead of a response.
+    pub fn retry_after_ip(
+        &self,
+        limiter: &RateLimiter<IpAddr>,
+        request: &HttpRequest,
+    ) -> Result<(), Duration> {
+        match self.client_ip(request) {
+            Some(ip) => limiter.check(ip),
+            None => Ok(()),
+        }
+    }
+}
+
+/// `429 Too Many Requests`, telling the client how many seconds to wait.
+pub fn too_many_requests(retry_after: Duration) -> HttpResponse {
+    HttpResponse::TooManyRequests()
+        .insert_header((header::RETRY_AFTER, retry_after_seconds(retry_after)))
+        .finish()
+}
+
+/// Value of the `Retry-After` header, in whole seconds.
+pub fn retry_after_seconds(retry_after: Duration) -> u64 {
+    let seconds = retry_after.as_secs_f64().ceil().min(u32::MAX as f64) as u64;
+    seconds.max(1)
+}
@@ -0,0 +1,114 @@
+//! The admin UI, a single page application served on every path not used by the API.
+//!
+//! Paths with an extension are files of the application, read from the directory in the
+//! settings or embedded in the binary. Any other path gets `index.html`, and the application
+//! does the routing. Paths that could leave the static directory are rejected.
+//!
+//! Files with a content hash in their name, like `main.1a2b3c4d.js`, never change and can be
+//! cached forever. The others are revalidated before each use.
+
+use std::path::{Path, PathBuf};
+
+use actix_files::NamedFile;
+use actix_web::{
+    http::header::{self, HeaderValue},
+    web, HttpRequest, HttpResponse, Responder,
+};
+
+use crate::configuration::{Settings, StaticFiles};
+
+/// Whether the files of the `static` directory are built into the binary.
+pub const EMBEDDED: bool = cfg!(feature = "embedded-ui");
+
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+const REVALIDATE: &str = "no-cache";
+
+#[cfg(feature = "embedded-ui")]
+#[derive(rust_embed::Embed)]
+#[folder = "static/"]
+struct Embedded;
+
+pub async fn spa(request: HttpRequest, config: web::Data<Settings>) -> HttpResponse {
+    let path = request.match_info().query("filename");
+    if !is_safe(path) {
+        return HttpResponse::BadRequest().finish();
+    }
+    let path = if Path::new(path).extension().is_some() {
+        path
+    } else {
+        "index.html"
+    };
+
+    let response = match &config.static_files {
+        StaticFiles::Directory(directory) => {
+            match NamedFile::open(PathBuf::from(&directory.path).join(path)) {
+                Ok(file) => file.respond_to(&request).map_into_boxed_body(),
+                Err(_) => return HttpResponse::NotFound().finish(),
+            }
+        }
+        StaticFiles::Embedded => embedded(&request, path),
+    };
+    with_cache_control(response, path)
+}
+
+/// Whether the path stays in the static directory: it's relative, and none of its segments are
+/// `..` or hidden files.
+fn is_safe(path: &str) -> bool {
+    !path.starts_with('/')
+        && path
+            .split('/')
+            .all(|segment| !segment.starts_with('.') && !segment.contains(['\\', ':', '\0']))
+}
+
+/// Whether the name of the file contains a content hash, as added by the build of the UI.
+fn is_hashed(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let parts = name.split('.').collect::<Vec<_>>();
+    parts.len() > 2
+        && parts[1..parts.len() - 1]
+            .iter()
+            .any(|part| part.len() >= 8 && part.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn with_cache_control(mut response: HttpResponse, path: &str) -> HttpResponse {
+    if response.status().is_success() || response.status().is_redirection() {
+        let cache_control = if is_hashed(path) {
+            IMMUTABLE
+        } else {
+            REVALIDATE
+        };
+        response.headers_mut().insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(cache_control),
+        );
+    }
+    response
+}
+
+#[cfg(feature = "embedded-ui")]
+fn embedded(request: &HttpRequest, path: &str) -> HttpResponse {
+    let Some(file) = Embedded::get(path) else {
+        return HttpResponse::NotFound().finish();
+    };
+    let etag = format!("\"{}\"", hex::encode(file.metadata.sha256_hash()));
+    let unchanged = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag));
+    if unchanged {
+        return HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .finish();
+    }
+    HttpResponse::Ok()
+        .content_type(file.metadata.mimetype())
+        .insert_header((header::ETAG, etag))
+        .body(file.data.into_owned())
+}
+
+#[cfg(not(feature = "embedded-ui"))]
+fn embedded(_request: &HttpRequest, _path: &str) -> HttpResponse {
+    // refused when loading the configuration
+    HttpResponse::NotFound().finish()
+}
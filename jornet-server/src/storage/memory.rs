@@ -0,0 +1,1097 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use serde_json::Value;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::domains::{
+    admin::{AdminAccount, GithubUser},
+    audit::{AuditAction, AuditEntry, AuditPage, AuditQuery, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE},
+    leaderboard::{Ban, Leaderboard, LeaderboardWithScoreCount},
+    organization::{Invitation, Member, Organization, OrganizationWithRole, Role},
+    player::Player,
+    score::{ModeratedScore, Score, ScoreInput, ScoreValue, SortDirection},
+    superadmin::AdminSummary,
+    transfer::{Transfer, TransferStatus},
+    webhook::{Delivery, DeliveryStatus, Webhook},
+};
+
+use super::{
+    AdminStore, AuditStore, LeaderboardStore, OrganizationStore, PlayerStore, PoolStats,
+    ScoreStore, StatusStore, TransferStore, WebhookStore,
+};
+
+/// Storage kept in memory, lost when the server stops. Meant for tests and for embedding a
+/// server in another process, without any database to set up.
+///
+/// Clones share the same data, so a clone can be kept to seed or inspect the storage used by a
+/// running server.
+#[derive(Clone, Default)]
+pub struct MemoryStorage {
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    players: HashMap<Uuid, Player>,
+    scores: Vec<StoredScore>,
+    leaderboards: Vec<StoredLeaderboard>,
+    bans: Vec<StoredBan>,
+    admins: HashMap<Uuid, bool>,
+    anonymous_admins: HashSet<Uuid>,
+    github: Vec<(GithubUser, Uuid)>,
+    organizations: Vec<Organization>,
+    members: Vec<(Uuid, Uuid, Role)>,
+    invitations: Vec<Invitation>,
+    audit_log: Vec<AuditEntry>,
+    transfers: Vec<Transfer>,
+    webhooks: Vec<Webhook>,
+    deliveries: Vec<Delivery>,
+}
+
+struct StoredScore {
+    id: Uuid,
+    leaderboard: Uuid,
+    score: ScoreValue,
+    player: Uuid,
+    meta: Option<String>,
+    timestamp: u64,
+}
+
+struct StoredBan {
+    leaderboard: Uuid,
+    player: Uuid,
+    banned_by: Uuid,
+}
+
+struct StoredLeaderboard {
+    leaderboard: Leaderboard,
+    organization: Uuid,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+}
+
+fn format_timestamp(timestamp: OffsetDateTime) -> String {
+    timestamp.format(&Rfc3339).unwrap()
+}
+
+fn format_unix_timestamp(timestamp: u64) -> String {
+    OffsetDateTime::from_unix_timestamp(timestamp as i64)
+        .map(format_timestamp)
+        .unwrap_or_default()
+}
+
+impl State {
+    fn moderated_score(&self, score: &StoredScore) -> ModeratedScore {
+        ModeratedScore {
+            id: score.id,
+            score: score.score,
+            meta: score.meta.clone(),
+            timestamp: format_unix_timestamp(score.timestamp),
+            player: score.player,
+            player_name: self
+                .players
+                .get(&score.player)
+                .map(|player| player.name.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn github_login(&self, admin: Uuid) -> Option<String> {
+        self.github
+            .iter()
+            .find(|(_, id)| *id == admin)
+            .map(|(github, _)| github.login.clone())
+    }
+
+    fn count_owners(&self, organization: Uuid) -> i64 {
+        self.members
+            .iter()
+            .filter(|(org, _, role)| *org == organization && *role == Role::Owner)
+            .count() as i64
+    }
+
+    fn delete_leaderboards_of(&mut self, organization: Uuid) {
+        let removed: Vec<Uuid> = self
+            .leaderboards
+            .iter()
+            .filter(|stored| stored.organization == organization)
+            .map(|stored| stored.leaderboard.id)
+            .collect();
+        self.scores
+            .retain(|score| !removed.contains(&score.leaderboard));
+        self.bans.retain(|ban| !removed.contains(&ban.leaderboard));
+        for leaderboard in removed {
+            self.delete_webhooks_of(leaderboard);
+        }
+        self.leaderboards
+            .retain(|stored| stored.organization != organization);
+    }
+
+    fn delete_webhooks_of(&mut self, leaderboard: Uuid) {
+        let removed: Vec<Uuid> = self
+            .webhooks
+            .iter()
+            .filter(|webhook| webhook.leaderboard == leaderboard)
+            .map(|webhook| webhook.id)
+            .collect();
+        self.deliveries
+            .retain(|delivery| !removed.contains(&delivery.webhook));
+        self.webhooks
+            .retain(|webhook| webhook.leaderboard != leaderboard);
+    }
+
+    fn delete_organization(&mut self, id: Uuid) {
+        self.invitations
+            .retain(|invitation| invitation.organization != id);
+        self.members
+            .retain(|(organization, _, _)| *organization != id);
+        self.organizations
+            .retain(|organization| organization.id != id);
+    }
+}
+
+#[async_trait]
+impl PlayerStore for MemoryStorage {
+    #[instrument(skip_all, fields(player = %player.id))]
+    async fn create_player(&self, player: &Player) -> bool {
+        let mut state = self.state();
+        if state.players.contains_key(&player.id) {
+            return false;
+        }
+        state.players.insert(player.id, player.clone());
+        true
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_player(&self, id: Uuid) -> Option<Player> {
+        self.state().players.get(&id).cloned()
+    }
+}
+
+#[async_trait]
+impl ScoreStore for MemoryStorage {
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_scores(&self, leaderboard: Uuid) -> Vec<Score> {
+        let state = self.state();
+        state
+            .scores
+            .iter()
+            .filter(|score| score.leaderboard == leaderboard)
+            .filter_map(|score| {
+                Some(Score {
+                    score: score.score,
+                    meta: score.meta.clone(),
+                    timestamp: format_unix_timestamp(score.timestamp),
+                    player: state.players.get(&score.player)?.name.clone(),
+                })
+            })
+            .collect()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_scores_for_moderation(&self, leaderboard: Uuid) -> Vec<ModeratedScore> {
+        let state = self.state();
+        state
+            .scores
+            .iter()
+            .filter(|score| score.leaderboard == leaderboard)
+            .filter(|score| state.players.contains_key(&score.player))
+            .map(|score| state.moderated_score(score))
+            .collect()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_ranked_scores(
+        &self,
+        leaderboard: Uuid,
+        direction: SortDirection,
+        offset: i64,
+        limit: i64,
+    ) -> Vec<ModeratedScore> {
+        let mut scores = self.get_scores_for_moderation(leaderboard).await;
+        scores.sort_by(|a, b| direction.compare(&a.score, &b.score));
+        scores
+            .into_iter()
+            .skip(offset.max(0) as usize)
+            .take(limit.max(0) as usize)
+            .collect()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn count_scores(&self, leaderboard: Uuid) -> i64 {
+        self.get_scores_for_moderation(leaderboard).await.len() as i64
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %score.player))]
+    async fn score_exists(&self, leaderboard: Uuid, score: &ScoreInput) -> bool {
+        self.state().scores.iter().any(|stored| {
+            stored.leaderboard == leaderboard
+                && stored.player == score.player
+                && stored.score == score.score
+                && stored.timestamp == score.timestamp
+        })
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %score.player))]
+    async fn insert_score(&self, leaderboard: Uuid, score: &ScoreInput) -> bool {
+        let mut state = self.state();
+        // same constraints as the foreign keys of the SQL backends
+        if !state.players.contains_key(&score.player)
+            || !state
+                .leaderboards
+                .iter()
+                .any(|stored| stored.leaderboard.id == leaderboard)
+        {
+            return false;
+        }
+        state.scores.push(StoredScore {
+            id: Uuid::new_v4(),
+            leaderboard,
+            score: score.score,
+            player: score.player,
+            meta: score.meta.clone(),
+            timestamp: score.timestamp,
+        });
+        true
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, id = %id))]
+    async fn delete_score(&self, leaderboard: Uuid, id: Uuid) -> Option<ModeratedScore> {
+        let mut state = self.state();
+        let index = state
+            .scores
+            .iter()
+            .position(|score| score.leaderboard == leaderboard && score.id == id)?;
+        let score = state.scores.remove(index);
+        Some(ModeratedScore {
+            player_name: String::new(),
+            ..state.moderated_score(&score)
+        })
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn delete_all_scores(&self, leaderboard: Uuid) -> Option<u64> {
+        let mut state = self.state();
+        let before = state.scores.len();
+        state
+            .scores
+            .retain(|score| score.leaderboard != leaderboard);
+        Some((before - state.scores.len()) as u64)
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_best_score(
+        &self,
+        leaderboard: Uuid,
+        direction: SortDirection,
+    ) -> Option<ScoreValue> {
+        self.state()
+            .scores
+            .iter()
+            .filter(|score| score.leaderboard == leaderboard)
+            .map(|score| score.score)
+            .min_by(|a, b| direction.compare(a, b))
+    }
+}
+
+#[async_trait]
+impl LeaderboardStore for MemoryStorage {
+    #[instrument(skip_all, fields(admin = %admin))]
+    async fn get_leaderboards(&self, admin: Uuid) -> Vec<LeaderboardWithScoreCount> {
+        let state = self.state();
+        state
+            .leaderboards
+            .iter()
+            .filter_map(|stored| {
+                let role = state
+                    .members
+                    .iter()
+                    .find_map(|(organization, member, role)| {
+                        (*organization == stored.organization && *member == admin).then_some(*role)
+                    })?;
+                Some(LeaderboardWithScoreCount {
+                    id: stored.leaderboard.id,
+                    name: stored.leaderboard.name.clone(),
+                    organization: stored.organization,
+                    role,
+                    score_type: stored.leaderboard.score_type,
+                    scores: state
+                        .scores
+                        .iter()
+                        .filter(|score| score.leaderboard == stored.leaderboard.id)
+                        .count() as i64,
+                })
+            })
+            .collect()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin))]
+    async fn count_owned_leaderboards(&self, admin: Uuid) -> i64 {
+        let state = self.state();
+        state
+            .leaderboards
+            .iter()
+            .filter(|stored| {
+                state.members.iter().any(|(organization, member, role)| {
+                    *organization == stored.organization && *member == admin && *role == Role::Owner
+                })
+            })
+            .count() as i64
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn count_leaderboards_in_organization(&self, organization: Uuid) -> i64 {
+        self.state()
+            .leaderboards
+            .iter()
+            .filter(|stored| stored.organization == organization)
+            .count() as i64
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_leaderboard(&self, id: Uuid) -> Option<Leaderboard> {
+        self.state()
+            .leaderboards
+            .iter()
+            .find(|stored| stored.leaderboard.id == id)
+            .map(|stored| stored.leaderboard.clone())
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_leaderboard_key(&self, id: Uuid) -> Option<Uuid> {
+        self.get_leaderboard(id)
+            .await
+            .map(|leaderboard| leaderboard.key)
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_leaderboard_organization(&self, id: Uuid) -> Option<Uuid> {
+        self.state()
+            .leaderboards
+            .iter()
+            .find(|stored| stored.leaderboard.id == id)
+            .map(|stored| stored.organization)
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard.id, organization = %organization))]
+    async fn create_leaderboard(&self, leaderboard: &Leaderboard, organization: Uuid) -> bool {
+        let mut state = self.state();
+        if state
+            .leaderboards
+            .iter()
+            .any(|stored| stored.leaderboard.id == leaderboard.id)
+        {
+            return false;
+        }
+        state.leaderboards.push(StoredLeaderboard {
+            leaderboard: leaderboard.clone(),
+            organization,
+        });
+        true
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn set_leaderboard_key(&self, id: Uuid, key: Uuid) -> bool {
+        let mut state = self.state();
+        if let Some(stored) = state
+            .leaderboards
+            .iter_mut()
+            .find(|stored| stored.leaderboard.id == id)
+        {
+            stored.leaderboard.key = key;
+        }
+        true
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn delete_leaderboard(&self, id: Uuid) -> bool {
+        let mut state = self.state();
+        state.scores.retain(|score| score.leaderboard != id);
+        state.bans.retain(|ban| ban.leaderboard != id);
+        state.delete_webhooks_of(id);
+        state
+            .leaderboards
+            .retain(|stored| stored.leaderboard.id != id);
+        true
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %player, banned_by = %banned_by))]
+    async fn ban_player(&self, leaderboard: Uuid, player: Uuid, banned_by: Uuid) -> Option<u64> {
+        let mut state = self.state();
+        if !state
+            .bans
+            .iter()
+            .any(|ban| ban.leaderboard == leaderboard && ban.player == player)
+        {
+            state.bans.push(StoredBan {
+                leaderboard,
+                player,
+                banned_by,
+            });
+        }
+        let before = state.scores.len();
+        state
+            .scores
+            .retain(|score| !(score.leaderboard == leaderboard && score.player == player));
+        Some((before - state.scores.len()) as u64)
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %player))]
+    async fn unban_player(&self, leaderboard: Uuid, player: Uuid) -> bool {
+        self.state()
+            .bans
+            .retain(|ban| !(ban.leaderboard == leaderboard && ban.player == player));
+        true
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %player))]
+    async fn is_banned(&self, leaderboard: Uuid, player: Uuid) -> bool {
+        self.state()
+            .bans
+            .iter()
+            .any(|ban| ban.leaderboard == leaderboard && ban.player == player)
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_bans(&self, leaderboard: Uuid) -> Vec<Ban> {
+        let state = self.state();
+        state
+            .bans
+            .iter()
+            .filter(|ban| ban.leaderboard == leaderboard)
+            .filter_map(|ban| {
+                Some(Ban {
+                    player: ban.player,
+                    name: state.players.get(&ban.player)?.name.clone(),
+                    banned_by: ban.banned_by,
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl AdminStore for MemoryStorage {
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn create_admin(&self, admin: &AdminAccount) -> bool {
+        let mut state = self.state();
+        if state.admins.contains_key(&admin.id) {
+            return false;
+        }
+        state.admins.insert(admin.id, false);
+        true
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn create_anonymous_admin(&self, admin: &AdminAccount) -> bool {
+        if !self.create_admin(admin).await {
+            return false;
+        }
+        self.state().anonymous_admins.insert(admin.id);
+        true
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn admin_exists(&self, admin: &AdminAccount) -> bool {
+        self.state().admins.contains_key(&admin.id)
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn is_admin_anonymous(&self, admin: &AdminAccount) -> bool {
+        self.state().anonymous_admins.contains(&admin.id)
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn is_admin_active(&self, admin: &AdminAccount) -> bool {
+        self.state().admins.get(&admin.id) == Some(&false)
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn set_admin_disabled(&self, admin: &AdminAccount, disabled: bool) -> bool {
+        match self.state().admins.get_mut(&admin.id) {
+            Some(current) => {
+                *current = disabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[instrument(skip_all)]
+    async fn get_admins(&self) -> Vec<AdminSummary> {
+        let state = self.state();
+        state
+            .admins
+            .iter()
+            .map(|(id, disabled)| AdminSummary {
+                id: *id,
+                disabled: *disabled,
+                github: state.github_login(*id),
+            })
+            .collect()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id, transfer_to = ?transfer_to))]
+    async fn delete_admin(&self, admin: &AdminAccount, transfer_to: Option<Uuid>) -> bool {
+        let mut state = self.state();
+        let owned: Vec<(Uuid, bool)> = state
+            .organizations
+            .iter()
+            .filter(|organization| {
+                state.members.iter().any(|(org, member, role)| {
+                    *org == organization.id && *member == admin.id && *role == Role::Owner
+                }) && state.count_owners(organization.id) == 1
+            })
+            .map(|organization| (organization.id, organization.personal))
+            .collect();
+
+        for (organization, personal) in owned {
+            match (transfer_to, personal) {
+                (Some(recipient), true) => {
+                    for stored in state
+                        .leaderboards
+                        .iter_mut()
+                        .filter(|stored| stored.organization == organization)
+                    {
+                        stored.organization = recipient;
+                    }
+                }
+                (Some(recipient), false) => {
+                    state
+                        .members
+                        .retain(|(org, member, _)| !(*org == organization && *member == recipient));
+                    state.members.push((organization, recipient, Role::Owner));
+                }
+                (None, _) => state.delete_leaderboards_of(organization),
+            }
+            // a transferred organization keeps existing, unless it was personal
+            if transfer_to.is_none() || personal {
+                state.delete_organization(organization);
+            }
+        }
+
+        for transfer in state.transfers.iter_mut().filter(|transfer| {
+            transfer.to_admin == admin.id && transfer.status == TransferStatus::Pending
+        }) {
+            transfer.status = TransferStatus::Cancelled;
+        }
+        state.members.retain(|(_, member, _)| *member != admin.id);
+        state.github.retain(|(_, id)| *id != admin.id);
+        state.admins.remove(&admin.id);
+        state.anonymous_admins.remove(&admin.id);
+        true
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn get_admin_github(&self, admin: &AdminAccount) -> Option<GithubUser> {
+        self.state()
+            .github
+            .iter()
+            .find(|(_, id)| *id == admin.id)
+            .map(|(github, _)| github.clone())
+    }
+
+    #[instrument(skip_all, fields(github = %github.login, admin = %admin.id))]
+    async fn link_github(&self, github: &GithubUser, admin: &AdminAccount) -> bool {
+        let mut state = self.state();
+        if state
+            .github
+            .iter()
+            .any(|(linked, _)| linked.id == github.id)
+        {
+            return false;
+        }
+        state.github.push((github.clone(), admin.id));
+        true
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn unlink_github(&self, admin: &AdminAccount) -> bool {
+        self.state().github.retain(|(_, id)| *id != admin.id);
+        true
+    }
+
+    #[instrument(skip_all, fields(github = %github.login))]
+    async fn github_exists(&self, github: &GithubUser) -> bool {
+        self.get_github_admin(github).await.is_some()
+    }
+
+    #[instrument(skip_all, fields(github = %github.login))]
+    async fn get_github_admin(&self, github: &GithubUser) -> Option<AdminAccount> {
+        self.state()
+            .github
+            .iter()
+            .find(|(linked, _)| linked.id == github.id && linked.login == github.login)
+            .map(|(_, id)| AdminAccount { id: *id })
+    }
+}
+
+#[async_trait]
+impl OrganizationStore for MemoryStorage {
+    #[instrument(skip_all, fields(organization = %organization.id, owner = %owner))]
+    async fn create_organization(&self, organization: &Organization, owner: Uuid) -> bool {
+        let mut state = self.state();
+        if state
+            .organizations
+            .iter()
+            .any(|existing| existing.id == organization.id)
+        {
+            return false;
+        }
+        state.organizations.push(organization.clone());
+        state.members.push((organization.id, owner, Role::Owner));
+        true
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_organization(&self, id: Uuid) -> Option<Organization> {
+        self.state()
+            .organizations
+            .iter()
+            .find(|organization| organization.id == id)
+            .cloned()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin))]
+    async fn get_organizations(&self, admin: Uuid) -> Vec<OrganizationWithRole> {
+        let state = self.state();
+        state
+            .organizations
+            .iter()
+            .filter_map(|organization| {
+                let role = state.members.iter().find_map(|(org, member, role)| {
+                    (*org == organization.id && *member == admin).then_some(*role)
+                })?;
+                Some(OrganizationWithRole {
+                    id: organization.id,
+                    name: organization.name.clone(),
+                    personal: organization.personal,
+                    role,
+                })
+            })
+            .collect()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn delete_organization(&self, id: Uuid) -> bool {
+        self.state().delete_organization(id);
+        true
+    }
+
+    #[instrument(skip_all, fields(organization = %organization, admin = %admin))]
+    async fn get_role(&self, organization: Uuid, admin: Uuid) -> Option<Role> {
+        self.state().members.iter().find_map(|(org, member, role)| {
+            (*org == organization && *member == admin).then_some(*role)
+        })
+    }
+
+    #[instrument(skip_all, fields(organization = %organization, admin = %admin))]
+    async fn set_role(&self, organization: Uuid, admin: Uuid, role: Role) -> bool {
+        for member in self
+            .state()
+            .members
+            .iter_mut()
+            .filter(|(org, member, _)| *org == organization && *member == admin)
+        {
+            member.2 = role;
+        }
+        true
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn get_members(&self, organization: Uuid) -> Vec<Member> {
+        let state = self.state();
+        state
+            .members
+            .iter()
+            .filter(|(org, _, _)| *org == organization)
+            .map(|(_, admin, role)| Member {
+                admin: *admin,
+                role: *role,
+                github: state.github_login(*admin),
+            })
+            .collect()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn count_owners(&self, organization: Uuid) -> i64 {
+        self.state().count_owners(organization)
+    }
+
+    #[instrument(skip_all, fields(organization = %organization, admin = %admin))]
+    async fn remove_member(&self, organization: Uuid, admin: Uuid) -> bool {
+        self.state()
+            .members
+            .retain(|(org, member, _)| !(*org == organization && *member == admin));
+        true
+    }
+
+    #[instrument(skip_all, fields(invitation = %invitation.id, organization = %invitation.organization))]
+    async fn create_invitation(&self, invitation: &Invitation) -> bool {
+        let mut state = self.state();
+        if state
+            .invitations
+            .iter()
+            .any(|existing| existing.id == invitation.id)
+        {
+            return false;
+        }
+        state.invitations.push(invitation.clone());
+        true
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_invitation(&self, id: Uuid) -> Option<Invitation> {
+        self.state()
+            .invitations
+            .iter()
+            .find(|invitation| invitation.id == id)
+            .cloned()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn get_invitations_for_organization(&self, organization: Uuid) -> Vec<Invitation> {
+        self.state()
+            .invitations
+            .iter()
+            .filter(|invitation| invitation.organization == organization)
+            .cloned()
+            .collect()
+    }
+
+    #[instrument(skip_all, fields(login))]
+    async fn get_invitations_for_github(&self, login: &str) -> Vec<Invitation> {
+        self.state()
+            .invitations
+            .iter()
+            .filter(|invitation| {
+                invitation
+                    .github_login
+                    .as_deref()
+                    .is_some_and(|invited| invited.eq_ignore_ascii_case(login))
+            })
+            .cloned()
+            .collect()
+    }
+
+    #[instrument(skip_all, fields(invitation = %invitation.id, organization = %invitation.organization, admin = %admin))]
+    async fn accept_invitation(&self, invitation: &Invitation, admin: Uuid) -> bool {
+        let mut state = self.state();
+        if !state
+            .members
+            .iter()
+            .any(|(org, member, _)| *org == invitation.organization && *member == admin)
+        {
+            state
+                .members
+                .push((invitation.organization, admin, invitation.role));
+        }
+        state
+            .invitations
+            .retain(|existing| existing.id != invitation.id);
+        true
+    }
+
+    #[instrument(skip_all, fields(organization = %organization, id = %id))]
+    async fn delete_invitation(&self, organization: Uuid, id: Uuid) -> bool {
+        self.state()
+            .invitations
+            .retain(|invitation| !(invitation.organization == organization && invitation.id == id));
+        true
+    }
+}
+
+#[async_trait]
+impl AuditStore for MemoryStorage {
+    #[instrument(skip_all, fields(actor = %actor, organization = ?organization, target = ?target))]
+    async fn record_audit(
+        &self,
+        actor: Uuid,
+        organization: Option<Uuid>,
+        action: AuditAction,
+        target: Option<Uuid>,
+        payload: Value,
+    ) -> bool {
+        let mut state = self.state();
+        let id = state.audit_log.len() as i64 + 1;
+        state.audit_log.push(AuditEntry {
+            id,
+            timestamp: format_timestamp(OffsetDateTime::now_utc()),
+            actor,
+            organization,
+            action: action.as_str().to_string(),
+            target,
+            payload: (!payload.is_null()).then_some(payload),
+        });
+        true
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn get_audit_page(&self, organization: Uuid, query: &AuditQuery) -> AuditPage {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query
+            .per_page
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        let action = query.action.map(|action| action.as_str());
+
+        let state = self.state();
+        let matching: Vec<&AuditEntry> = state
+            .audit_log
+            .iter()
+            .rev()
+            .filter(|entry| entry.organization == Some(organization))
+            .filter(|entry| query.actor.is_none_or(|actor| entry.actor == actor))
+            .filter(|entry| action.is_none_or(|action| entry.action == action))
+            .filter(|entry| {
+                query
+                    .target
+                    .is_none_or(|target| entry.target == Some(target))
+            })
+            .collect();
+
+        AuditPage {
+            total: matching.len() as i64,
+            entries: matching
+                .into_iter()
+                .skip(((page - 1) * per_page) as usize)
+                .take(per_page as usize)
+                .cloned()
+                .collect(),
+            page,
+            per_page,
+        }
+    }
+}
+
+#[async_trait]
+impl TransferStore for MemoryStorage {
+    #[instrument(skip_all, fields(transfer = %transfer.id, leaderboard = %transfer.leaderboard))]
+    async fn create_transfer(&self, transfer: &Transfer) -> bool {
+        let mut state = self.state();
+        if state
+            .transfers
+            .iter()
+            .any(|existing| existing.id == transfer.id)
+        {
+            return false;
+        }
+        state.transfers.push(transfer.clone());
+        true
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_transfer(&self, id: Uuid) -> Option<Transfer> {
+        self.state()
+            .transfers
+            .iter()
+            .find(|transfer| transfer.id == id)
+            .cloned()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_pending_transfer_for_leaderboard(&self, leaderboard: Uuid) -> Option<Transfer> {
+        self.state()
+            .transfers
+            .iter()
+            .find(|transfer| {
+                transfer.leaderboard == leaderboard && transfer.status == TransferStatus::Pending
+            })
+            .cloned()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin))]
+    async fn get_pending_transfers_for_admin(&self, admin: Uuid) -> Vec<Transfer> {
+        self.state()
+            .transfers
+            .iter()
+            .filter(|transfer| {
+                transfer.to_admin == admin && transfer.status == TransferStatus::Pending
+            })
+            .cloned()
+            .collect()
+    }
+
+    #[instrument(skip_all, fields(transfer = %transfer.id, leaderboard = %transfer.leaderboard))]
+    async fn resolve_transfer(&self, transfer: &Transfer, status: TransferStatus) -> bool {
+        match self.state().transfers.iter_mut().find(|existing| {
+            existing.id == transfer.id && existing.status == TransferStatus::Pending
+        }) {
+            Some(existing) => {
+                existing.status = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[instrument(skip_all, fields(transfer = %transfer.id, leaderboard = %transfer.leaderboard, organization = %organization))]
+    async fn accept_transfer(&self, transfer: &Transfer, organization: Uuid) -> Option<bool> {
+        let mut state = self.state();
+        let moved = match state.leaderboards.iter_mut().find(|stored| {
+            stored.leaderboard.id == transfer.leaderboard
+                && stored.organization == transfer.from_organization
+        }) {
+            Some(stored) => {
+                stored.organization = organization;
+                true
+            }
+            None => false,
+        };
+        if let Some(existing) = state
+            .transfers
+            .iter_mut()
+            .find(|existing| existing.id == transfer.id)
+        {
+            existing.status = if moved {
+                TransferStatus::Accepted
+            } else {
+                TransferStatus::Cancelled
+            };
+        }
+        Some(moved)
+    }
+}
+
+#[async_trait]
+impl WebhookStore for MemoryStorage {
+    #[instrument(skip_all, fields(webhook = %webhook.id, leaderboard = %webhook.leaderboard))]
+    async fn create_webhook(&self, webhook: &Webhook) -> bool {
+        let mut state = self.state();
+        if !state
+            .leaderboards
+            .iter()
+            .any(|stored| stored.leaderboard.id == webhook.leaderboard)
+        {
+            return false;
+        }
+        state.webhooks.push(webhook.clone());
+        true
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_webhook(&self, id: Uuid) -> Option<Webhook> {
+        self.state()
+            .webhooks
+            .iter()
+            .find(|webhook| webhook.id == id)
+            .cloned()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_webhooks(&self, leaderboard: Uuid) -> Vec<Webhook> {
+        self.state()
+            .webhooks
+            .iter()
+            .filter(|webhook| webhook.leaderboard == leaderboard)
+            .cloned()
+            .collect()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, id = %id))]
+    async fn delete_webhook(&self, leaderboard: Uuid, id: Uuid) -> bool {
+        let mut state = self.state();
+        let before = state.webhooks.len();
+        state
+            .webhooks
+            .retain(|webhook| !(webhook.id == id && webhook.leaderboard == leaderboard));
+        if state.webhooks.len() == before {
+            return false;
+        }
+        state.deliveries.retain(|delivery| delivery.webhook != id);
+        true
+    }
+
+    #[instrument(skip_all, fields(delivery = %delivery.id, webhook = %delivery.webhook))]
+    async fn create_delivery(&self, delivery: &Delivery) -> bool {
+        let mut state = self.state();
+        if !state
+            .webhooks
+            .iter()
+            .any(|webhook| webhook.id == delivery.webhook)
+        {
+            return false;
+        }
+        state.deliveries.push(Delivery {
+            created_at: format_timestamp(OffsetDateTime::now_utc()),
+            last_attempt_at: None,
+            ..delivery.clone()
+        });
+        true
+    }
+
+    #[instrument(skip_all, fields(delivery = %delivery.id, webhook = %delivery.webhook))]
+    async fn update_delivery(&self, delivery: &Delivery) -> bool {
+        match self
+            .state()
+            .deliveries
+            .iter_mut()
+            .find(|existing| existing.id == delivery.id)
+        {
+            Some(existing) => {
+                existing.status = delivery.status;
+                existing.attempts = delivery.attempts;
+                existing.response_status = delivery.response_status;
+                existing.error = delivery.error.clone();
+                existing.last_attempt_at = Some(format_timestamp(OffsetDateTime::now_utc()));
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[instrument(skip_all, fields(webhook = %webhook))]
+    async fn get_deliveries(&self, webhook: Uuid, limit: i64) -> Vec<Delivery> {
+        self.state()
+            .deliveries
+            .iter()
+            .rev()
+            .filter(|delivery| delivery.webhook == webhook)
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect()
+    }
+
+    #[instrument(skip_all)]
+    async fn get_pending_deliveries(&self) -> Vec<Delivery> {
+        self.state()
+            .deliveries
+            .iter()
+            .filter(|delivery| delivery.status == DeliveryStatus::Pending)
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl StatusStore for MemoryStorage {
+    fn pool_stats(&self) -> Option<PoolStats> {
+        None
+    }
+
+    async fn ping(&self) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    async fn schema_version(&self) -> Result<Option<i64>, sqlx::Error> {
+        Ok(None)
+    }
+
+    fn expected_schema_version(&self) -> Option<i64> {
+        None
+    }
+}
@@ -0,0 +1,147 @@
+//! Schema migrations of the SQL backends, applied at startup when `auto_migrate` is set in the
+//! configuration, or with `jornet-cli migrate`.
+
+use std::{borrow::Cow, collections::HashMap};
+
+use serde::Serialize;
+use sqlx::{
+    migrate::{Migrate, MigrateError, Migrator},
+    Connection, PgConnection,
+};
+
+use crate::configuration::{Settings, StorageBackend};
+
+use super::{postgres, sqlite, SqliteStorage};
+
+/// A migration known to the server, and whether it has been applied to the database.
+#[derive(Debug, Serialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but the file changed since.
+    Modified,
+    /// Started but didn't complete, it must be fixed manually.
+    Failed,
+}
+
+impl MigrationState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MigrationState::Applied => "applied",
+            MigrationState::Pending => "pending",
+            MigrationState::Modified => "modified",
+            MigrationState::Failed => "failed",
+        }
+    }
+}
+
+/// Status of the migrations of `migrator` against the database. It only reads, the table of
+/// applied migrations isn't created if it doesn't exist yet.
+pub(super) async fn status_of<C: Migrate>(
+    connection: &mut C,
+    migrator: &Migrator,
+    has_migrations_table: bool,
+) -> Result<Vec<MigrationStatus>, MigrateError> {
+    if !has_migrations_table {
+        return Ok(status_from(migrator, None, &HashMap::new()));
+    }
+    let dirty = connection.dirty_version().await?;
+    let applied: HashMap<_, _> = connection
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| (migration.version, migration.checksum))
+        .collect();
+
+    Ok(status_from(migrator, dirty, &applied))
+}
+
+fn status_from(
+    migrator: &Migrator,
+    dirty: Option<i64>,
+    applied: &HashMap<i64, Cow<'static, [u8]>>,
+) -> Vec<MigrationStatus> {
+    migrator
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            description: migration.description.to_string(),
+            state: match applied.get(&migration.version) {
+                _ if dirty == Some(migration.version) => MigrationState::Failed,
+                Some(checksum) if *checksum == migration.checksum => MigrationState::Applied,
+                Some(_) => MigrationState::Modified,
+                None => MigrationState::Pending,
+            },
+        })
+        .collect()
+}
+
+/// State of all the migrations of the configured storage, without writing to it. The in-memory
+/// storage doesn't have any.
+pub async fn status(configuration: &Settings) -> Result<Vec<MigrationStatus>, MigrateError> {
+    match &configuration.storage {
+        StorageBackend::Postgres => {
+            let mut connection =
+                PgConnection::connect(&configuration.database.connection_string()).await?;
+            let has_migrations_table = postgres::has_migrations_table(&mut connection).await?;
+            status_of(&mut connection, &postgres::MIGRATOR, has_migrations_table).await
+        }
+        StorageBackend::Sqlite(settings) => {
+            match SqliteStorage::connect_read_only(&settings.filename).await? {
+                Some(mut connection) => {
+                    let has_migrations_table =
+                        sqlite::has_migrations_table(&mut connection).await?;
+                    status_of(&mut connection, &sqlite::MIGRATOR, has_migrations_table).await
+                }
+                // nothing has been applied to a database that doesn't exist yet
+                None => Ok(status_from(&sqlite::MIGRATOR, None, &HashMap::new())),
+            }
+        }
+        StorageBackend::Memory => Ok(vec![]),
+    }
+}
+
+/// Apply the pending migrations of the configured storage, and return them. With `dry_run`, they
+/// are only returned.
+pub async fn apply(
+    configuration: &Settings,
+    dry_run: bool,
+) -> Result<Vec<MigrationStatus>, MigrateError> {
+    let pending: Vec<_> = status(configuration)
+        .await?
+        .into_iter()
+        .filter(|migration| migration.state == MigrationState::Pending)
+        .collect();
+    if dry_run || pending.is_empty() {
+        return Ok(pending);
+    }
+
+    match &configuration.storage {
+        StorageBackend::Postgres => {
+            let mut connection =
+                PgConnection::connect(&configuration.database.connection_string()).await?;
+            postgres::MIGRATOR.run(&mut connection).await?;
+        }
+        StorageBackend::Sqlite(settings) => {
+            let storage = SqliteStorage::connect(&settings.filename).await?;
+            storage.migrate().await?;
+        }
+        StorageBackend::Memory => (),
+    }
+    Ok(pending
+        .into_iter()
+        .map(|migration| MigrationStatus {
+            state: MigrationState::Applied,
+            ..migration
+        })
+        .collect())
+}
@@ -0,0 +1,276 @@
+//! Persistence of the server state. Handlers only talk to a [`Storage`], and the backend is
+//! selected in the configuration.
+
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::{migrate::MigrateError, pool::PoolOptions, Database};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    configuration::{PoolSettings, Settings, StorageBackend},
+    domains::{
+        admin::{AdminAccount, GithubUser},
+        audit::{AuditAction, AuditPage, AuditQuery},
+        leaderboard::{Ban, Leaderboard, LeaderboardWithScoreCount},
+        organization::{Invitation, Member, Organization, OrganizationWithRole, Role},
+        player::Player,
+        score::{ModeratedScore, Score, ScoreInput, ScoreValue, SortDirection},
+        superadmin::AdminSummary,
+        transfer::{Transfer, TransferStatus},
+        webhook::{Delivery, Webhook},
+    },
+};
+
+mod memory;
+pub mod migrations;
+mod postgres;
+mod sqlite;
+
+pub use memory::MemoryStorage;
+pub use postgres::PostgresStorage;
+pub use sqlite::SqliteStorage;
+
+/// Log a failed query before the database backends turn it into a `bool` or an `Option`. Not
+/// finding a row is an expected answer and isn't logged. The fields of the storage span say
+/// what the query was about.
+fn log_error(error: &sqlx::Error) {
+    if !matches!(error, sqlx::Error::RowNotFound) {
+        error!(%error, "storage query failed");
+    }
+}
+
+#[async_trait]
+pub trait PlayerStore {
+    async fn create_player(&self, player: &Player) -> bool;
+    async fn get_player(&self, id: Uuid) -> Option<Player>;
+}
+
+#[async_trait]
+pub trait ScoreStore {
+    async fn get_scores(&self, leaderboard: Uuid) -> Vec<Score>;
+    async fn get_scores_for_moderation(&self, leaderboard: Uuid) -> Vec<ModeratedScore>;
+    /// A page of the scores of a leaderboard, from the best.
+    async fn get_ranked_scores(
+        &self,
+        leaderboard: Uuid,
+        direction: SortDirection,
+        offset: i64,
+        limit: i64,
+    ) -> Vec<ModeratedScore>;
+    async fn count_scores(&self, leaderboard: Uuid) -> i64;
+    /// Check if the exact same score was already sent, to refuse replays.
+    async fn score_exists(&self, leaderboard: Uuid, score: &ScoreInput) -> bool;
+    async fn insert_score(&self, leaderboard: Uuid, score: &ScoreInput) -> bool;
+    /// Delete a score from a leaderboard, returning it if it existed.
+    async fn delete_score(&self, leaderboard: Uuid, id: Uuid) -> Option<ModeratedScore>;
+    /// Delete all scores of a leaderboard, returning how many were deleted.
+    async fn delete_all_scores(&self, leaderboard: Uuid) -> Option<u64>;
+    async fn get_best_score(
+        &self,
+        leaderboard: Uuid,
+        direction: SortDirection,
+    ) -> Option<ScoreValue>;
+}
+
+#[async_trait]
+pub trait LeaderboardStore {
+    /// Get the leaderboards of all the organizations an admin is a member of.
+    async fn get_leaderboards(&self, admin: Uuid) -> Vec<LeaderboardWithScoreCount>;
+    /// Count the leaderboards in all the organizations owned by an admin.
+    async fn count_owned_leaderboards(&self, admin: Uuid) -> i64;
+    async fn count_leaderboards_in_organization(&self, organization: Uuid) -> i64;
+    async fn get_leaderboard(&self, id: Uuid) -> Option<Leaderboard>;
+    async fn get_leaderboard_key(&self, id: Uuid) -> Option<Uuid>;
+    async fn get_leaderboard_organization(&self, id: Uuid) -> Option<Uuid>;
+    async fn create_leaderboard(&self, leaderboard: &Leaderboard, organization: Uuid) -> bool;
+    async fn set_leaderboard_key(&self, id: Uuid, key: Uuid) -> bool;
+    /// Delete a leaderboard with its scores, bans and webhooks.
+    async fn delete_leaderboard(&self, id: Uuid) -> bool;
+    /// Ban a player from a leaderboard, removing their scores. Returns how many scores were
+    /// removed.
+    async fn ban_player(&self, leaderboard: Uuid, player: Uuid, banned_by: Uuid) -> Option<u64>;
+    async fn unban_player(&self, leaderboard: Uuid, player: Uuid) -> bool;
+    async fn is_banned(&self, leaderboard: Uuid, player: Uuid) -> bool;
+    async fn get_bans(&self, leaderboard: Uuid) -> Vec<Ban>;
+}
+
+#[async_trait]
+pub trait AdminStore {
+    async fn create_admin(&self, admin: &AdminAccount) -> bool;
+    /// An account created by `/oauth/anonymous`, which can log in again by UUID.
+    async fn create_anonymous_admin(&self, admin: &AdminAccount) -> bool;
+    async fn admin_exists(&self, admin: &AdminAccount) -> bool;
+    async fn is_admin_anonymous(&self, admin: &AdminAccount) -> bool;
+    /// An account can be used if it exists and hasn't been disabled by a superadmin.
+    async fn is_admin_active(&self, admin: &AdminAccount) -> bool;
+    async fn set_admin_disabled(&self, admin: &AdminAccount, disabled: bool) -> bool;
+    async fn get_admins(&self) -> Vec<AdminSummary>;
+    /// Delete the account. Organizations where it's the only owner are given to `transfer_to`,
+    /// or deleted with their leaderboards. Leaderboards of the personal organization move to the
+    /// personal organization of `transfer_to`, which must already exist.
+    async fn delete_admin(&self, admin: &AdminAccount, transfer_to: Option<Uuid>) -> bool;
+    async fn get_admin_github(&self, admin: &AdminAccount) -> Option<GithubUser>;
+    async fn link_github(&self, github: &GithubUser, admin: &AdminAccount) -> bool;
+    async fn unlink_github(&self, admin: &AdminAccount) -> bool;
+    async fn github_exists(&self, github: &GithubUser) -> bool;
+    async fn get_github_admin(&self, github: &GithubUser) -> Option<AdminAccount>;
+}
+
+#[async_trait]
+pub trait OrganizationStore {
+    /// Create an organization, with `owner` as its first member.
+    async fn create_organization(&self, organization: &Organization, owner: Uuid) -> bool;
+    async fn get_organization(&self, id: Uuid) -> Option<Organization>;
+    async fn get_organizations(&self, admin: Uuid) -> Vec<OrganizationWithRole>;
+    /// Delete an organization with its members and invitations.
+    async fn delete_organization(&self, id: Uuid) -> bool;
+    async fn get_role(&self, organization: Uuid, admin: Uuid) -> Option<Role>;
+    async fn set_role(&self, organization: Uuid, admin: Uuid, role: Role) -> bool;
+    async fn get_members(&self, organization: Uuid) -> Vec<Member>;
+    async fn count_owners(&self, organization: Uuid) -> i64;
+    async fn remove_member(&self, organization: Uuid, admin: Uuid) -> bool;
+    async fn create_invitation(&self, invitation: &Invitation) -> bool;
+    async fn get_invitation(&self, id: Uuid) -> Option<Invitation>;
+    async fn get_invitations_for_organization(&self, organization: Uuid) -> Vec<Invitation>;
+    async fn get_invitations_for_github(&self, login: &str) -> Vec<Invitation>;
+    /// Add the admin to the organization and remove the invitation. Accepting an invitation
+    /// never changes the role of an existing member.
+    async fn accept_invitation(&self, invitation: &Invitation, admin: Uuid) -> bool;
+    async fn delete_invitation(&self, organization: Uuid, id: Uuid) -> bool;
+}
+
+#[async_trait]
+pub trait AuditStore {
+    /// Append an entry to the audit log. Entries can't be modified or removed once recorded.
+    /// Append an entry to the audit log, after the action it records. A failure is logged and
+    /// doesn't undo the action.
+    async fn record_audit(
+        &self,
+        actor: Uuid,
+        organization: Option<Uuid>,
+        action: AuditAction,
+        target: Option<Uuid>,
+        payload: Value,
+    ) -> bool;
+    async fn get_audit_page(&self, organization: Uuid, query: &AuditQuery) -> AuditPage;
+}
+
+#[async_trait]
+pub trait TransferStore {
+    async fn create_transfer(&self, transfer: &Transfer) -> bool;
+    async fn get_transfer(&self, id: Uuid) -> Option<Transfer>;
+    async fn get_pending_transfer_for_leaderboard(&self, leaderboard: Uuid) -> Option<Transfer>;
+    async fn get_pending_transfers_for_admin(&self, admin: Uuid) -> Vec<Transfer>;
+    /// Mark a pending transfer as resolved, without moving the leaderboard.
+    async fn resolve_transfer(&self, transfer: &Transfer, status: TransferStatus) -> bool;
+    /// Move the leaderboard to the organization of the recipient. Returns `Some(false)` if the
+    /// leaderboard isn't in the organization it was requested from anymore.
+    async fn accept_transfer(&self, transfer: &Transfer, organization: Uuid) -> Option<bool>;
+}
+
+#[async_trait]
+pub trait WebhookStore {
+    async fn create_webhook(&self, webhook: &Webhook) -> bool;
+    async fn get_webhook(&self, id: Uuid) -> Option<Webhook>;
+    async fn get_webhooks(&self, leaderboard: Uuid) -> Vec<Webhook>;
+    /// Delete a webhook with its deliveries.
+    async fn delete_webhook(&self, leaderboard: Uuid, id: Uuid) -> bool;
+    async fn create_delivery(&self, delivery: &Delivery) -> bool;
+    /// Record the result of an attempt.
+    async fn update_delivery(&self, delivery: &Delivery) -> bool;
+    /// The latest deliveries of a webhook, most recent first.
+    async fn get_deliveries(&self, webhook: Uuid, limit: i64) -> Vec<Delivery>;
+    async fn get_pending_deliveries(&self) -> Vec<Delivery>;
+}
+
+/// Connections of a database pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    pub connections: u32,
+    pub idle: u32,
+}
+
+/// State of the storage itself, for monitoring.
+#[async_trait]
+pub trait StatusStore {
+    /// `None` for storages without a connection pool.
+    fn pool_stats(&self) -> Option<PoolStats>;
+    /// Check that the database answers.
+    async fn ping(&self) -> Result<(), sqlx::Error>;
+    /// Latest migration applied to the database, read without touching the schema. `None` when
+    /// none has been applied yet.
+    async fn schema_version(&self) -> Result<Option<i64>, sqlx::Error>;
+    /// Latest migration known to the server. `None` for storages without a schema.
+    fn expected_schema_version(&self) -> Option<i64>;
+}
+
+/// Everything the server needs to persist.
+pub trait Storage:
+    PlayerStore
+    + ScoreStore
+    + LeaderboardStore
+    + AdminStore
+    + OrganizationStore
+    + AuditStore
+    + TransferStore
+    + WebhookStore
+    + StatusStore
+    + Send
+    + Sync
+{
+}
+
+impl<T> Storage for T where
+    T: PlayerStore
+        + ScoreStore
+        + LeaderboardStore
+        + AdminStore
+        + OrganizationStore
+        + AuditStore
+        + TransferStore
+        + WebhookStore
+        + StatusStore
+        + Send
+        + Sync
+{
+}
+
+fn pool_options<DB: Database>(settings: &PoolSettings) -> PoolOptions<DB> {
+    PoolOptions::new()
+        .max_connections(settings.max_connections)
+        .min_connections(settings.min_connections)
+        .acquire_timeout(Duration::from_secs(settings.acquire_timeout_seconds))
+}
+
+/// Connect to the storage backend selected in the configuration, applying the pending
+/// migrations if `auto_migrate` is set.
+pub async fn connect(configuration: &Settings) -> Result<Arc<dyn Storage>, MigrateError> {
+    Ok(match &configuration.storage {
+        StorageBackend::Postgres => {
+            let storage = PostgresStorage::new(
+                pool_options(&configuration.pool)
+                    .connect(&configuration.database.connection_string())
+                    .await?,
+            );
+            if configuration.auto_migrate {
+                storage.migrate().await?;
+            }
+            Arc::new(storage)
+        }
+        StorageBackend::Sqlite(settings) => {
+            let storage = SqliteStorage::connect_with_pool(
+                &settings.filename,
+                pool_options(&configuration.pool),
+            )
+            .await?;
+            if configuration.auto_migrate {
+                storage.migrate().await?;
+            }
+            Arc::new(storage)
+        }
+        StorageBackend::Memory => Arc::new(MemoryStorage::new()),
+    })
+}
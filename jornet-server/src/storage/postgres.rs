@@ -0,0 +1,1521 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    PgConnection, PgPool,
+};
+use time::{format_description::well_known::Rfc3339, PrimitiveDateTime, UtcOffset};
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::domains::{
+    admin::{AdminAccount, GithubUser},
+    audit::{AuditAction, AuditEntry, AuditPage, AuditQuery, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE},
+    leaderboard::{Ban, Leaderboard, LeaderboardWithScoreCount},
+    organization::{Invitation, Member, Organization, OrganizationWithRole, Role},
+    player::Player,
+    score::{ModeratedScore, Score, ScoreInput, ScoreType, ScoreValue, SortDirection},
+    superadmin::AdminSummary,
+    transfer::{Transfer, TransferStatus},
+    webhook::{Delivery, DeliveryStatus, Webhook, WebhookEvent, WebhookFormat},
+};
+
+use super::{
+    log_error, AdminStore, AuditStore, LeaderboardStore, OrganizationStore, PlayerStore, PoolStats,
+    ScoreStore, StatusStore, TransferStore, WebhookStore,
+};
+
+pub(super) static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Whether `MIGRATOR` has been run at least once, without creating its table.
+pub(super) async fn has_migrations_table(
+    connection: &mut PgConnection,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar!(r#"SELECT to_regclass('_sqlx_migrations') IS NOT NULL AS "exists!""#)
+        .fetch_one(connection)
+        .await
+}
+
+/// Storage in a PostgreSQL database, migrated with the files in `migrations`.
+pub struct PostgresStorage {
+    pool: PgPool,
+}
+
+impl PostgresStorage {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// Apply the pending migrations.
+    pub async fn migrate(&self) -> Result<(), MigrateError> {
+        MIGRATOR.run(&self.pool).await
+    }
+}
+
+fn format_timestamp(timestamp: PrimitiveDateTime) -> String {
+    timestamp
+        .assume_offset(UtcOffset::UTC)
+        .format(&Rfc3339)
+        .unwrap()
+}
+
+fn score_value(score_type: &str, score: f64, exact: Option<i64>) -> ScoreValue {
+    ScoreValue::from_stored(
+        ScoreType::parse(score_type).unwrap_or_default(),
+        score,
+        exact,
+    )
+}
+
+/// A score with its player, read in either order by `get_ranked_scores`.
+struct ModeratedScoreRecord {
+    id: Uuid,
+    score: f64,
+    exact: Option<i64>,
+    meta: Option<String>,
+    timestamp: PrimitiveDateTime,
+    player: Uuid,
+    name: String,
+    score_type: String,
+}
+
+impl From<ModeratedScoreRecord> for ModeratedScore {
+    fn from(r: ModeratedScoreRecord) -> Self {
+        ModeratedScore {
+            id: r.id,
+            score: score_value(&r.score_type, r.score, r.exact),
+            meta: r.meta,
+            player: r.player,
+            player_name: r.name,
+            timestamp: format_timestamp(r.timestamp),
+        }
+    }
+}
+
+#[async_trait]
+impl PlayerStore for PostgresStorage {
+    #[instrument(skip_all, fields(player = %player.id))]
+    async fn create_player(&self, player: &Player) -> bool {
+        sqlx::query!(
+            r#"
+            INSERT INTO players (id, name, key) VALUES ($1, $2, $3)
+            "#,
+            player.id,
+            player.name,
+            player.key,
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_player(&self, id: Uuid) -> Option<Player> {
+        sqlx::query!(
+            r#"
+            SELECT id, name, key FROM players WHERE id = $1
+            "#,
+            id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| Player {
+            id: r.id,
+            name: r.name,
+            key: r.key,
+        })
+        .ok()
+    }
+}
+
+#[async_trait]
+impl ScoreStore for PostgresStorage {
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_scores(&self, leaderboard: Uuid) -> Vec<Score> {
+        sqlx::query!(
+            "SELECT score, exact, meta, timestamp, players.name, score_type FROM scores, players, leaderboards WHERE leaderboard = $1 and scores.player = players.id and scores.leaderboard = leaderboards.id",
+            leaderboard
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| Score {
+            score: score_value(&r.score_type, r.score, r.exact),
+            meta: r.meta,
+            player: r.name,
+            timestamp: format_timestamp(r.timestamp),
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_scores_for_moderation(&self, leaderboard: Uuid) -> Vec<ModeratedScore> {
+        sqlx::query!(
+            "SELECT scores.id, score, exact, meta, timestamp, player, players.name, score_type FROM scores, players, leaderboards WHERE leaderboard = $1 and scores.player = players.id and scores.leaderboard = leaderboards.id",
+            leaderboard
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| ModeratedScore {
+            id: r.id,
+            score: score_value(&r.score_type, r.score, r.exact),
+            meta: r.meta,
+            player: r.player,
+            player_name: r.name,
+            timestamp: format_timestamp(r.timestamp),
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_ranked_scores(
+        &self,
+        leaderboard: Uuid,
+        direction: SortDirection,
+        offset: i64,
+        limit: i64,
+    ) -> Vec<ModeratedScore> {
+        match direction {
+            SortDirection::Descending => sqlx::query_as!(
+                ModeratedScoreRecord,
+                "SELECT scores.id, score, exact, meta, timestamp, player, players.name, score_type FROM scores, players, leaderboards WHERE leaderboard = $1 and scores.player = players.id and scores.leaderboard = leaderboards.id ORDER BY score DESC, exact DESC NULLS LAST OFFSET $2 LIMIT $3",
+                leaderboard,
+                offset,
+                limit
+            )
+            .fetch_all(&self.pool)
+            .await,
+            SortDirection::Ascending => sqlx::query_as!(
+                ModeratedScoreRecord,
+                "SELECT scores.id, score, exact, meta, timestamp, player, players.name, score_type FROM scores, players, leaderboards WHERE leaderboard = $1 and scores.player = players.id and scores.leaderboard = leaderboards.id ORDER BY score ASC, exact ASC NULLS LAST OFFSET $2 LIMIT $3",
+                leaderboard,
+                offset,
+                limit
+            )
+            .fetch_all(&self.pool)
+            .await,
+        }
+        .unwrap()
+        .into_iter()
+        .map(ModeratedScore::from)
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn count_scores(&self, leaderboard: Uuid) -> i64 {
+        sqlx::query!(
+            r#"SELECT COUNT(*) AS "count!" FROM scores WHERE leaderboard = $1"#,
+            leaderboard
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| r.count)
+        .unwrap_or_default()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %score.player))]
+    async fn score_exists(&self, leaderboard: Uuid, score: &ScoreInput) -> bool {
+        sqlx::query!(
+            "SELECT id FROM scores WHERE leaderboard = $1 AND player = $2 AND score = $3 AND exact IS NOT DISTINCT FROM $4 AND timestamp = TO_TIMESTAMP($5)",
+            leaderboard,
+            score.player,
+            score.score.as_f64(),
+            score.score.exact(),
+            score.timestamp as f64
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %score.player))]
+    async fn insert_score(&self, leaderboard: Uuid, score: &ScoreInput) -> bool {
+        sqlx::query!(
+            r#"
+            INSERT INTO scores (id, leaderboard, score, exact, player, meta, timestamp) VALUES ($1, $2, $3, $4, $5, $6, TO_TIMESTAMP($7))
+            "#,
+            Uuid::new_v4(),
+            leaderboard,
+            score.score.as_f64(),
+            score.score.exact(),
+            score.player,
+            score.meta,
+            score.timestamp as f64
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, id = %id))]
+    async fn delete_score(&self, leaderboard: Uuid, id: Uuid) -> Option<ModeratedScore> {
+        sqlx::query!(
+            "DELETE FROM scores USING leaderboards WHERE leaderboard = $1 AND scores.id = $2 AND leaderboards.id = scores.leaderboard RETURNING scores.id, score, exact, meta, timestamp, player, score_type",
+            leaderboard,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| ModeratedScore {
+            id: r.id,
+            score: score_value(&r.score_type, r.score, r.exact),
+            meta: r.meta,
+            player: r.player,
+            player_name: String::new(),
+            timestamp: format_timestamp(r.timestamp),
+        })
+        .ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn delete_all_scores(&self, leaderboard: Uuid) -> Option<u64> {
+        sqlx::query!("DELETE FROM scores WHERE leaderboard = $1", leaderboard)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|result| result.rows_affected())
+            .ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_best_score(
+        &self,
+        leaderboard: Uuid,
+        direction: SortDirection,
+    ) -> Option<ScoreValue> {
+        match direction {
+            SortDirection::Descending => sqlx::query!(
+                "SELECT score, exact, score_type FROM scores, leaderboards WHERE leaderboard = $1 and scores.leaderboard = leaderboards.id ORDER BY score DESC, exact DESC NULLS LAST LIMIT 1",
+                leaderboard
+            )
+            .fetch_optional(&self.pool)
+            .await
+            .map(|r| r.map(|r| score_value(&r.score_type, r.score, r.exact))),
+            SortDirection::Ascending => sqlx::query!(
+                "SELECT score, exact, score_type FROM scores, leaderboards WHERE leaderboard = $1 and scores.leaderboard = leaderboards.id ORDER BY score ASC, exact ASC NULLS LAST LIMIT 1",
+                leaderboard
+            )
+            .fetch_optional(&self.pool)
+            .await
+            .map(|r| r.map(|r| score_value(&r.score_type, r.score, r.exact))),
+        }
+        .inspect_err(log_error)
+        .ok()?
+    }
+}
+
+#[async_trait]
+impl LeaderboardStore for PostgresStorage {
+    #[instrument(skip_all, fields(admin = %admin))]
+    async fn get_leaderboards(&self, admin: Uuid) -> Vec<LeaderboardWithScoreCount> {
+        sqlx::query!(
+            "SELECT leaderboards.id, name, leaderboards.organization, role, score_type, count(scores.leaderboard) FROM leaderboards JOIN organization_members ON leaderboards.organization = organization_members.organization LEFT JOIN scores ON leaderboards.id = scores.leaderboard WHERE admin = $1 GROUP BY leaderboards.id, role;",
+            admin
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|r| {
+            Some(LeaderboardWithScoreCount {
+                id: r.id,
+                name: r.name,
+                organization: r.organization,
+                role: Role::parse(&r.role)?,
+                score_type: ScoreType::parse(&r.score_type)?,
+                scores: r.count.unwrap(),
+            })
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin))]
+    async fn count_owned_leaderboards(&self, admin: Uuid) -> i64 {
+        sqlx::query!(
+            "SELECT count(id) FROM leaderboards JOIN organization_members ON leaderboards.organization = organization_members.organization WHERE admin = $1 AND role = $2;",
+            admin,
+            Role::Owner.as_str()
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()
+        .and_then(|r| r.count)
+        .unwrap_or_default()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn count_leaderboards_in_organization(&self, organization: Uuid) -> i64 {
+        sqlx::query!(
+            "SELECT count(id) FROM leaderboards WHERE organization = $1;",
+            organization
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()
+        .and_then(|r| r.count)
+        .unwrap_or_default()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_leaderboard(&self, id: Uuid) -> Option<Leaderboard> {
+        sqlx::query!(
+            "SELECT id, key, name, score_type FROM leaderboards WHERE id = $1;",
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()
+        .and_then(|r| {
+            Some(Leaderboard {
+                id: r.id,
+                key: r.key,
+                name: r.name,
+                score_type: ScoreType::parse(&r.score_type)?,
+            })
+        })
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_leaderboard_key(&self, id: Uuid) -> Option<Uuid> {
+        sqlx::query!("SELECT key FROM leaderboards WHERE id = $1;", id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| r.key)
+            .ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_leaderboard_organization(&self, id: Uuid) -> Option<Uuid> {
+        sqlx::query!("SELECT organization FROM leaderboards WHERE id = $1;", id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| r.organization)
+            .ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard.id, organization = %organization))]
+    async fn create_leaderboard(&self, leaderboard: &Leaderboard, organization: Uuid) -> bool {
+        sqlx::query!(
+            r#"
+            INSERT INTO leaderboards (id, name, organization, key, score_type) VALUES ($1, $2, $3, $4, $5)
+            "#,
+            leaderboard.id,
+            leaderboard.name,
+            organization,
+            leaderboard.key,
+            leaderboard.score_type.as_str(),
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn set_leaderboard_key(&self, id: Uuid, key: Uuid) -> bool {
+        sqlx::query!("UPDATE leaderboards SET key = $2 WHERE id = $1", id, key)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn delete_leaderboard(&self, id: Uuid) -> bool {
+        async {
+            let mut transaction = self.pool.begin().await?;
+            sqlx::query!("DELETE FROM scores WHERE leaderboard = $1", id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query!("DELETE FROM leaderboard_bans WHERE leaderboard = $1", id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query!("DELETE FROM leaderboards WHERE id = $1", id)
+                .execute(&mut *transaction)
+                .await?;
+            transaction.commit().await
+        }
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %player, banned_by = %banned_by))]
+    async fn ban_player(&self, leaderboard: Uuid, player: Uuid, banned_by: Uuid) -> Option<u64> {
+        let mut transaction = self.pool.begin().await.inspect_err(log_error).ok()?;
+        sqlx::query!(
+            r#"
+            INSERT INTO leaderboard_bans (leaderboard, player, banned_by) VALUES ($1, $2, $3)
+            ON CONFLICT (leaderboard, player) DO NOTHING
+            "#,
+            leaderboard,
+            player,
+            banned_by,
+        )
+        .execute(&mut *transaction)
+        .await
+        .inspect_err(log_error)
+        .ok()?;
+        let removed = sqlx::query!(
+            "DELETE FROM scores WHERE leaderboard = $1 AND player = $2",
+            leaderboard,
+            player
+        )
+        .execute(&mut *transaction)
+        .await
+        .inspect_err(log_error)
+        .ok()?
+        .rows_affected();
+        transaction.commit().await.inspect_err(log_error).ok()?;
+        Some(removed)
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %player))]
+    async fn unban_player(&self, leaderboard: Uuid, player: Uuid) -> bool {
+        sqlx::query!(
+            "DELETE FROM leaderboard_bans WHERE leaderboard = $1 AND player = $2",
+            leaderboard,
+            player
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %player))]
+    async fn is_banned(&self, leaderboard: Uuid, player: Uuid) -> bool {
+        sqlx::query!(
+            "SELECT player FROM leaderboard_bans WHERE leaderboard = $1 AND player = $2",
+            leaderboard,
+            player
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_bans(&self, leaderboard: Uuid) -> Vec<Ban> {
+        sqlx::query!(
+            "SELECT player, players.name, banned_by FROM leaderboard_bans, players WHERE leaderboard = $1 AND leaderboard_bans.player = players.id",
+            leaderboard
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| Ban {
+            player: r.player,
+            name: r.name,
+            banned_by: r.banned_by,
+        })
+        .collect()
+    }
+}
+
+#[async_trait]
+impl AdminStore for PostgresStorage {
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn create_admin(&self, admin: &AdminAccount) -> bool {
+        sqlx::query!(
+            r#"
+            INSERT INTO admins (id) VALUES ($1)
+            "#,
+            admin.id,
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn create_anonymous_admin(&self, admin: &AdminAccount) -> bool {
+        sqlx::query!(
+            "INSERT INTO admins (id, anonymous) VALUES ($1, TRUE)",
+            admin.id,
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn admin_exists(&self, admin: &AdminAccount) -> bool {
+        sqlx::query!("SELECT id FROM admins WHERE id = $1", admin.id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn is_admin_anonymous(&self, admin: &AdminAccount) -> bool {
+        sqlx::query!("SELECT anonymous FROM admins WHERE id = $1", admin.id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| r.anonymous)
+            .unwrap_or(false)
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn is_admin_active(&self, admin: &AdminAccount) -> bool {
+        sqlx::query!("SELECT disabled FROM admins WHERE id = $1", admin.id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| !r.disabled)
+            .unwrap_or(false)
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn set_admin_disabled(&self, admin: &AdminAccount, disabled: bool) -> bool {
+        sqlx::query!(
+            "UPDATE admins SET disabled = $2 WHERE id = $1",
+            admin.id,
+            disabled
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| r.rows_affected() == 1)
+        .unwrap_or(false)
+    }
+
+    #[instrument(skip_all)]
+    async fn get_admins(&self) -> Vec<AdminSummary> {
+        sqlx::query!(
+            "SELECT admins.id as \"id!\", disabled as \"disabled!\", login as \"login?\" FROM admins LEFT JOIN admins_github ON admins.id = admins_github.admin_id"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| AdminSummary {
+            id: r.id,
+            github: r.login,
+            disabled: r.disabled,
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id, transfer_to = ?transfer_to))]
+    async fn delete_admin(&self, admin: &AdminAccount, transfer_to: Option<Uuid>) -> bool {
+        async {
+            let mut transaction = self.pool.begin().await?;
+            let owned = sqlx::query!(
+                r#"
+                SELECT organizations.id, organizations.personal FROM organizations, organization_members
+                WHERE organizations.id = organization_members.organization AND admin = $1 AND role = $2
+                AND (SELECT count(admin) FROM organization_members AS owners WHERE owners.organization = organizations.id AND owners.role = $2) = 1
+                "#,
+                admin.id,
+                Role::Owner.as_str(),
+            )
+            .fetch_all(&mut *transaction)
+            .await?;
+
+            for organization in owned {
+                match (transfer_to, organization.personal) {
+                    (Some(recipient), true) => {
+                        sqlx::query!(
+                            "UPDATE leaderboards SET organization = $2 WHERE organization = $1",
+                            organization.id,
+                            recipient
+                        )
+                        .execute(&mut *transaction)
+                        .await?;
+                    }
+                    (Some(recipient), false) => {
+                        sqlx::query!(
+                            r#"
+                            INSERT INTO organization_members (organization, admin, role) VALUES ($1, $2, $3)
+                            ON CONFLICT (organization, admin) DO UPDATE SET role = $3
+                            "#,
+                            organization.id,
+                            recipient,
+                            Role::Owner.as_str(),
+                        )
+                        .execute(&mut *transaction)
+                        .await?;
+                    }
+                    (None, _) => {
+                        sqlx::query!(
+                            "DELETE FROM scores WHERE leaderboard IN (SELECT id FROM leaderboards WHERE organization = $1)",
+                            organization.id
+                        )
+                        .execute(&mut *transaction)
+                        .await?;
+                        sqlx::query!(
+                            "DELETE FROM leaderboard_bans WHERE leaderboard IN (SELECT id FROM leaderboards WHERE organization = $1)",
+                            organization.id
+                        )
+                        .execute(&mut *transaction)
+                        .await?;
+                        sqlx::query!(
+                            "DELETE FROM leaderboards WHERE organization = $1",
+                            organization.id
+                        )
+                        .execute(&mut *transaction)
+                        .await?;
+                    }
+                }
+                // a transferred organization keeps existing, unless it was personal
+                if transfer_to.is_none() || organization.personal {
+                    sqlx::query!(
+                        "DELETE FROM organization_invitations WHERE organization = $1",
+                        organization.id
+                    )
+                    .execute(&mut *transaction)
+                    .await?;
+                    sqlx::query!(
+                        "DELETE FROM organization_members WHERE organization = $1",
+                        organization.id
+                    )
+                    .execute(&mut *transaction)
+                    .await?;
+                    sqlx::query!("DELETE FROM organizations WHERE id = $1", organization.id)
+                        .execute(&mut *transaction)
+                        .await?;
+                }
+            }
+
+            sqlx::query!(
+                "UPDATE leaderboard_transfers SET status = $2, resolved_at = NOW() WHERE to_admin = $1 AND status = $3",
+                admin.id,
+                TransferStatus::Cancelled.as_str(),
+                TransferStatus::Pending.as_str()
+            )
+            .execute(&mut *transaction)
+            .await?;
+            sqlx::query!("DELETE FROM organization_members WHERE admin = $1", admin.id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query!("DELETE FROM admins_github WHERE admin_id = $1", admin.id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query!("DELETE FROM admins WHERE id = $1", admin.id)
+                .execute(&mut *transaction)
+                .await?;
+            transaction.commit().await
+        }
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn get_admin_github(&self, admin: &AdminAccount) -> Option<GithubUser> {
+        sqlx::query!(
+            "SELECT id, login FROM admins_github WHERE admin_id = $1",
+            admin.id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|record| GithubUser {
+            login: record.login,
+            id: record.id as u32,
+        })
+        .ok()
+    }
+
+    #[instrument(skip_all, fields(github = %github.login, admin = %admin.id))]
+    async fn link_github(&self, github: &GithubUser, admin: &AdminAccount) -> bool {
+        sqlx::query!(
+            r#"
+            INSERT INTO admins_github (id, login, admin_id) VALUES ($1, $2, $3)
+            "#,
+            github.id as i64,
+            github.login,
+            admin.id,
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn unlink_github(&self, admin: &AdminAccount) -> bool {
+        sqlx::query!("DELETE FROM admins_github WHERE admin_id = $1", admin.id)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(github = %github.login))]
+    async fn github_exists(&self, github: &GithubUser) -> bool {
+        sqlx::query!(
+            "SELECT id FROM admins_github WHERE id = $1 AND login = $2",
+            github.id as i32,
+            github.login
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(github = %github.login))]
+    async fn get_github_admin(&self, github: &GithubUser) -> Option<AdminAccount> {
+        sqlx::query!(
+            "SELECT admin_id FROM admins_github WHERE id = $1 AND login = $2",
+            github.id as i32,
+            github.login
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|record| AdminAccount {
+            id: record.admin_id,
+        })
+        .ok()
+    }
+}
+
+#[async_trait]
+impl OrganizationStore for PostgresStorage {
+    #[instrument(skip_all, fields(organization = %organization.id, owner = %owner))]
+    async fn create_organization(&self, organization: &Organization, owner: Uuid) -> bool {
+        async {
+            let mut transaction = self.pool.begin().await?;
+            sqlx::query!(
+                r#"
+                INSERT INTO organizations (id, name, personal) VALUES ($1, $2, $3)
+                "#,
+                organization.id,
+                organization.name,
+                organization.personal,
+            )
+            .execute(&mut *transaction)
+            .await?;
+            sqlx::query!(
+                r#"
+                INSERT INTO organization_members (organization, admin, role) VALUES ($1, $2, $3)
+                "#,
+                organization.id,
+                owner,
+                Role::Owner.as_str(),
+            )
+            .execute(&mut *transaction)
+            .await?;
+            transaction.commit().await
+        }
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_organization(&self, id: Uuid) -> Option<Organization> {
+        sqlx::query!(
+            "SELECT id, name, personal FROM organizations WHERE id = $1",
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| Organization {
+            id: r.id,
+            name: r.name,
+            personal: r.personal,
+        })
+        .ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin))]
+    async fn get_organizations(&self, admin: Uuid) -> Vec<OrganizationWithRole> {
+        sqlx::query!(
+            "SELECT id, name, personal, role FROM organizations, organization_members WHERE organizations.id = organization_members.organization AND admin = $1",
+            admin
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|r| {
+            Some(OrganizationWithRole {
+                id: r.id,
+                name: r.name,
+                personal: r.personal,
+                role: Role::parse(&r.role)?,
+            })
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn delete_organization(&self, id: Uuid) -> bool {
+        async {
+            let mut transaction = self.pool.begin().await?;
+            sqlx::query!(
+                "DELETE FROM organization_invitations WHERE organization = $1",
+                id
+            )
+            .execute(&mut *transaction)
+            .await?;
+            sqlx::query!(
+                "DELETE FROM organization_members WHERE organization = $1",
+                id
+            )
+            .execute(&mut *transaction)
+            .await?;
+            sqlx::query!("DELETE FROM organizations WHERE id = $1", id)
+                .execute(&mut *transaction)
+                .await?;
+            transaction.commit().await
+        }
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization, admin = %admin))]
+    async fn get_role(&self, organization: Uuid, admin: Uuid) -> Option<Role> {
+        sqlx::query!(
+            "SELECT role FROM organization_members WHERE organization = $1 AND admin = $2",
+            organization,
+            admin
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()
+        .and_then(|r| Role::parse(&r.role))
+    }
+
+    #[instrument(skip_all, fields(organization = %organization, admin = %admin))]
+    async fn set_role(&self, organization: Uuid, admin: Uuid, role: Role) -> bool {
+        sqlx::query!(
+            "UPDATE organization_members SET role = $3 WHERE organization = $1 AND admin = $2",
+            organization,
+            admin,
+            role.as_str()
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn get_members(&self, organization: Uuid) -> Vec<Member> {
+        sqlx::query!(
+            "SELECT admin, role, login as \"login?\" FROM organization_members LEFT JOIN admins_github ON organization_members.admin = admins_github.admin_id WHERE organization = $1",
+            organization
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|r| {
+            Some(Member {
+                admin: r.admin,
+                role: Role::parse(&r.role)?,
+                github: r.login,
+            })
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn count_owners(&self, organization: Uuid) -> i64 {
+        sqlx::query!(
+            "SELECT count(admin) FROM organization_members WHERE organization = $1 AND role = $2",
+            organization,
+            Role::Owner.as_str()
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()
+        .and_then(|r| r.count)
+        .unwrap_or_default()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization, admin = %admin))]
+    async fn remove_member(&self, organization: Uuid, admin: Uuid) -> bool {
+        sqlx::query!(
+            "DELETE FROM organization_members WHERE organization = $1 AND admin = $2",
+            organization,
+            admin
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(invitation = %invitation.id, organization = %invitation.organization))]
+    async fn create_invitation(&self, invitation: &Invitation) -> bool {
+        sqlx::query!(
+            r#"
+            INSERT INTO organization_invitations (id, organization, role, github_login, email, invited_by) VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            invitation.id,
+            invitation.organization,
+            invitation.role.as_str(),
+            invitation.github_login,
+            invitation.email,
+            invitation.invited_by,
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_invitation(&self, id: Uuid) -> Option<Invitation> {
+        let r = sqlx::query!(
+            "SELECT id, organization, role, github_login, email, invited_by FROM organization_invitations WHERE id = $1",
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()?;
+        Some(Invitation {
+            id: r.id,
+            organization: r.organization,
+            role: Role::parse(&r.role)?,
+            github_login: r.github_login,
+            email: r.email,
+            invited_by: r.invited_by,
+        })
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn get_invitations_for_organization(&self, organization: Uuid) -> Vec<Invitation> {
+        sqlx::query!(
+            "SELECT id, organization, role, github_login, email, invited_by FROM organization_invitations WHERE organization = $1",
+            organization
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|r| {
+            Some(Invitation {
+                id: r.id,
+                organization: r.organization,
+                role: Role::parse(&r.role)?,
+                github_login: r.github_login,
+                email: r.email,
+                invited_by: r.invited_by,
+            })
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(login))]
+    async fn get_invitations_for_github(&self, login: &str) -> Vec<Invitation> {
+        sqlx::query!(
+            "SELECT id, organization, role, github_login, email, invited_by FROM organization_invitations WHERE lower(github_login) = lower($1)",
+            login
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|r| {
+            Some(Invitation {
+                id: r.id,
+                organization: r.organization,
+                role: Role::parse(&r.role)?,
+                github_login: r.github_login,
+                email: r.email,
+                invited_by: r.invited_by,
+            })
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(invitation = %invitation.id, organization = %invitation.organization, admin = %admin))]
+    async fn accept_invitation(&self, invitation: &Invitation, admin: Uuid) -> bool {
+        async {
+            let mut transaction = self.pool.begin().await?;
+            sqlx::query!(
+                r#"
+                INSERT INTO organization_members (organization, admin, role) VALUES ($1, $2, $3)
+                ON CONFLICT (organization, admin) DO NOTHING
+                "#,
+                invitation.organization,
+                admin,
+                invitation.role.as_str(),
+            )
+            .execute(&mut *transaction)
+            .await?;
+            sqlx::query!(
+                "DELETE FROM organization_invitations WHERE id = $1",
+                invitation.id
+            )
+            .execute(&mut *transaction)
+            .await?;
+            transaction.commit().await
+        }
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization, id = %id))]
+    async fn delete_invitation(&self, organization: Uuid, id: Uuid) -> bool {
+        sqlx::query!(
+            "DELETE FROM organization_invitations WHERE organization = $1 AND id = $2",
+            organization,
+            id
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+}
+
+#[async_trait]
+impl AuditStore for PostgresStorage {
+    #[instrument(skip_all, fields(actor = %actor, organization = ?organization, target = ?target))]
+    async fn record_audit(
+        &self,
+        actor: Uuid,
+        organization: Option<Uuid>,
+        action: AuditAction,
+        target: Option<Uuid>,
+        payload: Value,
+    ) -> bool {
+        sqlx::query!(
+            r#"
+            INSERT INTO audit_log (actor, organization, action, target, payload) VALUES ($1, $2, $3, $4, $5)
+            "#,
+            actor,
+            organization,
+            action.as_str(),
+            target,
+            (!payload.is_null()).then(|| payload.to_string()),
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|error| {
+            error!(%error, action = action.as_str(), %actor, "failed to record an audit entry")
+        })
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn get_audit_page(&self, organization: Uuid, query: &AuditQuery) -> AuditPage {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query
+            .per_page
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        let action = query.action.map(|action| action.as_str());
+
+        let total = sqlx::query!(
+            "SELECT count(id) FROM audit_log WHERE organization = $1 AND ($2::uuid IS NULL OR actor = $2) AND ($3::text IS NULL OR action = $3) AND ($4::uuid IS NULL OR target = $4)",
+            organization,
+            query.actor,
+            action,
+            query.target,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()
+        .and_then(|r| r.count)
+        .unwrap_or_default();
+
+        let entries = sqlx::query!(
+            "SELECT id, timestamp, actor, organization, action, target, payload FROM audit_log WHERE organization = $1 AND ($2::uuid IS NULL OR actor = $2) AND ($3::text IS NULL OR action = $3) AND ($4::uuid IS NULL OR target = $4) ORDER BY id DESC LIMIT $5 OFFSET $6",
+            organization,
+            query.actor,
+            action,
+            query.target,
+            per_page,
+            (page - 1) * per_page,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| AuditEntry {
+            id: r.id,
+            timestamp: format_timestamp(r.timestamp),
+            actor: r.actor,
+            organization: r.organization,
+            action: r.action,
+            target: r.target,
+            payload: r
+                .payload
+                .and_then(|payload| serde_json::from_str(&payload).ok()),
+        })
+        .collect();
+
+        AuditPage {
+            entries,
+            page,
+            per_page,
+            total,
+        }
+    }
+}
+
+#[async_trait]
+impl TransferStore for PostgresStorage {
+    #[instrument(skip_all, fields(transfer = %transfer.id, leaderboard = %transfer.leaderboard))]
+    async fn create_transfer(&self, transfer: &Transfer) -> bool {
+        sqlx::query!(
+            r#"
+            INSERT INTO leaderboard_transfers (id, leaderboard, from_organization, to_admin, requested_by, status) VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            transfer.id,
+            transfer.leaderboard,
+            transfer.from_organization,
+            transfer.to_admin,
+            transfer.requested_by,
+            transfer.status.as_str(),
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_transfer(&self, id: Uuid) -> Option<Transfer> {
+        let r = sqlx::query!(
+            "SELECT id, leaderboard, from_organization, to_admin, requested_by, status FROM leaderboard_transfers WHERE id = $1",
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()?;
+        Some(Transfer {
+            id: r.id,
+            leaderboard: r.leaderboard,
+            from_organization: r.from_organization,
+            to_admin: r.to_admin,
+            requested_by: r.requested_by,
+            status: TransferStatus::parse(&r.status)?,
+        })
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_pending_transfer_for_leaderboard(&self, leaderboard: Uuid) -> Option<Transfer> {
+        sqlx::query!(
+            "SELECT id, leaderboard, from_organization, to_admin, requested_by FROM leaderboard_transfers WHERE leaderboard = $1 AND status = $2",
+            leaderboard,
+            TransferStatus::Pending.as_str()
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| Transfer {
+            id: r.id,
+            leaderboard: r.leaderboard,
+            from_organization: r.from_organization,
+            to_admin: r.to_admin,
+            requested_by: r.requested_by,
+            status: TransferStatus::Pending,
+        })
+        .ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin))]
+    async fn get_pending_transfers_for_admin(&self, admin: Uuid) -> Vec<Transfer> {
+        sqlx::query!(
+            "SELECT id, leaderboard, from_organization, to_admin, requested_by FROM leaderboard_transfers WHERE to_admin = $1 AND status = $2",
+            admin,
+            TransferStatus::Pending.as_str()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| Transfer {
+            id: r.id,
+            leaderboard: r.leaderboard,
+            from_organization: r.from_organization,
+            to_admin: r.to_admin,
+            requested_by: r.requested_by,
+            status: TransferStatus::Pending,
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(transfer = %transfer.id, leaderboard = %transfer.leaderboard))]
+    async fn resolve_transfer(&self, transfer: &Transfer, status: TransferStatus) -> bool {
+        sqlx::query!(
+            "UPDATE leaderboard_transfers SET status = $2, resolved_at = NOW() WHERE id = $1 AND status = $3",
+            transfer.id,
+            status.as_str(),
+            TransferStatus::Pending.as_str()
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| r.rows_affected() == 1)
+        .unwrap_or(false)
+    }
+
+    #[instrument(skip_all, fields(transfer = %transfer.id, leaderboard = %transfer.leaderboard, organization = %organization))]
+    async fn accept_transfer(&self, transfer: &Transfer, organization: Uuid) -> Option<bool> {
+        let mut transaction = self.pool.begin().await.inspect_err(log_error).ok()?;
+        let moved = sqlx::query!(
+            "UPDATE leaderboards SET organization = $3 WHERE id = $1 AND organization = $2",
+            transfer.leaderboard,
+            transfer.from_organization,
+            organization
+        )
+        .execute(&mut *transaction)
+        .await
+        .inspect_err(log_error)
+        .ok()?
+        .rows_affected();
+        let status = if moved == 1 {
+            TransferStatus::Accepted
+        } else {
+            TransferStatus::Cancelled
+        };
+        sqlx::query!(
+            "UPDATE leaderboard_transfers SET status = $2, resolved_at = NOW() WHERE id = $1",
+            transfer.id,
+            status.as_str()
+        )
+        .execute(&mut *transaction)
+        .await
+        .inspect_err(log_error)
+        .ok()?;
+        transaction.commit().await.inspect_err(log_error).ok()?;
+        Some(moved == 1)
+    }
+}
+
+#[async_trait]
+impl WebhookStore for PostgresStorage {
+    #[instrument(skip_all, fields(webhook = %webhook.id, leaderboard = %webhook.leaderboard))]
+    async fn create_webhook(&self, webhook: &Webhook) -> bool {
+        sqlx::query!(
+            r#"
+            INSERT INTO webhooks (id, leaderboard, url, secret, events, format, created_by) VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            webhook.id,
+            webhook.leaderboard,
+            webhook.url,
+            webhook.secret,
+            WebhookEvent::join(&webhook.events),
+            webhook.format.as_str(),
+            webhook.created_by,
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_webhook(&self, id: Uuid) -> Option<Webhook> {
+        let r = sqlx::query!(
+            "SELECT id, leaderboard, url, secret, events, format, created_by FROM webhooks WHERE id = $1",
+            id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()?;
+        Some(Webhook {
+            id: r.id,
+            leaderboard: r.leaderboard,
+            url: r.url,
+            events: WebhookEvent::split(&r.events),
+            format: WebhookFormat::parse(&r.format)?,
+            secret: r.secret,
+            created_by: r.created_by,
+        })
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_webhooks(&self, leaderboard: Uuid) -> Vec<Webhook> {
+        sqlx::query!(
+            "SELECT id, leaderboard, url, secret, events, format, created_by FROM webhooks WHERE leaderboard = $1 ORDER BY created_at",
+            leaderboard
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|r| {
+            Some(Webhook {
+                id: r.id,
+                leaderboard: r.leaderboard,
+                url: r.url,
+                events: WebhookEvent::split(&r.events),
+                format: WebhookFormat::parse(&r.format)?,
+                secret: r.secret,
+                created_by: r.created_by,
+            })
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, id = %id))]
+    async fn delete_webhook(&self, leaderboard: Uuid, id: Uuid) -> bool {
+        sqlx::query!(
+            "DELETE FROM webhooks WHERE id = $1 AND leaderboard = $2",
+            id,
+            leaderboard
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| r.rows_affected() == 1)
+        .unwrap_or(false)
+    }
+
+    #[instrument(skip_all, fields(delivery = %delivery.id, webhook = %delivery.webhook))]
+    async fn create_delivery(&self, delivery: &Delivery) -> bool {
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_deliveries (id, webhook, event, payload, status, attempts) VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            delivery.id,
+            delivery.webhook,
+            delivery.event.as_str(),
+            delivery.payload.to_string(),
+            delivery.status.as_str(),
+            delivery.attempts,
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(delivery = %delivery.id, webhook = %delivery.webhook))]
+    async fn update_delivery(&self, delivery: &Delivery) -> bool {
+        sqlx::query!(
+            "UPDATE webhook_deliveries SET status = $2, attempts = $3, response_status = $4, error = $5, last_attempt_at = NOW() WHERE id = $1",
+            delivery.id,
+            delivery.status.as_str(),
+            delivery.attempts,
+            delivery.response_status,
+            delivery.error,
+        )
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| r.rows_affected() == 1)
+        .unwrap_or(false)
+    }
+
+    #[instrument(skip_all, fields(webhook = %webhook))]
+    async fn get_deliveries(&self, webhook: Uuid, limit: i64) -> Vec<Delivery> {
+        sqlx::query!(
+            "SELECT id, webhook, event, payload, status, attempts, response_status, error, created_at, last_attempt_at FROM webhook_deliveries WHERE webhook = $1 ORDER BY created_at DESC LIMIT $2",
+            webhook,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|r| {
+            Some(Delivery {
+                id: r.id,
+                webhook: r.webhook,
+                event: WebhookEvent::parse(&r.event)?,
+                payload: serde_json::from_str(&r.payload).ok()?,
+                status: DeliveryStatus::parse(&r.status)?,
+                attempts: r.attempts,
+                response_status: r.response_status,
+                error: r.error,
+                created_at: format_timestamp(r.created_at),
+                last_attempt_at: r.last_attempt_at.map(format_timestamp),
+            })
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all)]
+    async fn get_pending_deliveries(&self) -> Vec<Delivery> {
+        sqlx::query!(
+            "SELECT id, webhook, event, payload, status, attempts, response_status, error, created_at, last_attempt_at FROM webhook_deliveries WHERE status = $1 ORDER BY created_at",
+            DeliveryStatus::Pending.as_str()
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|r| {
+            Some(Delivery {
+                id: r.id,
+                webhook: r.webhook,
+                event: WebhookEvent::parse(&r.event)?,
+                payload: serde_json::from_str(&r.payload).ok()?,
+                status: DeliveryStatus::Pending,
+                attempts: r.attempts,
+                response_status: r.response_status,
+                error: r.error,
+                created_at: format_timestamp(r.created_at),
+                last_attempt_at: r.last_attempt_at.map(format_timestamp),
+            })
+        })
+        .collect()
+    }
+}
+
+#[async_trait]
+impl StatusStore for PostgresStorage {
+    fn pool_stats(&self) -> Option<PoolStats> {
+        Some(PoolStats {
+            connections: self.pool.size(),
+            idle: self.pool.num_idle() as u32,
+        })
+    }
+
+    async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+    }
+
+    async fn schema_version(&self) -> Result<Option<i64>, sqlx::Error> {
+        let mut connection = self.pool.acquire().await?;
+        if !has_migrations_table(&mut connection).await? {
+            return Ok(None);
+        }
+        sqlx::query_scalar("SELECT max(version) FROM _sqlx_migrations WHERE success")
+            .fetch_one(&mut *connection)
+            .await
+    }
+
+    fn expected_schema_version(&self) -> Option<i64> {
+        MIGRATOR.iter().map(|migration| migration.version).max()
+    }
+}
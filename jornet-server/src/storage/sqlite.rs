@@ -0,0 +1,1447 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::{
+    migrate::{MigrateError, Migrator},
+    sqlite::{SqliteConnectOptions, SqliteConnection, SqlitePoolOptions},
+    ConnectOptions, SqlitePool,
+};
+use time::{format_description::well_known::Rfc3339, PrimitiveDateTime, UtcOffset};
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::domains::{
+    admin::{AdminAccount, GithubUser},
+    audit::{AuditAction, AuditEntry, AuditPage, AuditQuery, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE},
+    leaderboard::{Ban, Leaderboard, LeaderboardWithScoreCount},
+    organization::{Invitation, Member, Organization, OrganizationWithRole, Role},
+    player::Player,
+    score::{ModeratedScore, Score, ScoreInput, ScoreType, ScoreValue, SortDirection},
+    superadmin::AdminSummary,
+    transfer::{Transfer, TransferStatus},
+    webhook::{Delivery, DeliveryStatus, Webhook, WebhookEvent, WebhookFormat},
+};
+
+use super::{
+    log_error, AdminStore, AuditStore, LeaderboardStore, OrganizationStore, PlayerStore, PoolStats,
+    ScoreStore, StatusStore, TransferStore, WebhookStore,
+};
+
+pub(super) static MIGRATOR: Migrator = sqlx::migrate!("./migrations_sqlite");
+
+/// Whether `MIGRATOR` has been run at least once, without creating its table.
+pub(super) async fn has_migrations_table(
+    connection: &mut SqliteConnection,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(connection)
+    .await
+}
+
+/// Storage in a single SQLite file, migrated with the files in `migrations_sqlite`.
+///
+/// Queries are checked at runtime, as the compile time checks of `sqlx` are done against the
+/// PostgreSQL database.
+pub struct SqliteStorage {
+    pool: SqlitePool,
+}
+
+impl SqliteStorage {
+    /// Open the database, creating it if needed.
+    pub async fn connect(filename: &str) -> Result<Self, sqlx::Error> {
+        Self::connect_with_pool(filename, SqlitePoolOptions::new()).await
+    }
+
+    /// Open the database with a tuned connection pool, creating it if needed.
+    pub async fn connect_with_pool(
+        filename: &str,
+        pool: SqlitePoolOptions,
+    ) -> Result<Self, sqlx::Error> {
+        let options = SqliteConnectOptions::from_str(filename)?
+            .create_if_missing(true)
+            .foreign_keys(true);
+        let pool = pool.connect_with(options).await?;
+        Ok(Self { pool })
+    }
+
+    /// Open the database read-only, without creating it. `None` if it doesn't exist.
+    pub async fn connect_read_only(
+        filename: &str,
+    ) -> Result<Option<SqliteConnection>, sqlx::Error> {
+        let options = SqliteConnectOptions::from_str(filename)?.read_only(true);
+        if !options.get_filename().exists() {
+            return Ok(None);
+        }
+        options.connect().await.map(Some)
+    }
+
+    /// Apply the pending migrations.
+    pub async fn migrate(&self) -> Result<(), MigrateError> {
+        MIGRATOR.run(&self.pool).await
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+}
+
+fn format_timestamp(timestamp: PrimitiveDateTime) -> String {
+    timestamp
+        .assume_offset(UtcOffset::UTC)
+        .format(&Rfc3339)
+        .unwrap()
+}
+
+fn score_value(score_type: &str, score: f64, exact: Option<i64>) -> ScoreValue {
+    ScoreValue::from_stored(
+        ScoreType::parse(score_type).unwrap_or_default(),
+        score,
+        exact,
+    )
+}
+
+type InvitationRow = (Uuid, Uuid, String, Option<String>, Option<String>, Uuid);
+type ModeratedScoreRow = (
+    Uuid,
+    f64,
+    Option<i64>,
+    Option<String>,
+    PrimitiveDateTime,
+    Uuid,
+    String,
+    String,
+);
+
+fn invitation_from_row(r: InvitationRow) -> Option<Invitation> {
+    Some(Invitation {
+        id: r.0,
+        organization: r.1,
+        role: Role::parse(&r.2)?,
+        github_login: r.3,
+        email: r.4,
+        invited_by: r.5,
+    })
+}
+
+type TransferRow = (Uuid, Uuid, Uuid, Uuid, Uuid, String);
+
+type WebhookRow = (Uuid, Uuid, String, String, String, String, Uuid);
+
+fn webhook_from_row(r: WebhookRow) -> Option<Webhook> {
+    Some(Webhook {
+        id: r.0,
+        leaderboard: r.1,
+        url: r.2,
+        secret: r.3,
+        events: WebhookEvent::split(&r.4),
+        format: WebhookFormat::parse(&r.5)?,
+        created_by: r.6,
+    })
+}
+
+type DeliveryRow = (
+    Uuid,
+    Uuid,
+    String,
+    String,
+    String,
+    i32,
+    Option<i32>,
+    Option<String>,
+    PrimitiveDateTime,
+    Option<PrimitiveDateTime>,
+);
+
+fn delivery_from_row(r: DeliveryRow) -> Option<Delivery> {
+    Some(Delivery {
+        id: r.0,
+        webhook: r.1,
+        event: WebhookEvent::parse(&r.2)?,
+        payload: serde_json::from_str(&r.3).ok()?,
+        status: DeliveryStatus::parse(&r.4)?,
+        attempts: r.5,
+        response_status: r.6,
+        error: r.7,
+        created_at: format_timestamp(r.8),
+        last_attempt_at: r.9.map(format_timestamp),
+    })
+}
+
+fn transfer_from_row(r: TransferRow) -> Option<Transfer> {
+    Some(Transfer {
+        id: r.0,
+        leaderboard: r.1,
+        from_organization: r.2,
+        to_admin: r.3,
+        requested_by: r.4,
+        status: TransferStatus::parse(&r.5)?,
+    })
+}
+
+#[async_trait]
+impl PlayerStore for SqliteStorage {
+    #[instrument(skip_all, fields(player = %player.id))]
+    async fn create_player(&self, player: &Player) -> bool {
+        sqlx::query("INSERT INTO players (id, name, key) VALUES (?1, ?2, ?3)")
+            .bind(player.id)
+            .bind(&player.name)
+            .bind(player.key)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_player(&self, id: Uuid) -> Option<Player> {
+        sqlx::query_as::<_, (Uuid, String, Uuid)>("SELECT id, name, key FROM players WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| Player {
+                id: r.0,
+                name: r.1,
+                key: r.2,
+            })
+            .ok()
+    }
+}
+
+#[async_trait]
+impl ScoreStore for SqliteStorage {
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_scores(&self, leaderboard: Uuid) -> Vec<Score> {
+        sqlx::query_as::<_, (f64, Option<i64>, Option<String>, PrimitiveDateTime, String, String)>(
+            "SELECT score, exact, meta, timestamp, players.name, score_type FROM scores, players, leaderboards WHERE leaderboard = ?1 and scores.player = players.id and scores.leaderboard = leaderboards.id",
+        )
+        .bind(leaderboard)
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| Score {
+            score: score_value(&r.5, r.0, r.1),
+            meta: r.2,
+            timestamp: format_timestamp(r.3),
+            player: r.4,
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_scores_for_moderation(&self, leaderboard: Uuid) -> Vec<ModeratedScore> {
+        sqlx::query_as::<_, ModeratedScoreRow>(
+            "SELECT scores.id, score, exact, meta, timestamp, player, players.name, score_type FROM scores, players, leaderboards WHERE leaderboard = ?1 and scores.player = players.id and scores.leaderboard = leaderboards.id",
+        )
+        .bind(leaderboard)
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| ModeratedScore {
+            id: r.0,
+            score: score_value(&r.7, r.1, r.2),
+            meta: r.3,
+            timestamp: format_timestamp(r.4),
+            player: r.5,
+            player_name: r.6,
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_ranked_scores(
+        &self,
+        leaderboard: Uuid,
+        direction: SortDirection,
+        offset: i64,
+        limit: i64,
+    ) -> Vec<ModeratedScore> {
+        let query = match direction {
+            SortDirection::Descending => "SELECT scores.id, score, exact, meta, timestamp, player, players.name, score_type FROM scores, players, leaderboards WHERE leaderboard = ?1 and scores.player = players.id and scores.leaderboard = leaderboards.id ORDER BY score DESC, exact DESC NULLS LAST LIMIT ?3 OFFSET ?2",
+            SortDirection::Ascending => "SELECT scores.id, score, exact, meta, timestamp, player, players.name, score_type FROM scores, players, leaderboards WHERE leaderboard = ?1 and scores.player = players.id and scores.leaderboard = leaderboards.id ORDER BY score ASC, exact ASC NULLS LAST LIMIT ?3 OFFSET ?2",
+        };
+        sqlx::query_as::<_, ModeratedScoreRow>(query)
+            .bind(leaderboard)
+            .bind(offset)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| ModeratedScore {
+                id: r.0,
+                score: score_value(&r.7, r.1, r.2),
+                meta: r.3,
+                timestamp: format_timestamp(r.4),
+                player: r.5,
+                player_name: r.6,
+            })
+            .collect()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn count_scores(&self, leaderboard: Uuid) -> i64 {
+        sqlx::query_as::<_, (i64,)>("SELECT COUNT(*) FROM scores WHERE leaderboard = ?1")
+            .bind(leaderboard)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| r.0)
+            .unwrap_or_default()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %score.player))]
+    async fn score_exists(&self, leaderboard: Uuid, score: &ScoreInput) -> bool {
+        sqlx::query(
+            "SELECT id FROM scores WHERE leaderboard = ?1 AND player = ?2 AND score = ?3 AND exact IS ?4 AND timestamp = datetime(?5, 'unixepoch')",
+        )
+        .bind(leaderboard)
+        .bind(score.player)
+        .bind(score.score.as_f64())
+        .bind(score.score.exact())
+        .bind(score.timestamp as i64)
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %score.player))]
+    async fn insert_score(&self, leaderboard: Uuid, score: &ScoreInput) -> bool {
+        sqlx::query(
+            "INSERT INTO scores (id, leaderboard, score, exact, player, meta, timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6, datetime(?7, 'unixepoch'))",
+        )
+        .bind(Uuid::new_v4())
+        .bind(leaderboard)
+        .bind(score.score.as_f64())
+        .bind(score.score.exact())
+        .bind(score.player)
+        .bind(&score.meta)
+        .bind(score.timestamp as i64)
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, id = %id))]
+    async fn delete_score(&self, leaderboard: Uuid, id: Uuid) -> Option<ModeratedScore> {
+        sqlx::query_as::<_, ModeratedScoreRow>(
+            "DELETE FROM scores WHERE leaderboard = ?1 AND id = ?2 RETURNING id, score, exact, meta, timestamp, player, '', (SELECT score_type FROM leaderboards WHERE leaderboards.id = scores.leaderboard)",
+        )
+        .bind(leaderboard)
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| ModeratedScore {
+            id: r.0,
+            score: score_value(&r.7, r.1, r.2),
+            meta: r.3,
+            timestamp: format_timestamp(r.4),
+            player: r.5,
+            player_name: r.6,
+        })
+        .ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn delete_all_scores(&self, leaderboard: Uuid) -> Option<u64> {
+        sqlx::query("DELETE FROM scores WHERE leaderboard = ?1")
+            .bind(leaderboard)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|result| result.rows_affected())
+            .ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_best_score(
+        &self,
+        leaderboard: Uuid,
+        direction: SortDirection,
+    ) -> Option<ScoreValue> {
+        let query = match direction {
+            SortDirection::Descending => "SELECT score, exact, score_type FROM scores, leaderboards WHERE leaderboard = ?1 and scores.leaderboard = leaderboards.id ORDER BY score DESC, exact DESC NULLS LAST LIMIT 1",
+            SortDirection::Ascending => "SELECT score, exact, score_type FROM scores, leaderboards WHERE leaderboard = ?1 and scores.leaderboard = leaderboards.id ORDER BY score ASC, exact ASC NULLS LAST LIMIT 1",
+        };
+        sqlx::query_as::<_, (f64, Option<i64>, String)>(query)
+            .bind(leaderboard)
+            .fetch_optional(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .ok()?
+            .map(|r| score_value(&r.2, r.0, r.1))
+    }
+}
+
+#[async_trait]
+impl LeaderboardStore for SqliteStorage {
+    #[instrument(skip_all, fields(admin = %admin))]
+    async fn get_leaderboards(&self, admin: Uuid) -> Vec<LeaderboardWithScoreCount> {
+        sqlx::query_as::<_, (Uuid, String, Uuid, String, String, i64)>(
+            "SELECT leaderboards.id, name, leaderboards.organization, role, score_type, count(scores.leaderboard) FROM leaderboards JOIN organization_members ON leaderboards.organization = organization_members.organization LEFT JOIN scores ON leaderboards.id = scores.leaderboard WHERE admin = ?1 GROUP BY leaderboards.id, role",
+        )
+        .bind(admin)
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|r| {
+            Some(LeaderboardWithScoreCount {
+                id: r.0,
+                name: r.1,
+                organization: r.2,
+                role: Role::parse(&r.3)?,
+                score_type: ScoreType::parse(&r.4)?,
+                scores: r.5,
+            })
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin))]
+    async fn count_owned_leaderboards(&self, admin: Uuid) -> i64 {
+        sqlx::query_as::<_, (i64,)>(
+            "SELECT count(id) FROM leaderboards JOIN organization_members ON leaderboards.organization = organization_members.organization WHERE admin = ?1 AND role = ?2",
+        )
+        .bind(admin)
+        .bind(Role::Owner.as_str())
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| r.0)
+        .unwrap_or_default()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn count_leaderboards_in_organization(&self, organization: Uuid) -> i64 {
+        sqlx::query_as::<_, (i64,)>("SELECT count(id) FROM leaderboards WHERE organization = ?1")
+            .bind(organization)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| r.0)
+            .unwrap_or_default()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_leaderboard(&self, id: Uuid) -> Option<Leaderboard> {
+        sqlx::query_as::<_, (Uuid, Uuid, String, String)>(
+            "SELECT id, key, name, score_type FROM leaderboards WHERE id = ?1",
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()
+        .and_then(|r| {
+            Some(Leaderboard {
+                id: r.0,
+                key: r.1,
+                name: r.2,
+                score_type: ScoreType::parse(&r.3)?,
+            })
+        })
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_leaderboard_key(&self, id: Uuid) -> Option<Uuid> {
+        sqlx::query_as::<_, (Uuid,)>("SELECT key FROM leaderboards WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| r.0)
+            .ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_leaderboard_organization(&self, id: Uuid) -> Option<Uuid> {
+        sqlx::query_as::<_, (Uuid,)>("SELECT organization FROM leaderboards WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| r.0)
+            .ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard.id, organization = %organization))]
+    async fn create_leaderboard(&self, leaderboard: &Leaderboard, organization: Uuid) -> bool {
+        sqlx::query(
+            "INSERT INTO leaderboards (id, name, organization, key, score_type) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(leaderboard.id)
+        .bind(&leaderboard.name)
+        .bind(organization)
+        .bind(leaderboard.key)
+        .bind(leaderboard.score_type.as_str())
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn set_leaderboard_key(&self, id: Uuid, key: Uuid) -> bool {
+        sqlx::query("UPDATE leaderboards SET key = ?2 WHERE id = ?1")
+            .bind(id)
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn delete_leaderboard(&self, id: Uuid) -> bool {
+        async {
+            let mut transaction = self.pool.begin().await?;
+            sqlx::query("DELETE FROM scores WHERE leaderboard = ?1")
+                .bind(id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query("DELETE FROM leaderboard_bans WHERE leaderboard = ?1")
+                .bind(id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query("DELETE FROM leaderboards WHERE id = ?1")
+                .bind(id)
+                .execute(&mut *transaction)
+                .await?;
+            transaction.commit().await
+        }
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %player, banned_by = %banned_by))]
+    async fn ban_player(&self, leaderboard: Uuid, player: Uuid, banned_by: Uuid) -> Option<u64> {
+        let mut transaction = self.pool.begin().await.inspect_err(log_error).ok()?;
+        sqlx::query(
+            "INSERT INTO leaderboard_bans (leaderboard, player, banned_by) VALUES (?1, ?2, ?3) ON CONFLICT (leaderboard, player) DO NOTHING",
+        )
+        .bind(leaderboard)
+        .bind(player)
+        .bind(banned_by)
+        .execute(&mut *transaction)
+        .await
+        .inspect_err(log_error)
+        .ok()?;
+        let removed = sqlx::query("DELETE FROM scores WHERE leaderboard = ?1 AND player = ?2")
+            .bind(leaderboard)
+            .bind(player)
+            .execute(&mut *transaction)
+            .await
+            .inspect_err(log_error)
+            .ok()?
+            .rows_affected();
+        transaction.commit().await.inspect_err(log_error).ok()?;
+        Some(removed)
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %player))]
+    async fn unban_player(&self, leaderboard: Uuid, player: Uuid) -> bool {
+        sqlx::query("DELETE FROM leaderboard_bans WHERE leaderboard = ?1 AND player = ?2")
+            .bind(leaderboard)
+            .bind(player)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, player = %player))]
+    async fn is_banned(&self, leaderboard: Uuid, player: Uuid) -> bool {
+        sqlx::query("SELECT player FROM leaderboard_bans WHERE leaderboard = ?1 AND player = ?2")
+            .bind(leaderboard)
+            .bind(player)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_bans(&self, leaderboard: Uuid) -> Vec<Ban> {
+        sqlx::query_as::<_, (Uuid, String, Uuid)>(
+            "SELECT player, players.name, banned_by FROM leaderboard_bans, players WHERE leaderboard = ?1 AND leaderboard_bans.player = players.id",
+        )
+        .bind(leaderboard)
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| Ban {
+            player: r.0,
+            name: r.1,
+            banned_by: r.2,
+        })
+        .collect()
+    }
+}
+
+#[async_trait]
+impl AdminStore for SqliteStorage {
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn create_admin(&self, admin: &AdminAccount) -> bool {
+        sqlx::query("INSERT INTO admins (id) VALUES (?1)")
+            .bind(admin.id)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn create_anonymous_admin(&self, admin: &AdminAccount) -> bool {
+        sqlx::query("INSERT INTO admins (id, anonymous) VALUES (?1, TRUE)")
+            .bind(admin.id)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn admin_exists(&self, admin: &AdminAccount) -> bool {
+        sqlx::query("SELECT id FROM admins WHERE id = ?1")
+            .bind(admin.id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn is_admin_anonymous(&self, admin: &AdminAccount) -> bool {
+        sqlx::query_as::<_, (bool,)>("SELECT anonymous FROM admins WHERE id = ?1")
+            .bind(admin.id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| r.0)
+            .unwrap_or(false)
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn is_admin_active(&self, admin: &AdminAccount) -> bool {
+        sqlx::query_as::<_, (bool,)>("SELECT disabled FROM admins WHERE id = ?1")
+            .bind(admin.id)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| !r.0)
+            .unwrap_or(false)
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn set_admin_disabled(&self, admin: &AdminAccount, disabled: bool) -> bool {
+        sqlx::query("UPDATE admins SET disabled = ?2 WHERE id = ?1")
+            .bind(admin.id)
+            .bind(disabled)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| r.rows_affected() == 1)
+            .unwrap_or(false)
+    }
+
+    #[instrument(skip_all)]
+    async fn get_admins(&self) -> Vec<AdminSummary> {
+        sqlx::query_as::<_, (Uuid, bool, Option<String>)>(
+            "SELECT admins.id, disabled, login FROM admins LEFT JOIN admins_github ON admins.id = admins_github.admin_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| AdminSummary {
+            id: r.0,
+            disabled: r.1,
+            github: r.2,
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id, transfer_to = ?transfer_to))]
+    async fn delete_admin(&self, admin: &AdminAccount, transfer_to: Option<Uuid>) -> bool {
+        async {
+            let mut transaction = self.pool.begin().await?;
+            let owned = sqlx::query_as::<_, (Uuid, bool)>(
+                r#"
+                SELECT organizations.id, organizations.personal FROM organizations, organization_members
+                WHERE organizations.id = organization_members.organization AND admin = ?1 AND role = ?2
+                AND (SELECT count(admin) FROM organization_members AS owners WHERE owners.organization = organizations.id AND owners.role = ?2) = 1
+                "#,
+            )
+            .bind(admin.id)
+            .bind(Role::Owner.as_str())
+            .fetch_all(&mut *transaction)
+            .await?;
+
+            for (organization, personal) in owned {
+                match (transfer_to, personal) {
+                    (Some(recipient), true) => {
+                        sqlx::query(
+                            "UPDATE leaderboards SET organization = ?2 WHERE organization = ?1",
+                        )
+                        .bind(organization)
+                        .bind(recipient)
+                        .execute(&mut *transaction)
+                        .await?;
+                    }
+                    (Some(recipient), false) => {
+                        sqlx::query(
+                            "INSERT INTO organization_members (organization, admin, role) VALUES (?1, ?2, ?3) ON CONFLICT (organization, admin) DO UPDATE SET role = ?3",
+                        )
+                        .bind(organization)
+                        .bind(recipient)
+                        .bind(Role::Owner.as_str())
+                        .execute(&mut *transaction)
+                        .await?;
+                    }
+                    (None, _) => {
+                        sqlx::query(
+                            "DELETE FROM scores WHERE leaderboard IN (SELECT id FROM leaderboards WHERE organization = ?1)",
+                        )
+                        .bind(organization)
+                        .execute(&mut *transaction)
+                        .await?;
+                        sqlx::query(
+                            "DELETE FROM leaderboard_bans WHERE leaderboard IN (SELECT id FROM leaderboards WHERE organization = ?1)",
+                        )
+                        .bind(organization)
+                        .execute(&mut *transaction)
+                        .await?;
+                        sqlx::query("DELETE FROM leaderboards WHERE organization = ?1")
+                            .bind(organization)
+                            .execute(&mut *transaction)
+                            .await?;
+                    }
+                }
+                // a transferred organization keeps existing, unless it was personal
+                if transfer_to.is_none() || personal {
+                    sqlx::query("DELETE FROM organization_invitations WHERE organization = ?1")
+                        .bind(organization)
+                        .execute(&mut *transaction)
+                        .await?;
+                    sqlx::query("DELETE FROM organization_members WHERE organization = ?1")
+                        .bind(organization)
+                        .execute(&mut *transaction)
+                        .await?;
+                    sqlx::query("DELETE FROM organizations WHERE id = ?1")
+                        .bind(organization)
+                        .execute(&mut *transaction)
+                        .await?;
+                }
+            }
+
+            sqlx::query(
+                "UPDATE leaderboard_transfers SET status = ?2, resolved_at = CURRENT_TIMESTAMP WHERE to_admin = ?1 AND status = ?3",
+            )
+            .bind(admin.id)
+            .bind(TransferStatus::Cancelled.as_str())
+            .bind(TransferStatus::Pending.as_str())
+            .execute(&mut *transaction)
+            .await?;
+            sqlx::query("DELETE FROM organization_members WHERE admin = ?1")
+                .bind(admin.id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query("DELETE FROM admins_github WHERE admin_id = ?1")
+                .bind(admin.id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query("DELETE FROM admins WHERE id = ?1")
+                .bind(admin.id)
+                .execute(&mut *transaction)
+                .await?;
+            transaction.commit().await
+        }
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn get_admin_github(&self, admin: &AdminAccount) -> Option<GithubUser> {
+        sqlx::query_as::<_, (i64, String)>(
+            "SELECT id, login FROM admins_github WHERE admin_id = ?1",
+        )
+        .bind(admin.id)
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| GithubUser {
+            id: r.0 as u32,
+            login: r.1,
+        })
+        .ok()
+    }
+
+    #[instrument(skip_all, fields(github = %github.login, admin = %admin.id))]
+    async fn link_github(&self, github: &GithubUser, admin: &AdminAccount) -> bool {
+        sqlx::query("INSERT INTO admins_github (id, login, admin_id) VALUES (?1, ?2, ?3)")
+            .bind(github.id as i64)
+            .bind(&github.login)
+            .bind(admin.id)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin.id))]
+    async fn unlink_github(&self, admin: &AdminAccount) -> bool {
+        sqlx::query("DELETE FROM admins_github WHERE admin_id = ?1")
+            .bind(admin.id)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(github = %github.login))]
+    async fn github_exists(&self, github: &GithubUser) -> bool {
+        sqlx::query("SELECT id FROM admins_github WHERE id = ?1 AND login = ?2")
+            .bind(github.id as i64)
+            .bind(&github.login)
+            .fetch_one(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(github = %github.login))]
+    async fn get_github_admin(&self, github: &GithubUser) -> Option<AdminAccount> {
+        sqlx::query_as::<_, (Uuid,)>(
+            "SELECT admin_id FROM admins_github WHERE id = ?1 AND login = ?2",
+        )
+        .bind(github.id as i64)
+        .bind(&github.login)
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| AdminAccount { id: r.0 })
+        .ok()
+    }
+}
+
+#[async_trait]
+impl OrganizationStore for SqliteStorage {
+    #[instrument(skip_all, fields(organization = %organization.id, owner = %owner))]
+    async fn create_organization(&self, organization: &Organization, owner: Uuid) -> bool {
+        async {
+            let mut transaction = self.pool.begin().await?;
+            sqlx::query("INSERT INTO organizations (id, name, personal) VALUES (?1, ?2, ?3)")
+                .bind(organization.id)
+                .bind(&organization.name)
+                .bind(organization.personal)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query(
+                "INSERT INTO organization_members (organization, admin, role) VALUES (?1, ?2, ?3)",
+            )
+            .bind(organization.id)
+            .bind(owner)
+            .bind(Role::Owner.as_str())
+            .execute(&mut *transaction)
+            .await?;
+            transaction.commit().await
+        }
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_organization(&self, id: Uuid) -> Option<Organization> {
+        sqlx::query_as::<_, (Uuid, String, bool)>(
+            "SELECT id, name, personal FROM organizations WHERE id = ?1",
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| Organization {
+            id: r.0,
+            name: r.1,
+            personal: r.2,
+        })
+        .ok()
+    }
+
+    #[instrument(skip_all, fields(admin = %admin))]
+    async fn get_organizations(&self, admin: Uuid) -> Vec<OrganizationWithRole> {
+        sqlx::query_as::<_, (Uuid, String, bool, String)>(
+            "SELECT id, name, personal, role FROM organizations, organization_members WHERE organizations.id = organization_members.organization AND admin = ?1",
+        )
+        .bind(admin)
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|r| {
+            Some(OrganizationWithRole {
+                id: r.0,
+                name: r.1,
+                personal: r.2,
+                role: Role::parse(&r.3)?,
+            })
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn delete_organization(&self, id: Uuid) -> bool {
+        async {
+            let mut transaction = self.pool.begin().await?;
+            sqlx::query("DELETE FROM organization_invitations WHERE organization = ?1")
+                .bind(id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query("DELETE FROM organization_members WHERE organization = ?1")
+                .bind(id)
+                .execute(&mut *transaction)
+                .await?;
+            sqlx::query("DELETE FROM organizations WHERE id = ?1")
+                .bind(id)
+                .execute(&mut *transaction)
+                .await?;
+            transaction.commit().await
+        }
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization, admin = %admin))]
+    async fn get_role(&self, organization: Uuid, admin: Uuid) -> Option<Role> {
+        sqlx::query_as::<_, (String,)>(
+            "SELECT role FROM organization_members WHERE organization = ?1 AND admin = ?2",
+        )
+        .bind(organization)
+        .bind(admin)
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()
+        .and_then(|r| Role::parse(&r.0))
+    }
+
+    #[instrument(skip_all, fields(organization = %organization, admin = %admin))]
+    async fn set_role(&self, organization: Uuid, admin: Uuid, role: Role) -> bool {
+        sqlx::query(
+            "UPDATE organization_members SET role = ?3 WHERE organization = ?1 AND admin = ?2",
+        )
+        .bind(organization)
+        .bind(admin)
+        .bind(role.as_str())
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn get_members(&self, organization: Uuid) -> Vec<Member> {
+        sqlx::query_as::<_, (Uuid, String, Option<String>)>(
+            "SELECT admin, role, login FROM organization_members LEFT JOIN admins_github ON organization_members.admin = admins_github.admin_id WHERE organization = ?1",
+        )
+        .bind(organization)
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|r| {
+            Some(Member {
+                admin: r.0,
+                role: Role::parse(&r.1)?,
+                github: r.2,
+            })
+        })
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn count_owners(&self, organization: Uuid) -> i64 {
+        sqlx::query_as::<_, (i64,)>(
+            "SELECT count(admin) FROM organization_members WHERE organization = ?1 AND role = ?2",
+        )
+        .bind(organization)
+        .bind(Role::Owner.as_str())
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| r.0)
+        .unwrap_or_default()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization, admin = %admin))]
+    async fn remove_member(&self, organization: Uuid, admin: Uuid) -> bool {
+        sqlx::query("DELETE FROM organization_members WHERE organization = ?1 AND admin = ?2")
+            .bind(organization)
+            .bind(admin)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+
+    #[instrument(skip_all, fields(invitation = %invitation.id, organization = %invitation.organization))]
+    async fn create_invitation(&self, invitation: &Invitation) -> bool {
+        sqlx::query(
+            "INSERT INTO organization_invitations (id, organization, role, github_login, email, invited_by) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )
+        .bind(invitation.id)
+        .bind(invitation.organization)
+        .bind(invitation.role.as_str())
+        .bind(&invitation.github_login)
+        .bind(&invitation.email)
+        .bind(invitation.invited_by)
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_invitation(&self, id: Uuid) -> Option<Invitation> {
+        sqlx::query_as::<_, InvitationRow>(
+            "SELECT id, organization, role, github_login, email, invited_by FROM organization_invitations WHERE id = ?1",
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()
+        .and_then(invitation_from_row)
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn get_invitations_for_organization(&self, organization: Uuid) -> Vec<Invitation> {
+        sqlx::query_as::<_, InvitationRow>(
+            "SELECT id, organization, role, github_login, email, invited_by FROM organization_invitations WHERE organization = ?1",
+        )
+        .bind(organization)
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(invitation_from_row)
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(login))]
+    async fn get_invitations_for_github(&self, login: &str) -> Vec<Invitation> {
+        sqlx::query_as::<_, InvitationRow>(
+            "SELECT id, organization, role, github_login, email, invited_by FROM organization_invitations WHERE lower(github_login) = lower(?1)",
+        )
+        .bind(login)
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(invitation_from_row)
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(invitation = %invitation.id, organization = %invitation.organization, admin = %admin))]
+    async fn accept_invitation(&self, invitation: &Invitation, admin: Uuid) -> bool {
+        async {
+            let mut transaction = self.pool.begin().await?;
+            sqlx::query(
+                "INSERT INTO organization_members (organization, admin, role) VALUES (?1, ?2, ?3) ON CONFLICT (organization, admin) DO NOTHING",
+            )
+            .bind(invitation.organization)
+            .bind(admin)
+            .bind(invitation.role.as_str())
+            .execute(&mut *transaction)
+            .await?;
+            sqlx::query("DELETE FROM organization_invitations WHERE id = ?1")
+                .bind(invitation.id)
+                .execute(&mut *transaction)
+                .await?;
+            transaction.commit().await
+        }
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization, id = %id))]
+    async fn delete_invitation(&self, organization: Uuid, id: Uuid) -> bool {
+        sqlx::query("DELETE FROM organization_invitations WHERE organization = ?1 AND id = ?2")
+            .bind(organization)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .is_ok()
+    }
+}
+
+#[async_trait]
+impl AuditStore for SqliteStorage {
+    #[instrument(skip_all, fields(actor = %actor, organization = ?organization, target = ?target))]
+    async fn record_audit(
+        &self,
+        actor: Uuid,
+        organization: Option<Uuid>,
+        action: AuditAction,
+        target: Option<Uuid>,
+        payload: Value,
+    ) -> bool {
+        sqlx::query(
+            "INSERT INTO audit_log (actor, organization, action, target, payload) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(actor)
+        .bind(organization)
+        .bind(action.as_str())
+        .bind(target)
+        .bind((!payload.is_null()).then(|| payload.to_string()))
+        .execute(&self.pool)
+        .await
+        .map_err(|error| {
+            error!(%error, action = action.as_str(), %actor, "failed to record an audit entry")
+        })
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(organization = %organization))]
+    async fn get_audit_page(&self, organization: Uuid, query: &AuditQuery) -> AuditPage {
+        let page = query.page.unwrap_or(1).max(1);
+        let per_page = query
+            .per_page
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        let action = query.action.map(|action| action.as_str());
+
+        let total = sqlx::query_as::<_, (i64,)>(
+            "SELECT count(id) FROM audit_log WHERE organization = ?1 AND (?2 IS NULL OR actor = ?2) AND (?3 IS NULL OR action = ?3) AND (?4 IS NULL OR target = ?4)",
+        )
+        .bind(organization)
+        .bind(query.actor)
+        .bind(action)
+        .bind(query.target)
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| r.0)
+        .unwrap_or_default();
+
+        let entries = sqlx::query_as::<
+            _,
+            (
+                i64,
+                PrimitiveDateTime,
+                Uuid,
+                Option<Uuid>,
+                String,
+                Option<Uuid>,
+                Option<String>,
+            ),
+        >(
+            "SELECT id, timestamp, actor, organization, action, target, payload FROM audit_log WHERE organization = ?1 AND (?2 IS NULL OR actor = ?2) AND (?3 IS NULL OR action = ?3) AND (?4 IS NULL OR target = ?4) ORDER BY id DESC LIMIT ?5 OFFSET ?6",
+        )
+        .bind(organization)
+        .bind(query.actor)
+        .bind(action)
+        .bind(query.target)
+        .bind(per_page)
+        .bind((page - 1) * per_page)
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| AuditEntry {
+            id: r.0,
+            timestamp: format_timestamp(r.1),
+            actor: r.2,
+            organization: r.3,
+            action: r.4,
+            target: r.5,
+            payload: r.6.and_then(|payload| serde_json::from_str(&payload).ok()),
+        })
+        .collect();
+
+        AuditPage {
+            entries,
+            page,
+            per_page,
+            total,
+        }
+    }
+}
+
+#[async_trait]
+impl TransferStore for SqliteStorage {
+    #[instrument(skip_all, fields(transfer = %transfer.id, leaderboard = %transfer.leaderboard))]
+    async fn create_transfer(&self, transfer: &Transfer) -> bool {
+        sqlx::query(
+            "INSERT INTO leaderboard_transfers (id, leaderboard, from_organization, to_admin, requested_by, status) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )
+        .bind(transfer.id)
+        .bind(transfer.leaderboard)
+        .bind(transfer.from_organization)
+        .bind(transfer.to_admin)
+        .bind(transfer.requested_by)
+        .bind(transfer.status.as_str())
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_transfer(&self, id: Uuid) -> Option<Transfer> {
+        sqlx::query_as::<_, TransferRow>(
+            "SELECT id, leaderboard, from_organization, to_admin, requested_by, status FROM leaderboard_transfers WHERE id = ?1",
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()
+        .and_then(transfer_from_row)
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_pending_transfer_for_leaderboard(&self, leaderboard: Uuid) -> Option<Transfer> {
+        sqlx::query_as::<_, TransferRow>(
+            "SELECT id, leaderboard, from_organization, to_admin, requested_by, status FROM leaderboard_transfers WHERE leaderboard = ?1 AND status = ?2",
+        )
+        .bind(leaderboard)
+        .bind(TransferStatus::Pending.as_str())
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()
+        .and_then(transfer_from_row)
+    }
+
+    #[instrument(skip_all, fields(admin = %admin))]
+    async fn get_pending_transfers_for_admin(&self, admin: Uuid) -> Vec<Transfer> {
+        sqlx::query_as::<_, TransferRow>(
+            "SELECT id, leaderboard, from_organization, to_admin, requested_by, status FROM leaderboard_transfers WHERE to_admin = ?1 AND status = ?2",
+        )
+        .bind(admin)
+        .bind(TransferStatus::Pending.as_str())
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(transfer_from_row)
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(transfer = %transfer.id, leaderboard = %transfer.leaderboard))]
+    async fn resolve_transfer(&self, transfer: &Transfer, status: TransferStatus) -> bool {
+        sqlx::query(
+            "UPDATE leaderboard_transfers SET status = ?2, resolved_at = CURRENT_TIMESTAMP WHERE id = ?1 AND status = ?3",
+        )
+        .bind(transfer.id)
+        .bind(status.as_str())
+        .bind(TransferStatus::Pending.as_str())
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| r.rows_affected() == 1)
+        .unwrap_or(false)
+    }
+
+    #[instrument(skip_all, fields(transfer = %transfer.id, leaderboard = %transfer.leaderboard, organization = %organization))]
+    async fn accept_transfer(&self, transfer: &Transfer, organization: Uuid) -> Option<bool> {
+        let mut transaction = self.pool.begin().await.inspect_err(log_error).ok()?;
+        let moved = sqlx::query(
+            "UPDATE leaderboards SET organization = ?3 WHERE id = ?1 AND organization = ?2",
+        )
+        .bind(transfer.leaderboard)
+        .bind(transfer.from_organization)
+        .bind(organization)
+        .execute(&mut *transaction)
+        .await
+        .inspect_err(log_error)
+        .ok()?
+        .rows_affected();
+        let status = if moved == 1 {
+            TransferStatus::Accepted
+        } else {
+            TransferStatus::Cancelled
+        };
+        sqlx::query(
+            "UPDATE leaderboard_transfers SET status = ?2, resolved_at = CURRENT_TIMESTAMP WHERE id = ?1",
+        )
+        .bind(transfer.id)
+        .bind(status.as_str())
+        .execute(&mut *transaction)
+        .await
+        .inspect_err(log_error)
+        .ok()?;
+        transaction.commit().await.inspect_err(log_error).ok()?;
+        Some(moved == 1)
+    }
+}
+
+#[async_trait]
+impl WebhookStore for SqliteStorage {
+    #[instrument(skip_all, fields(webhook = %webhook.id, leaderboard = %webhook.leaderboard))]
+    async fn create_webhook(&self, webhook: &Webhook) -> bool {
+        sqlx::query(
+            "INSERT INTO webhooks (id, leaderboard, url, secret, events, format, created_by) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )
+        .bind(webhook.id)
+        .bind(webhook.leaderboard)
+        .bind(&webhook.url)
+        .bind(&webhook.secret)
+        .bind(WebhookEvent::join(&webhook.events))
+        .bind(webhook.format.as_str())
+        .bind(webhook.created_by)
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    async fn get_webhook(&self, id: Uuid) -> Option<Webhook> {
+        sqlx::query_as::<_, WebhookRow>(
+            "SELECT id, leaderboard, url, secret, events, format, created_by FROM webhooks WHERE id = ?1",
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .ok()
+        .and_then(webhook_from_row)
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard))]
+    async fn get_webhooks(&self, leaderboard: Uuid) -> Vec<Webhook> {
+        sqlx::query_as::<_, WebhookRow>(
+            "SELECT id, leaderboard, url, secret, events, format, created_by FROM webhooks WHERE leaderboard = ?1 ORDER BY created_at",
+        )
+        .bind(leaderboard)
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(webhook_from_row)
+        .collect()
+    }
+
+    #[instrument(skip_all, fields(leaderboard = %leaderboard, id = %id))]
+    async fn delete_webhook(&self, leaderboard: Uuid, id: Uuid) -> bool {
+        sqlx::query("DELETE FROM webhooks WHERE id = ?1 AND leaderboard = ?2")
+            .bind(id)
+            .bind(leaderboard)
+            .execute(&self.pool)
+            .await
+            .inspect_err(log_error)
+            .map(|r| r.rows_affected() == 1)
+            .unwrap_or(false)
+    }
+
+    #[instrument(skip_all, fields(delivery = %delivery.id, webhook = %delivery.webhook))]
+    async fn create_delivery(&self, delivery: &Delivery) -> bool {
+        sqlx::query(
+            "INSERT INTO webhook_deliveries (id, webhook, event, payload, status, attempts) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )
+        .bind(delivery.id)
+        .bind(delivery.webhook)
+        .bind(delivery.event.as_str())
+        .bind(delivery.payload.to_string())
+        .bind(delivery.status.as_str())
+        .bind(delivery.attempts)
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .is_ok()
+    }
+
+    #[instrument(skip_all, fields(delivery = %delivery.id, webhook = %delivery.webhook))]
+    async fn update_delivery(&self, delivery: &Delivery) -> bool {
+        sqlx::query(
+            "UPDATE webhook_deliveries SET status = ?2, attempts = ?3, response_status = ?4, error = ?5, last_attempt_at = CURRENT_TIMESTAMP WHERE id = ?1",
+        )
+        .bind(delivery.id)
+        .bind(delivery.status.as_str())
+        .bind(delivery.attempts)
+        .bind(delivery.response_status)
+        .bind(&delivery.error)
+        .execute(&self.pool)
+        .await
+        .inspect_err(log_error)
+        .map(|r| r.rows_affected() == 1)
+        .unwrap_or(false)
+    }
+
+    #[instrument(skip_all, fields(webhook = %webhook))]
+    async fn get_deliveries(&self, webhook: Uuid, limit: i64) -> Vec<Delivery> {
+        sqlx::query_as::<_, DeliveryRow>(
+            "SELECT id, webhook, event, payload, status, attempts, response_status, error, created_at, last_attempt_at FROM webhook_deliveries WHERE webhook = ?1 ORDER BY created_at DESC, rowid DESC LIMIT ?2",
+        )
+        .bind(webhook)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(delivery_from_row)
+        .collect()
+    }
+
+    #[instrument(skip_all)]
+    async fn get_pending_deliveries(&self) -> Vec<Delivery> {
+        sqlx::query_as::<_, DeliveryRow>(
+            "SELECT id, webhook, event, payload, status, attempts, response_status, error, created_at, last_attempt_at FROM webhook_deliveries WHERE status = ?1 ORDER BY created_at, rowid",
+        )
+        .bind(DeliveryStatus::Pending.as_str())
+        .fetch_all(&self.pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(delivery_from_row)
+        .collect()
+    }
+}
+
+#[async_trait]
+impl StatusStore for SqliteStorage {
+    fn pool_stats(&self) -> Option<PoolStats> {
+        Some(PoolStats {
+            connections: self.pool.size(),
+            idle: self.pool.num_idle() as u32,
+        })
+    }
+
+    async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT 1")
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+    }
+
+    async fn schema_version(&self) -> Result<Option<i64>, sqlx::Error> {
+        let mut connection = self.pool.acquire().await?;
+        if !has_migrations_table(&mut connection).await? {
+            return Ok(None);
+        }
+        sqlx::query_scalar("SELECT max(version) FROM _sqlx_migrations WHERE success")
+            .fetch_one(&mut *connection)
+            .await
+    }
+
+    fn expected_schema_version(&self) -> Option<i64> {
+        MIGRATOR.iter().map(|migration| migration.version).max()
+    }
+}
@@ -0,0 +1,154 @@
+//! Logs and traces of the server, built on `tracing`.
+//!
+//! Each request runs in a `request` span carrying its id, taken from the `X-Request-Id` header when
+//! the client or a proxy sent a valid one, or generated otherwise. The id is sent back in the
+//! response. Storage calls run in their own spans, nested in the request, and the queries logged
+//! by sqlx are attached to them.
+//!
+//! Logs go to stdout, as JSON or text. Spans can also be exported to an OpenTelemetry collector
+//! over OTLP/HTTP.
+
+use std::time::Instant;
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::header::{HeaderName, HeaderValue},
+    middleware::Next,
+    Error, HttpMessage,
+};
+use opentelemetry::{trace::TracerProvider, KeyValue};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{trace::SdkTracerProvider, Resource};
+use tracing::{error, field::Empty, info, info_span, warn, Instrument};
+use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use uuid::Uuid;
+
+use crate::configuration::{LogFormat, LogSettings};
+
+pub const REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+/// Longest request id accepted from a client.
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// Id of the request being handled, available in the request extensions.
+#[derive(Clone, Debug)]
+pub struct RequestId(pub String);
+
+/// Keeps the exporter running. Dropping it flushes the spans not exported yet.
+pub struct Telemetry {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            if let Err(error) = provider.shutdown() {
+                eprintln!("Failed to export the last spans: {}", error);
+            }
+        }
+    }
+}
+
+/// Install the global subscriber. Must only be called once.
+pub fn init(settings: &LogSettings) -> Telemetry {
+    let provider = settings.otlp_endpoint.clone().map(|endpoint| {
+        // the exporter uses a blocking client, that can't be created in an async context
+        std::thread::spawn(move || {
+            let exporter = SpanExporter::builder()
+                .with_http()
+                .with_endpoint(endpoint)
+                .build()
+                .expect("Failed to create the OTLP exporter.");
+            SdkTracerProvider::builder()
+                .with_batch_exporter(exporter)
+                .with_resource(
+                    Resource::builder()
+                        .with_service_name("jornet-server")
+                        .with_attribute(KeyValue::new("service.version", env!("CARGO_PKG_VERSION")))
+                        .build(),
+                )
+                .build()
+        })
+        .join()
+        .unwrap()
+    });
+
+    let json = settings.format == LogFormat::Json;
+    tracing_subscriber::registry()
+        .with(EnvFilter::new(&settings.filter))
+        .with(json.then(|| fmt::layer().json()))
+        .with((!json).then(fmt::layer))
+        .with(provider.as_ref().map(|provider| {
+            tracing_opentelemetry::layer().with_tracer(provider.tracer("jornet-server"))
+        }))
+        .init();
+
+    Telemetry { provider }
+}
+
+fn valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LENGTH
+        && id
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"-_.:".contains(&byte))
+}
+
+/// Middleware running each request in its span, and logging its result.
+pub async fn trace_request(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| valid_request_id(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    request
+        .extensions_mut()
+        .insert(RequestId(request_id.clone()));
+
+    let span = info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.path(),
+        route = Empty,
+        status = Empty,
+    );
+    let start = Instant::now();
+
+    let response = next.call(request).instrument(span.clone()).await;
+
+    let _entered = span.enter();
+    let latency_ms = start.elapsed().as_millis() as u64;
+    match response {
+        Ok(mut response) => {
+            let status = response.status();
+            if let Some(route) = response.request().match_pattern() {
+                span.record("route", route);
+            }
+            span.record("status", status.as_u16());
+            // errors returned by handlers and extractors are kept in the response
+            match response.response().error() {
+                Some(error) if status.is_server_error() => {
+                    error!(latency_ms, error = %error, "request failed")
+                }
+                Some(error) => warn!(latency_ms, error = %error, "request rejected"),
+                None if status.is_server_error() => error!(latency_ms, "request failed"),
+                None => info!(latency_ms, "request completed"),
+            }
+            response.headers_mut().insert(
+                REQUEST_ID,
+                HeaderValue::from_str(&request_id).expect("request ids are valid headers"),
+            );
+            Ok(response)
+        }
+        Err(error) => {
+            error!(latency_ms, error = %error, "request failed");
+            Err(error)
+        }
+    }
+}
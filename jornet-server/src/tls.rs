@@ -0,0 +1,149 @@
+//! HTTPS served by the server itself, with rustls, for deployments without a reverse proxy.
+//!
+//! The certificate and its key are read again from their files on SIGHUP, so that a renewed
+//! certificate is used without a restart. If they can't be read, the previous ones are kept.
+//! Requests received on the plain HTTP port, when one is set, are redirected to HTTPS.
+
+use std::sync::{Arc, RwLock};
+
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::header,
+    middleware::Next,
+    web, Error, HttpResponse,
+};
+use rustls::{
+    crypto::{ring, CryptoProvider},
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+    server::{ClientHello, ResolvesServerCert},
+    sign::CertifiedKey,
+    ServerConfig,
+};
+use tracing::{error, info};
+
+use crate::configuration::{Settings, TlsSettings};
+
+/// Gives the current certificate to new connections.
+#[derive(Debug)]
+pub struct CertificateResolver {
+    settings: TlsSettings,
+    provider: Arc<CryptoProvider>,
+    current: RwLock<Arc<CertifiedKey>>,
+}
+
+impl CertificateResolver {
+    pub fn load(settings: &TlsSettings) -> Result<Self, String> {
+        let provider = Arc::new(ring::default_provider());
+        let current = read_certificate(settings, &provider)?;
+        Ok(Self {
+            settings: settings.clone(),
+            provider,
+            current: RwLock::new(Arc::new(current)),
+        })
+    }
+
+    /// Read the certificate and its key again from their files.
+    pub fn reload(&self) -> Result<(), String> {
+        let certificate = read_certificate(&self.settings, &self.provider)?;
+        *self.current.write().unwrap() = Arc::new(certificate);
+        Ok(())
+    }
+
+    pub fn server_config(self: Arc<Self>) -> ServerConfig {
+        ServerConfig::builder_with_provider(self.provider.clone())
+            .with_safe_default_protocol_versions()
+            .expect("the default protocol versions are supported")
+            .with_no_client_auth()
+            .with_cert_resolver(self)
+    }
+}
+
+impl ResolvesServerCert for CertificateResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.current.read().unwrap().clone())
+    }
+}
+
+fn read_certificate(
+    settings: &TlsSettings,
+    provider: &CryptoProvider,
+) -> Result<CertifiedKey, String> {
+    let chain = CertificateDer::pem_file_iter(&settings.certificate_path)
+        .and_then(|certificates| certificates.collect::<Result<Vec<_>, _>>())
+        .map_err(|error| format!("{}: {}", settings.certificate_path, error))?;
+    if chain.is_empty() {
+        return Err(format!(
+            "{}: no certificate found",
+            settings.certificate_path
+        ));
+    }
+    let key = PrivateKeyDer::from_pem_file(&settings.key_path)
+        .map_err(|error| format!("{}: {}", settings.key_path, error))?;
+    let key = provider
+        .key_provider
+        .load_private_key(key)
+        .map_err(|error| format!("{}: {}", settings.key_path, error))?;
+    let certificate = CertifiedKey::new(chain, key);
+    certificate
+        .keys_match()
+        .map_err(|_| "the private key doesn't match the certificate".to_string())?;
+    Ok(certificate)
+}
+
+/// Reload the certificate each time the process receives SIGHUP.
+pub fn reload_on_hangup(resolver: Arc<CertificateResolver>) {
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = signal(SignalKind::hangup()).expect("Failed to listen to SIGHUP");
+        while hangup.recv().await.is_some() {
+            match resolver.reload() {
+                Ok(()) => info!("TLS certificate reloaded"),
+                Err(error) => {
+                    error!(%error, "failed to reload the TLS certificate, keeping the previous one")
+                }
+            }
+        }
+    });
+}
+
+/// Middleware redirecting the requests received over plain HTTP to the same URL in HTTPS.
+pub async fn redirect_to_https(
+    request: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    if request.app_config().secure() {
+        return next
+            .call(request)
+            .await
+            .map(ServiceResponse::map_into_left_body);
+    }
+
+    let port = request
+        .app_data::<web::Data<Settings>>()
+        .map(|config| config.application_port)
+        .unwrap_or(443);
+    let host = request.connection_info().host().to_string();
+    // without the port, unless it's the end of an IPv6 address
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if !port.ends_with(']') => name.to_string(),
+        _ => host,
+    };
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+    let location = match port {
+        443 => format!("https://{}{}", host, path),
+        port => format!("https://{}:{}{}", host, port, path),
+    };
+
+    // keeps the method, for scores sent with POST
+    let response = HttpResponse::PermanentRedirect()
+        .insert_header((header::LOCATION, location))
+        .finish();
+    Ok(request.into_response(response).map_into_right_body())
+}
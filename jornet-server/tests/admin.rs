@@ -1,5 +1,8 @@
-use jornet_server::domains::admin::TokenReply;
-use serde::Serialize;
+use jornet_server::domains::{
+    admin::LinkedIdentity,
+    organization::{Organization, OrganizationInput, Role},
+    superadmin::AdminSummary,
+};
 use uuid::Uuid;
 
 mod helper;
@@ -10,7 +13,7 @@ async fn not_authenticated() {
     let client = reqwest::Client::new();
 
     let response = client
-        .get(&format!("{}/api/v1/admin/whoami", app.address))
+        .get(format!("{}/api/v1/admin/whoami", app.address))
         .send()
         .await
         .expect("Failed to execute request.");
@@ -19,19 +22,14 @@ async fn not_authenticated() {
     assert_eq!(Some(0), response.content_length());
 }
 
-#[derive(Serialize)]
-struct UuidInput {
-    uuid: Uuid,
-}
-
 #[tokio::test]
 async fn get_test_token() {
     let app = helper::spawn_app().await;
     let client = reqwest::Client::new();
 
     let response = client
-        .post(&format!("{}/oauth/by_uuid", app.address))
-        .json(&UuidInput {
+        .post(format!("{}/oauth/by_uuid", app.address))
+        .json(&helper::UuidInput {
             uuid: Uuid::new_v4(),
         })
         .send()
@@ -40,10 +38,7 @@ async fn get_test_token() {
 
     assert!(response.status().is_success());
 
-    sqlx::query!("SELECT id FROM admins",)
-        .fetch_one(&app.db_pool)
-        .await
-        .expect("Failed to fetch saved subscription.");
+    assert_eq!(app.storage.get_admins().await.len(), 1);
 }
 
 #[tokio::test]
@@ -51,24 +46,188 @@ async fn use_test_token() {
     let app = helper::spawn_app().await;
     let client = reqwest::Client::new();
 
-    let token = client
-        .post(&format!("{}/oauth/by_uuid", app.address))
-        .json(&UuidInput {
-            uuid: Uuid::new_v4(),
+    let token = helper::new_admin(&app, &client).await;
+
+    let response = client
+        .get(format!("{}/api/v1/admin/whoami", app.address))
+        .bearer_auth(token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn list_identities() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let admin = Uuid::new_v4();
+    let token = helper::login(&app, &client, admin).await;
+
+    let identities = client
+        .get(format!("{}/api/v1/admin/identities", app.address))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<LinkedIdentity>>()
+        .await
+        .expect("valid identities");
+
+    assert_eq!(identities, vec![LinkedIdentity::Uuid { id: admin }]);
+}
+
+#[tokio::test]
+async fn unlink_missing_github() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let token = helper::login(&app, &client, Uuid::new_v4()).await;
+
+    let response = client
+        .delete(format!("{}/api/v1/admin/github", app.address))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(404, response.status());
+}
+
+#[tokio::test]
+async fn delete_account_with_leaderboards() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let token = helper::login(&app, &client, Uuid::new_v4()).await;
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
+
+    let response = client
+        .delete(format!("{}/api/v1/admin", app.address))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    assert!(app.storage.get_admins().await.is_empty());
+    assert!(app.storage.get_leaderboard(leaderboard.id).await.is_none());
+
+    // the token of a deleted account can't be used anymore
+    let response = client
+        .get(format!("{}/api/v1/admin/whoami", app.address))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(401, response.status());
+}
+
+#[tokio::test]
+async fn delete_account_transfers_leaderboards() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let token = helper::login(&app, &client, Uuid::new_v4()).await;
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
+    let organization = client
+        .post(format!("{}/api/v1/organizations", app.address))
+        .bearer_auth(&token)
+        .json(&OrganizationInput {
+            name: "my team".to_string(),
         })
         .send()
         .await
         .expect("Failed to execute request.")
-        .json::<TokenReply>()
+        .json::<Organization>()
         .await
-        .expect("got body");
+        .expect("valid organization");
+
+    let recipient = Uuid::new_v4();
+    helper::login(&app, &client, recipient).await;
 
     let response = client
-        .get(&format!("{}/api/v1/admin/whoami", app.address))
-        .bearer_auth(token.token)
+        .delete(format!(
+            "{}/api/v1/admin?transfer_to={}",
+            app.address, recipient
+        ))
+        .bearer_auth(&token)
         .send()
         .await
         .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    assert_eq!(
+        app.storage
+            .get_leaderboard_organization(leaderboard.id)
+            .await,
+        Some(recipient)
+    );
+
+    let members = app.storage.get_members(organization.id).await;
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].admin, recipient);
+    assert_eq!(members[0].role, Role::Owner);
+}
+
+#[tokio::test]
+async fn superadmin_disables_admin() {
+    let superadmin = Uuid::new_v4();
+    let mut configuration = helper::configuration();
+    configuration.superadmins = vec![superadmin];
+    let app = helper::spawn_app_with_configuration(configuration).await;
+    let client = reqwest::Client::new();
+
+    let superadmin_token = helper::login(&app, &client, superadmin).await;
+    let admin = Uuid::new_v4();
+    let admin_token = helper::login(&app, &client, admin).await;
 
+    let response = client
+        .get(format!("{}/api/v1/superadmin/admins", app.address))
+        .bearer_auth(&admin_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(401, response.status());
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/superadmin/admins/{}/disable",
+            app.address, admin
+        ))
+        .bearer_auth(&superadmin_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
     assert!(response.status().is_success());
+
+    let admins = client
+        .get(format!("{}/api/v1/superadmin/admins", app.address))
+        .bearer_auth(&superadmin_token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<AdminSummary>>()
+        .await
+        .expect("valid admins");
+    assert_eq!(admins.len(), 2);
+    assert!(admins.iter().any(|a| a.id == admin && a.disabled));
+
+    let response = client
+        .get(format!("{}/api/v1/admin/whoami", app.address))
+        .bearer_auth(&admin_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(401, response.status());
+
+    let response = client
+        .post(format!("{}/oauth/by_uuid", app.address))
+        .json(&helper::UuidInput { uuid: admin })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(403, response.status());
 }
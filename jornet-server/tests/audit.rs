@@ -0,0 +1,123 @@
+use jornet_server::domains::audit::AuditPage;
+use uuid::Uuid;
+
+mod helper;
+
+#[tokio::test]
+async fn delete_all_scores_is_recorded() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let admin = Uuid::new_v4();
+    let token = helper::login(&app, &client, admin).await;
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
+
+    client
+        .delete(format!(
+            "{}/api/v1/leaderboards/{}/scores",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // the personal organization of an admin shares its id
+    let audit = client
+        .get(format!(
+            "{}/api/v1/organizations/{}/audit?action=scores_delete_all",
+            app.address, admin
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<AuditPage>()
+        .await
+        .expect("valid audit page");
+
+    assert_eq!(audit.total, 1);
+    assert_eq!(audit.entries[0].actor, admin);
+    assert_eq!(audit.entries[0].action, "scores_delete_all");
+    assert_eq!(audit.entries[0].target, Some(leaderboard.id));
+}
+
+#[tokio::test]
+async fn audit_log_is_paginated() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let admin = Uuid::new_v4();
+    let token = helper::login(&app, &client, admin).await;
+    for _ in 0..3 {
+        helper::create_leaderboard(&app, &client, &token).await;
+    }
+
+    let first_page = client
+        .get(format!(
+            "{}/api/v1/organizations/{}/audit?per_page=2",
+            app.address, admin
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<AuditPage>()
+        .await
+        .expect("valid audit page");
+    assert_eq!(first_page.total, 3);
+    assert_eq!(first_page.entries.len(), 2);
+
+    let second_page = client
+        .get(format!(
+            "{}/api/v1/organizations/{}/audit?per_page=2&page=2",
+            app.address, admin
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<AuditPage>()
+        .await
+        .expect("valid audit page");
+    assert_eq!(second_page.entries.len(), 1);
+    assert!(second_page.entries[0].id < first_page.entries[1].id);
+}
+
+#[tokio::test]
+async fn audit_log_needs_membership() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let admin = Uuid::new_v4();
+    let token = helper::login(&app, &client, admin).await;
+    helper::create_leaderboard(&app, &client, &token).await;
+
+    let other_token = helper::login(&app, &client, Uuid::new_v4()).await;
+    let response = client
+        .get(format!(
+            "{}/api/v1/organizations/{}/audit",
+            app.address, admin
+        ))
+        .bearer_auth(&other_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status());
+}
+
+#[tokio::test]
+async fn audit_log_is_append_only() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let token = helper::login(&app, &client, Uuid::new_v4()).await;
+    helper::create_leaderboard(&app, &client, &token).await;
+
+    assert!(app
+        .execute("UPDATE audit_log SET action = 'nothing'")
+        .await
+        .is_err());
+    assert!(app.execute("DELETE FROM audit_log").await.is_err());
+}
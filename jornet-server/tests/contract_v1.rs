@@ -0,0 +1,197 @@
+//! The requests of version 1 exactly as `bevy_jornet` 0.11 sends them, and what it needs in the
+//! responses. Games shipped with it can't be updated, these must keep passing.
+
+use jornet_server::domains::{leaderboard::Leaderboard, player::Player};
+use reqwest::{header, StatusCode};
+use serde_json::{json, Value};
+use uuid::{uuid, Uuid};
+
+mod helper;
+
+const PLAYER_ID: Uuid = uuid!("5b7c8f1e-3d2a-4c6b-9e8f-1a2b3c4d5e6f");
+const PLAYER_KEY: Uuid = uuid!("0f1e2d3c-4b5a-4968-8776-a5b4c3d2e1f0");
+const LEADERBOARD_KEY: Uuid = uuid!("c0ffee00-1234-4abc-8def-0123456789ab");
+
+/// 42.5 with the meta `level 3` at 1700000000, signed by the player.
+const SCORE: &str = r#"{"score":42.5,"player":"5b7c8f1e-3d2a-4c6b-9e8f-1a2b3c4d5e6f","meta":"level 3","timestamp":1700000000,"k":"bee023c62b863bf63bcb434f47ff44f0d9496c0a8259f5e672b4a6358509a5c3"}"#;
+/// 1337 without meta at 1700000001, signed by the player.
+const OTHER_SCORE: &str = r#"{"score":1337.0,"player":"5b7c8f1e-3d2a-4c6b-9e8f-1a2b3c4d5e6f","meta":null,"timestamp":1700000001,"k":"b8f50c367fc93a4d910766a1e1f3df32b55a6c417062d24071bc63ea6bc94785"}"#;
+
+/// A leaderboard with a known key, and a player with a known key.
+async fn setup(app: &helper::TestApp, client: &reqwest::Client) -> Leaderboard {
+    let token = helper::new_admin(app, client).await;
+
+    let leaderboard = helper::create_leaderboard(app, client, &token).await;
+    assert!(
+        app.storage
+            .set_leaderboard_key(leaderboard.id, LEADERBOARD_KEY)
+            .await
+    );
+    assert!(
+        app.storage
+            .create_player(&Player {
+                id: PLAYER_ID,
+                name: "bevy".to_string(),
+                key: PLAYER_KEY,
+            })
+            .await
+    );
+    leaderboard
+}
+
+/// Send a body as `ureq::post(..).send_json(..)` does.
+async fn post_json(client: &reqwest::Client, url: String, body: &str) -> reqwest::Response {
+    client
+        .post(url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn create_player() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    for body in [r#"{"name":null}"#, r#"{"name":"bevy"}"#] {
+        let response = post_json(&client, format!("{}/api/v1/players", app.address), body).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let player: Value = response.json().await.unwrap();
+        let fields = player.as_object().unwrap();
+        assert_eq!(fields.len(), 3);
+        assert!(Uuid::parse_str(player["id"].as_str().unwrap()).is_ok());
+        assert!(Uuid::parse_str(player["key"].as_str().unwrap()).is_ok());
+        assert!(!player["name"].as_str().unwrap().is_empty());
+    }
+    let response = post_json(
+        &client,
+        format!("{}/api/v1/players", app.address),
+        r#"{"name":"bevy"}"#,
+    )
+    .await;
+    assert_eq!(response.json::<Value>().await.unwrap()["name"], "bevy");
+}
+
+#[tokio::test]
+async fn send_score() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let leaderboard = setup(&app, &client).await;
+    let url = format!("{}/api/v1/scores/{}", app.address, leaderboard.id);
+
+    let response = post_json(&client, url.clone(), SCORE).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    // read as `()` by the game
+    assert_eq!(response.text().await.unwrap(), "null");
+
+    let response = post_json(&client, url.clone(), OTHER_SCORE).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // only a success is checked by the game
+    let response = post_json(&client, url.clone(), SCORE).await;
+    assert!(!response.status().is_success());
+    let tampered = SCORE.replace("42.5", "43.5");
+    let response = post_json(&client, url, &tampered).await;
+    assert!(!response.status().is_success());
+}
+
+#[tokio::test]
+async fn refresh_leaderboard() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let leaderboard = setup(&app, &client).await;
+    let url = format!("{}/api/v1/scores/{}", app.address, leaderboard.id);
+    post_json(&client, url.clone(), SCORE).await;
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[header::ETAG]
+        .to_str()
+        .unwrap()
+        .to_string();
+
+    let scores: Value = response.json().await.unwrap();
+    let scores = scores.as_array().unwrap();
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].as_object().unwrap().len(), 4);
+    assert_eq!(scores[0]["score"], json!(42.5));
+    assert_eq!(scores[0]["player"], "bevy");
+    assert_eq!(scores[0]["meta"], "level 3");
+    assert!(scores[0]["timestamp"].is_string());
+
+    let response = client
+        .get(&url)
+        .header("If-None-Match", &etag)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    post_json(&client, url.clone(), OTHER_SCORE).await;
+    let response = client
+        .get(&url)
+        .header("If-None-Match", &etag)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers()[header::ETAG].to_str().unwrap(), etag);
+}
+
+#[tokio::test]
+async fn subscribe() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let leaderboard = setup(&app, &client).await;
+    post_json(
+        &client,
+        format!("{}/api/v1/scores/{}", app.address, leaderboard.id),
+        SCORE,
+    )
+    .await;
+
+    let mut response = client
+        .get(format!(
+            "{}/api/v1/scores/{}/live?top=5&player={}",
+            app.address, leaderboard.id, PLAYER_ID
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "text/event-stream"
+    );
+
+    let mut received = String::new();
+    while !received.contains("event: rank") || !received.ends_with("\n\n") {
+        let chunk = response
+            .chunk()
+            .await
+            .expect("Failed to read event.")
+            .expect("stream ended");
+        received.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+    let events: Vec<(&str, Value)> = received
+        .split("\n\n")
+        .filter_map(|event| {
+            let name = event
+                .lines()
+                .find_map(|line| line.strip_prefix("event: "))?;
+            let data = event.lines().find_map(|line| line.strip_prefix("data: "))?;
+            Some((name, serde_json::from_str(data).unwrap()))
+        })
+        .collect();
+    assert_eq!(events[0].0, "top");
+    assert_eq!(events[0].1[0]["score"], json!(42.5));
+    assert_eq!(events[0].1[0]["player"], "bevy");
+    assert_eq!(events[1], ("rank", json!({ "rank": 1, "score": 42.5 })));
+}
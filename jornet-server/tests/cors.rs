@@ -0,0 +1,136 @@
+use jornet_server::configuration::{get_configuration, LeaderboardOrigins};
+use reqwest::{header, Method, StatusCode};
+use uuid::Uuid;
+
+mod helper;
+
+const ITCH: &str = "https://html-classic.itch.zone";
+const GAME: &str = "https://game.example.com";
+
+async fn preflight(client: &reqwest::Client, url: &str, origin: &str) -> reqwest::Response {
+    client
+        .request(Method::OPTIONS, url)
+        .header(header::ORIGIN, origin)
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+fn allowed_origin(response: &reqwest::Response) -> Option<&str> {
+    response
+        .headers()
+        .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+        .map(|value| value.to_str().unwrap())
+}
+
+#[tokio::test]
+async fn any_origin_is_allowed_by_default() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    for path in [
+        format!("api/v1/scores/{}", Uuid::new_v4()),
+        "api/v1/players".to_string(),
+        "api/v1/leaderboards".to_string(),
+    ] {
+        let response = preflight(&client, &format!("{}/{}", app.address, path), GAME).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(allowed_origin(&response), Some("*"));
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_MAX_AGE],
+            "3600",
+            "{}",
+            path
+        );
+    }
+
+    let response = client
+        .get(format!("{}/api/v1/scores/{}", app.address, Uuid::new_v4()))
+        .header(header::ORIGIN, GAME)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(allowed_origin(&response), Some("*"));
+    let exposed = response.headers()[header::ACCESS_CONTROL_EXPOSE_HEADERS]
+        .to_str()
+        .unwrap()
+        .to_lowercase();
+    assert!(exposed.contains("etag"));
+    assert!(exposed.contains("retry-after"));
+}
+
+#[tokio::test]
+async fn only_configured_origins_are_allowed() {
+    let leaderboard = Uuid::new_v4();
+    let mut configuration = get_configuration();
+    configuration.cors.allowed_origins = vec!["https://*.itch.zone".to_string()];
+    configuration.cors.leaderboard_origins = vec![LeaderboardOrigins {
+        leaderboard,
+        origins: vec![GAME.to_string()],
+    }];
+    configuration.cors.allow_credentials = true;
+    configuration.cors.max_age_seconds = Some(600);
+    let app = helper::spawn_app_with_configuration(configuration).await;
+    let client = reqwest::Client::new();
+
+    let scores = format!("{}/api/v1/scores/{}", app.address, leaderboard);
+    let other_scores = format!("{}/api/v1/scores/{}", app.address, Uuid::new_v4());
+    let players = format!("{}/api/v1/players", app.address);
+    let admin = format!("{}/api/v1/admin/whoami", app.address);
+
+    // global origins, on every endpoint
+    for url in [&scores, &other_scores, &players, &admin] {
+        let response = preflight(&client, url, ITCH).await;
+        assert_eq!(response.status(), StatusCode::OK, "{}", url);
+        assert_eq!(allowed_origin(&response), Some(ITCH));
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_CREDENTIALS],
+            "true"
+        );
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_MAX_AGE], "600");
+    }
+
+    // origins of a leaderboard, only on its endpoints
+    let response = preflight(&client, &scores, GAME).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(allowed_origin(&response), Some(GAME));
+    let response = client
+        .get(&scores)
+        .header(header::ORIGIN, GAME)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(allowed_origin(&response), Some(GAME));
+
+    for url in [&other_scores, &players, &admin] {
+        let response = preflight(&client, url, GAME).await;
+        assert_eq!(allowed_origin(&response), None, "{}", url);
+    }
+
+    // other origins
+    for origin in [
+        "https://evil.example.com",
+        "https://itch.zone",
+        "http://html-classic.itch.zone",
+        "https://html-classic.itch.zone.evil.com",
+    ] {
+        let response = preflight(&client, &scores, origin).await;
+        assert_eq!(allowed_origin(&response), None, "{}", origin);
+        let response = client
+            .get(&scores)
+            .header(header::ORIGIN, origin)
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert_eq!(allowed_origin(&response), None, "{}", origin);
+    }
+
+    // requests without an origin are not cross-origin
+    let response = client
+        .get(&scores)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -1,29 +1,202 @@
-use std::net::TcpListener;
+#![allow(dead_code)]
 
-use jornet_server::configuration::{get_configuration, DatabaseSettings};
-use sqlx::{Connection, Executor, PgConnection, PgPool};
+use std::{net::TcpListener, sync::Arc};
+
+use jornet_server::{
+    configuration::{get_configuration, DatabaseSettings, Settings, UuidLogin},
+    domains::{
+        admin::TokenReply,
+        leaderboard::{Leaderboard, LeaderboardInput},
+        player::{Player, PlayerInput},
+        score::ScoreType,
+    },
+    storage::{MemoryStorage, PostgresStorage, SqliteStorage, Storage},
+};
+use serde::Serialize;
+use sqlx::{Connection, Executor, PgConnection, PgPool, SqlitePool};
 use uuid::Uuid;
 
 pub struct TestApp {
     pub address: String,
-    pub db_pool: PgPool,
+    pub storage: Arc<dyn Storage>,
+    pub database: TestDatabase,
+}
+
+/// Direct access to the database behind the storage, for tests that need to bypass it.
+pub enum TestDatabase {
+    Postgres(PgPool),
+    Sqlite(SqlitePool),
+    Memory,
+}
+
+impl TestApp {
+    /// Execute a raw SQL statement against the database used by the app.
+    pub async fn execute(&self, query: &str) -> Result<(), sqlx::Error> {
+        match &self.database {
+            TestDatabase::Postgres(pool) => pool.execute(query).await.map(|_| ()),
+            TestDatabase::Sqlite(pool) => pool.execute(query).await.map(|_| ()),
+            TestDatabase::Memory => Err(sqlx::Error::Protocol(
+                "no SQL database behind the in-memory storage".to_string(),
+            )),
+        }
+    }
+}
+
+/// The configuration from `configuration.dhall`, with the dev login by UUID the tests use to get
+/// admin tokens, and webhooks allowed to the local receivers of the tests.
+pub fn configuration() -> Settings {
+    let mut configuration = get_configuration();
+    configuration.uuid_login = UuidLogin::Dev;
+    configuration.webhooks.allow_private_addresses = true;
+    configuration
 }
 
 pub async fn spawn_app() -> TestApp {
+    spawn_app_with_configuration(configuration()).await
+}
+
+#[derive(Serialize)]
+pub struct UuidInput {
+    pub uuid: Uuid,
+}
+
+/// Log in by UUID, which creates the admin in dev mode. Returns the token of the admin.
+pub async fn login(app: &TestApp, client: &reqwest::Client, uuid: Uuid) -> String {
+    client
+        .post(format!("{}/oauth/by_uuid", app.address))
+        .json(&UuidInput { uuid })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<TokenReply>()
+        .await
+        .expect("got body")
+        .token
+}
+
+/// The token of a new admin.
+pub async fn new_admin(app: &TestApp, client: &reqwest::Client) -> String {
+    login(app, client, Uuid::new_v4()).await
+}
+
+pub async fn create_leaderboard(
+    app: &TestApp,
+    client: &reqwest::Client,
+    token: &str,
+) -> Leaderboard {
+    create_typed_leaderboard(app, client, token, None).await
+}
+
+pub async fn create_typed_leaderboard(
+    app: &TestApp,
+    client: &reqwest::Client,
+    token: &str,
+    score_type: Option<ScoreType>,
+) -> Leaderboard {
+    client
+        .post(format!("{}/api/v1/leaderboards", app.address))
+        .bearer_auth(token)
+        .json(&LeaderboardInput {
+            name: "my leaderboard".to_string(),
+            score_type,
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Leaderboard>()
+        .await
+        .expect("valid leaderboard")
+}
+
+pub async fn create_player(app: &TestApp, client: &reqwest::Client, name: Option<&str>) -> Player {
+    client
+        .post(format!("{}/api/v1/players", app.address))
+        .json(&PlayerInput {
+            name: name.map(str::to_string),
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Player>()
+        .await
+        .unwrap()
+}
+
+/// A new admin with their token, a leaderboard they own, and a player.
+pub async fn setup(app: &TestApp, client: &reqwest::Client) -> (String, Leaderboard, Player) {
+    let token = new_admin(app, client).await;
+    let leaderboard = create_leaderboard(app, client, &token).await;
+    let player = create_player(app, client, None).await;
+    (token, leaderboard, player)
+}
+
+/// Tests run against PostgreSQL by default, set `JORNET_TEST_STORAGE` to `sqlite` or `memory`
+/// to run them against another storage.
+pub async fn spawn_app_with_configuration(mut configuration: Settings) -> TestApp {
     let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
     let port = listener.local_addr().unwrap().port();
 
-    let mut configuration = get_configuration();
-    configuration.database.database_name = Uuid::new_v4().to_string();
-    let connection_pool = configure_database(&configuration.database).await;
+    let (storage, database) = spawn_storage(&mut configuration).await;
 
-    let server =
-        jornet_server::run(listener, connection_pool.clone()).expect("Failed to bind address");
-    let _ = tokio::spawn(server);
+    let server = jornet_server::run_with_storage(listener, storage.clone(), configuration)
+        .expect("Failed to bind address");
+    tokio::spawn(server);
 
     TestApp {
         address: format!("http://127.0.0.1:{}", port),
-        db_pool: connection_pool,
+        storage,
+        database,
+    }
+}
+
+/// A new storage, selected by `JORNET_TEST_STORAGE`. For PostgreSQL, the database of the
+/// configuration is set to the one created.
+pub async fn spawn_storage(configuration: &mut Settings) -> (Arc<dyn Storage>, TestDatabase) {
+    let database_name = Uuid::new_v4().to_string();
+    match std::env::var("JORNET_TEST_STORAGE").as_deref() {
+        Ok("sqlite") => {
+            let filename = std::env::temp_dir().join(format!("jornet-{}.db", database_name));
+            let storage = SqliteStorage::connect(filename.to_str().unwrap())
+                .await
+                .expect("Failed to open SQLite database.");
+            storage
+                .migrate()
+                .await
+                .expect("Failed to migrate the database");
+            let pool = storage.pool().clone();
+            (Arc::new(storage), TestDatabase::Sqlite(pool))
+        }
+        Ok("memory") => (Arc::new(MemoryStorage::new()), TestDatabase::Memory),
+        _ => {
+            configuration.database.database_name = database_name;
+            let pool = configure_database(&configuration.database).await;
+            (
+                Arc::new(PostgresStorage::new(pool.clone())),
+                TestDatabase::Postgres(pool),
+            )
+        }
+    }
+}
+
+/// The storage of a stopped server, with a new connection pool. Connections opened by the workers
+/// of a server close when it stops.
+pub async fn reopen_storage(
+    config: &DatabaseSettings,
+    storage: Arc<dyn Storage>,
+    database: &TestDatabase,
+) -> Arc<dyn Storage> {
+    match database {
+        TestDatabase::Postgres(_) => Arc::new(PostgresStorage::new(
+            PgPool::connect(&config.connection_string())
+                .await
+                .expect("Failed to connect to Postgres."),
+        )),
+        TestDatabase::Sqlite(pool) => Arc::new(
+            SqliteStorage::connect(pool.connect_options().get_filename().to_str().unwrap())
+                .await
+                .expect("Failed to open SQLite database."),
+        ),
+        TestDatabase::Memory => storage,
     }
 }
 
@@ -0,0 +1,217 @@
+use jornet_server::{
+    cache::{Page, ScoreCache},
+    domains::{
+        live::PlayerRank,
+        player::{Player, PlayerInput},
+        score::{ScoreInput, ScoreValue},
+    },
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+mod helper;
+
+/// Reads the events of a Server-Sent Events response.
+struct Events {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl Events {
+    /// Name and data of the next event, skipping comments.
+    async fn next(&mut self) -> (String, String) {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let event = self.buffer[..end].to_string();
+                self.buffer.drain(..end + 2);
+                if event.starts_with(':') {
+                    continue;
+                }
+                let mut name = String::new();
+                let mut data = String::new();
+                for line in event.lines() {
+                    if let Some(value) = line.strip_prefix("event: ") {
+                        name = value.to_string();
+                    } else if let Some(value) = line.strip_prefix("data: ") {
+                        data = value.to_string();
+                    }
+                }
+                return (name, data);
+            }
+            let chunk = self
+                .response
+                .chunk()
+                .await
+                .expect("Failed to read event.")
+                .expect("stream ended");
+            self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+}
+
+#[tokio::test]
+async fn live_top_and_rank() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let token = helper::new_admin(&app, &client).await;
+
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
+
+    let mut players = vec![];
+    for name in ["player one", "player two"] {
+        players.push(
+            client
+                .post(format!("{}/api/v1/players", app.address))
+                .json(&PlayerInput {
+                    name: Some(name.to_string()),
+                })
+                .send()
+                .await
+                .expect("Failed to execute request.")
+                .json::<Player>()
+                .await
+                .unwrap(),
+        );
+    }
+
+    let response = client
+        .get(format!(
+            "{}/api/v1/scores/{}/live?top=1&player={}",
+            app.address, leaderboard.id, players[1].id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+    assert_eq!(
+        response.headers()[reqwest::header::CONTENT_TYPE],
+        "text/event-stream"
+    );
+    let mut events = Events {
+        response,
+        buffer: String::new(),
+    };
+
+    assert_eq!(events.next().await, ("top".to_string(), "[]".to_string()));
+    assert_eq!(
+        events.next().await,
+        ("rank".to_string(), "null".to_string())
+    );
+
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(
+            10.0,
+            players[1].clone(),
+            None,
+            leaderboard.key,
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let (name, data) = events.next().await;
+    assert_eq!(name, "top");
+    let top: Vec<Value> = serde_json::from_str(&data).unwrap();
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0]["player"], "player two");
+    let (name, data) = events.next().await;
+    assert_eq!(name, "rank");
+    assert_eq!(
+        serde_json::from_str::<PlayerRank>(&data).unwrap(),
+        PlayerRank {
+            rank: 1,
+            score: ScoreValue::F32(10.0)
+        }
+    );
+
+    // a better score from the other player changes both the top and the rank
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(
+            20.0,
+            players[0].clone(),
+            None,
+            leaderboard.key,
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let (name, data) = events.next().await;
+    assert_eq!(name, "top");
+    let top: Vec<Value> = serde_json::from_str(&data).unwrap();
+    assert_eq!(top[0]["player"], "player one");
+    let (name, data) = events.next().await;
+    assert_eq!(name, "rank");
+    assert_eq!(
+        serde_json::from_str::<PlayerRank>(&data).unwrap(),
+        PlayerRank {
+            rank: 2,
+            score: ScoreValue::F32(10.0)
+        }
+    );
+}
+
+#[tokio::test]
+async fn live_unknown_leaderboard() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!(
+            "{}/api/v1/scores/{}/live",
+            app.address,
+            Uuid::new_v4()
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn ranking_is_shared_until_scores_change() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (_, leaderboard, first) = helper::setup(&app, &client).await;
+    let second = helper::create_player(&app, &client, None).await;
+    let third = helper::create_player(&app, &client, None).await;
+    for (player, value) in [
+        (&first, 10.0),
+        (&second, 30.0),
+        (&third, 30.0),
+        (&first, 5.0),
+    ] {
+        let mut score = ScoreInput::new(value, player.clone(), None, leaderboard.key);
+        score.timestamp += value as u64;
+        assert!(app.storage.insert_score(leaderboard.id, &score).await);
+    }
+
+    let cache = ScoreCache::default();
+    let cached = cache
+        .get(app.storage.as_ref(), leaderboard.id, Page::All)
+        .await;
+    let ranking = cached.ranking();
+    assert_eq!(
+        ranking.players[&first.id],
+        PlayerRank {
+            rank: 3,
+            score: ScoreValue::F32(10.0)
+        }
+    );
+    assert_eq!(ranking.players[&second.id].rank, 1);
+    assert_eq!(ranking.players[&third.id].rank, 1);
+
+    let again = cache
+        .get(app.storage.as_ref(), leaderboard.id, Page::All)
+        .await;
+    assert!(std::ptr::eq(again.ranking(), ranking));
+
+    cache.invalidate(leaderboard.id);
+    let changed = cache
+        .get(app.storage.as_ref(), leaderboard.id, Page::All)
+        .await;
+    assert!(!std::ptr::eq(changed.ranking(), ranking));
+}
@@ -0,0 +1,83 @@
+use std::net::TcpListener;
+
+use jornet_server::{
+    configuration::get_configuration,
+    domains::{
+        leaderboard::Leaderboard,
+        player::{Player, PlayerInput},
+        score::ScoreInput,
+    },
+    storage::{LeaderboardStore, MemoryStorage, ScoreStore},
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+struct Score {
+    score: f32,
+    player: String,
+}
+
+#[tokio::test]
+async fn run_in_memory_with_seeded_leaderboard() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let address = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+
+    let storage = MemoryStorage::new();
+    let leaderboard = Leaderboard {
+        id: Uuid::new_v4(),
+        key: Uuid::new_v4(),
+        name: "seeded".to_string(),
+        score_type: Default::default(),
+    };
+    assert!(
+        storage
+            .create_leaderboard(&leaderboard, Uuid::new_v4())
+            .await
+    );
+
+    let server = jornet_server::run_in_memory(listener, storage.clone(), get_configuration())
+        .expect("Failed to bind address");
+    tokio::spawn(server);
+
+    let client = reqwest::Client::new();
+    let player = client
+        .post(format!("{}/api/v1/players", address))
+        .json(&PlayerInput {
+            name: Some("player one".to_string()),
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Player>()
+        .await
+        .unwrap();
+
+    let response = client
+        .post(format!("{}/api/v1/scores/{}", address, leaderboard.id))
+        .json(&ScoreInput::new(
+            543.21,
+            player.clone(),
+            None,
+            leaderboard.key,
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let scores = client
+        .get(format!("{}/api/v1/scores/{}", address, leaderboard.id))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<Score>>()
+        .await
+        .unwrap();
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].score, 543.21);
+    assert_eq!(scores[0].player, "player one");
+
+    // the clone kept by the test sees what the server saved
+    assert_eq!(storage.get_scores(leaderboard.id).await.len(), 1);
+}
@@ -0,0 +1,164 @@
+use jornet_server::domains::score::ScoreInput;
+use reqwest::StatusCode;
+use uuid::Uuid;
+
+mod helper;
+
+/// Value of a metric with its labels, as written in the text format.
+fn metric(metrics: &str, name: &str) -> Option<f64> {
+    metrics
+        .lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' ')?.parse().ok())
+}
+
+#[tokio::test]
+async fn metrics_are_exposed() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let player = helper::create_player(&app, &client, None).await;
+
+    let token = helper::new_admin(&app, &client).await;
+
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
+
+    let score = ScoreInput::new(10.0, player.clone(), None, leaderboard.key);
+    for _ in 0..2 {
+        client
+            .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+            .json(&score)
+            .send()
+            .await
+            .expect("Failed to execute request.");
+    }
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(20.0, player, None, Uuid::new_v4()))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    for _ in 0..2 {
+        client
+            .get(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+    }
+
+    client
+        .request(
+            reqwest::Method::from_bytes(b"FROBNICATE").unwrap(),
+            format!("{}/api/v1/nothing", app.address),
+        )
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let response = client
+        .get(format!("{}/metrics", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()[reqwest::header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+    let metrics = response.text().await.unwrap();
+
+    assert_eq!(metric(&metrics, "jornet_player_creations_total"), Some(1.0));
+    assert_eq!(
+        metric(
+            &metrics,
+            r#"jornet_score_submissions_total{outcome="accepted"}"#
+        ),
+        Some(1.0)
+    );
+    assert_eq!(
+        metric(
+            &metrics,
+            r#"jornet_score_submissions_total{outcome="duplicate"}"#
+        ),
+        Some(1.0)
+    );
+    assert_eq!(
+        metric(
+            &metrics,
+            r#"jornet_score_submissions_total{outcome="bad_mac"}"#
+        ),
+        Some(1.0)
+    );
+    assert_eq!(
+        metric(
+            &metrics,
+            r#"jornet_http_requests_total{method="GET",route="/api/v1/scores/{leaderboard_id}",status="200"}"#
+        ),
+        Some(2.0)
+    );
+    assert_eq!(
+        metric(
+            &metrics,
+            r#"jornet_http_requests_total{method="POST",route="/api/v1/scores/{leaderboard_id}",status="500"}"#
+        ),
+        Some(2.0)
+    );
+    assert!(metrics.contains("jornet_http_request_duration_seconds_bucket"));
+    assert!(!metrics.contains("FROBNICATE"));
+    assert!(metrics
+        .lines()
+        .any(|line| line.starts_with(r#"jornet_http_requests_total{method="other""#)));
+    assert_eq!(
+        metric(
+            &metrics,
+            r#"jornet_score_cache_lookups_total{result="miss"}"#
+        ),
+        Some(1.0)
+    );
+    assert_eq!(
+        metric(
+            &metrics,
+            r#"jornet_score_cache_lookups_total{result="hit"}"#
+        ),
+        Some(1.0)
+    );
+    if !matches!(app.database, helper::TestDatabase::Memory) {
+        assert!(metric(&metrics, "jornet_db_pool_connections").unwrap() >= 1.0);
+    }
+}
+
+#[tokio::test]
+async fn metrics_can_require_a_token() {
+    let mut configuration = helper::configuration();
+    configuration.metrics.token = Some("scraper token".to_string());
+    let app = helper::spawn_app_with_configuration(configuration).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/metrics", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .get(format!("{}/metrics", app.address))
+        .bearer_auth("scraper toke")
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .get(format!("{}/metrics", app.address))
+        .bearer_auth("scraper token")
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response
+        .text()
+        .await
+        .unwrap()
+        .contains("jornet_http_requests_total"));
+}
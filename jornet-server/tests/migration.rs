@@ -0,0 +1,99 @@
+use jornet_server::{
+    configuration::{get_configuration, Settings, SqliteSettings, StorageBackend},
+    storage::{
+        self,
+        migrations::{self, MigrationState},
+    },
+};
+use uuid::Uuid;
+
+fn sqlite_configuration(auto_migrate: bool) -> Settings {
+    let mut configuration = get_configuration();
+    let filename = std::env::temp_dir().join(format!("jornet-{}.db", Uuid::new_v4()));
+    configuration.storage = StorageBackend::Sqlite(SqliteSettings {
+        filename: filename.to_str().unwrap().to_string(),
+    });
+    configuration.auto_migrate = auto_migrate;
+    configuration
+}
+
+#[tokio::test]
+async fn apply_pending_migrations() {
+    let configuration = sqlite_configuration(false);
+    storage::connect(&configuration).await.unwrap();
+
+    let status = migrations::status(&configuration).await.unwrap();
+    assert!(!status.is_empty());
+    assert!(status
+        .iter()
+        .all(|migration| migration.state == MigrationState::Pending));
+
+    // a dry run lists the migrations without applying them
+    let applied = migrations::apply(&configuration, true).await.unwrap();
+    assert_eq!(applied.len(), status.len());
+    assert!(migrations::status(&configuration)
+        .await
+        .unwrap()
+        .iter()
+        .all(|migration| migration.state == MigrationState::Pending));
+
+    let applied = migrations::apply(&configuration, false).await.unwrap();
+    assert_eq!(applied.len(), status.len());
+    assert!(migrations::status(&configuration)
+        .await
+        .unwrap()
+        .iter()
+        .all(|migration| migration.state == MigrationState::Applied));
+
+    let applied = migrations::apply(&configuration, false).await.unwrap();
+    assert!(applied.is_empty());
+}
+
+#[tokio::test]
+async fn auto_migrate_on_connect() {
+    let configuration = sqlite_configuration(true);
+    storage::connect(&configuration).await.unwrap();
+
+    assert!(migrations::status(&configuration)
+        .await
+        .unwrap()
+        .iter()
+        .all(|migration| migration.state == MigrationState::Applied));
+}
+
+#[tokio::test]
+async fn status_is_read_only() {
+    let configuration = sqlite_configuration(false);
+    let StorageBackend::Sqlite(settings) = &configuration.storage else {
+        unreachable!()
+    };
+    let filename = std::path::PathBuf::from(&settings.filename);
+
+    // the database isn't created
+    assert!(migrations::status(&configuration)
+        .await
+        .unwrap()
+        .iter()
+        .all(|migration| migration.state == MigrationState::Pending));
+    assert!(!migrations::apply(&configuration, true)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(!filename.exists());
+
+    // nor the table of the migrations
+    let storage = storage::connect(&configuration).await.unwrap();
+    migrations::status(&configuration).await.unwrap();
+    migrations::apply(&configuration, true).await.unwrap();
+    storage.schema_version().await.unwrap();
+    let tables = storage::SqliteStorage::connect(&settings.filename)
+        .await
+        .unwrap();
+    let (count,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+    )
+    .fetch_one(tables.pool())
+    .await
+    .unwrap();
+    assert_eq!(count, 0);
+}
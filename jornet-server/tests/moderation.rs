@@ -0,0 +1,158 @@
+use jornet_server::domains::{
+    leaderboard::{BanInput, Leaderboard},
+    score::{ModeratedScore, ScoreInput},
+};
+
+mod helper;
+
+#[tokio::test]
+async fn delete_score() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, player) = helper::setup(&app, &client).await;
+
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(543.21, player, None, leaderboard.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let scores = client
+        .get(format!(
+            "{}/api/v1/leaderboards/{}/scores",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<ModeratedScore>>()
+        .await
+        .expect("valid scores");
+    assert_eq!(scores.len(), 1);
+
+    let response = client
+        .delete(format!(
+            "{}/api/v1/leaderboards/{}/scores/{}",
+            app.address, leaderboard.id, scores[0].id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    assert!(app
+        .storage
+        .get_scores_for_moderation(leaderboard.id)
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn banned_player_cannot_send_scores() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, player) = helper::setup(&app, &client).await;
+
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(
+            543.21,
+            player.clone(),
+            None,
+            leaderboard.key,
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/leaderboards/{}/bans",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .json(&BanInput { player: player.id })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    assert!(app
+        .storage
+        .get_scores_for_moderation(leaderboard.id)
+        .await
+        .is_empty());
+
+    let response = client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(123.45, player, None, leaderboard.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_server_error());
+}
+
+#[tokio::test]
+async fn rotated_key_replaces_old_one() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, player) = helper::setup(&app, &client).await;
+
+    let rotated = client
+        .post(format!(
+            "{}/api/v1/leaderboards/{}/key",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Leaderboard>()
+        .await
+        .expect("valid leaderboard");
+    assert_ne!(rotated.key, leaderboard.key);
+
+    let response = client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(
+            543.21,
+            player.clone(),
+            None,
+            leaderboard.key,
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_server_error());
+
+    let response = client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(543.21, player, None, rotated.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn delete_leaderboard() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, _) = helper::setup(&app, &client).await;
+
+    let response = client
+        .delete(format!(
+            "{}/api/v1/leaderboards/{}",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    assert!(app.storage.get_leaderboard(leaderboard.id).await.is_none());
+}
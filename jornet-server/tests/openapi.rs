@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use jornet_server::openapi::ApiDoc;
+use utoipa::OpenApi;
+
+mod helper;
+
+fn generated() -> String {
+    ApiDoc::openapi().to_pretty_json().unwrap() + "\n"
+}
+
+#[test]
+fn document_is_up_to_date() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("openapi.json");
+    let generated = generated();
+
+    if std::env::var("UPDATE_OPENAPI").is_ok() {
+        std::fs::write(&path, &generated).unwrap();
+        return;
+    }
+    let committed = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "openapi.json is outdated, run `UPDATE_OPENAPI=1 cargo test --test openapi` to update it"
+    );
+}
+
+#[tokio::test]
+async fn document_is_served() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/api/v1/openapi.json", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert!(response.status().is_success());
+    let served: serde_json::Value = response.json().await.unwrap();
+    let generated: serde_json::Value = serde_json::from_str(&generated()).unwrap();
+    assert_eq!(served, generated);
+    assert_eq!(
+        served["paths"]["/api/v1/scores/{leaderboard_id}"]["post"]["requestBody"]["content"]
+            ["application/json"]["schema"]["$ref"],
+        "#/components/schemas/ScoreInput"
+    );
+    assert!(served["components"]["schemas"]["ScoreInput"]["description"]
+        .as_str()
+        .unwrap()
+        .contains("HMAC-SHA256"));
+}
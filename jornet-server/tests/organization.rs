@@ -0,0 +1,285 @@
+use jornet_server::domains::{
+    leaderboard::{Leaderboard, LeaderboardInput},
+    organization::{
+        Invitation, InvitationInput, Organization, OrganizationInput, OrganizationWithRole, Role,
+        RoleInput,
+    },
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+mod helper;
+
+#[derive(Deserialize)]
+struct LeaderboardWithRole {
+    id: Uuid,
+    organization: Uuid,
+    role: Role,
+}
+
+async fn create_organization(
+    app: &helper::TestApp,
+    client: &reqwest::Client,
+    token: &str,
+) -> Organization {
+    client
+        .post(format!("{}/api/v1/organizations", app.address))
+        .bearer_auth(token)
+        .json(&OrganizationInput {
+            name: "my team".to_string(),
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Organization>()
+        .await
+        .expect("valid organization")
+}
+
+#[tokio::test]
+async fn create_organization_as_owner() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let token = helper::login(&app, &client, Uuid::new_v4()).await;
+    let organization = create_organization(&app, &client, &token).await;
+
+    let organizations = client
+        .get(format!("{}/api/v1/organizations", app.address))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<OrganizationWithRole>>()
+        .await
+        .expect("valid organizations");
+
+    assert_eq!(organizations.len(), 1);
+    assert_eq!(organizations[0].id, organization.id);
+    assert_eq!(organizations[0].role, Role::Owner);
+}
+
+#[tokio::test]
+async fn leaderboard_defaults_to_personal_organization() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let admin = Uuid::new_v4();
+    let token = helper::login(&app, &client, admin).await;
+
+    client
+        .post(format!("{}/api/v1/leaderboards", app.address))
+        .bearer_auth(&token)
+        .json(&LeaderboardInput {
+            name: "my leaderboard".to_string(),
+            score_type: None,
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let leaderboards = client
+        .get(format!("{}/api/v1/leaderboards", app.address))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<LeaderboardWithRole>>()
+        .await
+        .expect("valid leaderboards");
+
+    assert_eq!(leaderboards.len(), 1);
+    assert_eq!(leaderboards[0].organization, admin);
+    assert_eq!(leaderboards[0].role, Role::Owner);
+}
+
+#[tokio::test]
+async fn not_a_member_cannot_create_leaderboard() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let owner_token = helper::login(&app, &client, Uuid::new_v4()).await;
+    let organization = create_organization(&app, &client, &owner_token).await;
+
+    let other_token = helper::login(&app, &client, Uuid::new_v4()).await;
+    let response = client
+        .post(format!(
+            "{}/api/v1/leaderboards?organization={}",
+            app.address, organization.id
+        ))
+        .bearer_auth(&other_token)
+        .json(&LeaderboardInput {
+            name: "my leaderboard".to_string(),
+            score_type: None,
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status());
+}
+
+#[tokio::test]
+async fn permissions_follow_role() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let owner_token = helper::login(&app, &client, Uuid::new_v4()).await;
+    let organization = create_organization(&app, &client, &owner_token).await;
+    let leaderboard = client
+        .post(format!(
+            "{}/api/v1/leaderboards?organization={}",
+            app.address, organization.id
+        ))
+        .bearer_auth(&owner_token)
+        .json(&LeaderboardInput {
+            name: "my leaderboard".to_string(),
+            score_type: None,
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Leaderboard>()
+        .await
+        .expect("valid leaderboard");
+
+    let invitation = client
+        .post(format!(
+            "{}/api/v1/organizations/{}/invitations",
+            app.address, organization.id
+        ))
+        .bearer_auth(&owner_token)
+        .json(&InvitationInput {
+            role: Role::Viewer,
+            github_login: None,
+            email: Some("member@example.com".to_string()),
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Invitation>()
+        .await
+        .expect("valid invitation");
+
+    let member = Uuid::new_v4();
+    let member_token = helper::login(&app, &client, member).await;
+    let response = client
+        .post(format!(
+            "{}/api/v1/invitations/{}/accept",
+            app.address, invitation.id
+        ))
+        .bearer_auth(&member_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let leaderboards = client
+        .get(format!("{}/api/v1/leaderboards", app.address))
+        .bearer_auth(&member_token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<LeaderboardWithRole>>()
+        .await
+        .expect("valid leaderboards");
+    assert_eq!(leaderboards.len(), 1);
+    assert_eq!(leaderboards[0].id, leaderboard.id);
+    assert_eq!(leaderboards[0].role, Role::Viewer);
+
+    let response = client
+        .delete(format!(
+            "{}/api/v1/leaderboards/{}/scores",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&member_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(401, response.status());
+
+    let response = client
+        .put(format!(
+            "{}/api/v1/organizations/{}/members/{}",
+            app.address, organization.id, member
+        ))
+        .bearer_auth(&owner_token)
+        .json(&RoleInput { role: Role::Admin })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let response = client
+        .delete(format!(
+            "{}/api/v1/leaderboards/{}/scores",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&member_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn github_invitation_needs_matching_login() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let owner_token = helper::login(&app, &client, Uuid::new_v4()).await;
+    let organization = create_organization(&app, &client, &owner_token).await;
+
+    let invitation = client
+        .post(format!(
+            "{}/api/v1/organizations/{}/invitations",
+            app.address, organization.id
+        ))
+        .bearer_auth(&owner_token)
+        .json(&InvitationInput {
+            role: Role::Moderator,
+            github_login: Some("someone".to_string()),
+            email: None,
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Invitation>()
+        .await
+        .expect("valid invitation");
+
+    let other_token = helper::login(&app, &client, Uuid::new_v4()).await;
+    let response = client
+        .post(format!(
+            "{}/api/v1/invitations/{}/accept",
+            app.address, invitation.id
+        ))
+        .bearer_auth(&other_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status());
+}
+
+#[tokio::test]
+async fn last_owner_cannot_leave() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let owner = Uuid::new_v4();
+    let owner_token = helper::login(&app, &client, owner).await;
+    let organization = create_organization(&app, &client, &owner_token).await;
+
+    let response = client
+        .delete(format!(
+            "{}/api/v1/organizations/{}/members/{}",
+            app.address, organization.id, owner
+        ))
+        .bearer_auth(&owner_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(409, response.status());
+}
@@ -0,0 +1,68 @@
+use std::{
+    net::TcpListener,
+    sync::{Arc, Mutex},
+};
+
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use jornet_server::{
+    configuration::{get_configuration, LogFormat, LogSettings},
+    telemetry,
+};
+
+mod helper;
+
+/// Content types of the requests received by the collector.
+type Received = Arc<Mutex<Vec<String>>>;
+
+async fn collect(received: web::Data<Mutex<Vec<String>>>, request: HttpRequest) -> HttpResponse {
+    received.lock().unwrap().push(
+        request
+            .headers()
+            .get("content-type")
+            .map(|value| value.to_str().unwrap().to_string())
+            .unwrap_or_default(),
+    );
+    HttpResponse::Ok().finish()
+}
+
+// only test in this file, as it installs the global subscriber
+#[tokio::test(flavor = "multi_thread")]
+async fn spans_are_exported_to_the_collector() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let received: Received = Default::default();
+    let data = web::Data::from(received.clone());
+    let collector = HttpServer::new(move || {
+        App::new()
+            .app_data(data.clone())
+            .route("/v1/traces", web::post().to(collect))
+    })
+    .workers(1)
+    .listen(listener)
+    .unwrap()
+    .run();
+    tokio::spawn(collector);
+
+    let telemetry = telemetry::init(&LogSettings {
+        filter: "info".to_string(),
+        format: LogFormat::Json,
+        otlp_endpoint: Some(format!("http://127.0.0.1:{}/v1/traces", port)),
+    });
+
+    let app = helper::spawn_app_with_configuration(get_configuration()).await;
+    let response = reqwest::Client::new()
+        .get(format!("{}/health_check", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    // flushes the spans
+    tokio::task::spawn_blocking(move || drop(telemetry))
+        .await
+        .unwrap();
+
+    let received = received.lock().unwrap();
+    assert!(!received.is_empty());
+    assert_eq!(received[0], "application/x-protobuf");
+}
@@ -1,21 +1,14 @@
 use jornet_server::domains::player::{Player, PlayerInput};
-use serde::Serialize;
-use uuid::Uuid;
 
 mod helper;
 
-#[derive(Serialize)]
-struct UuidInput {
-    uuid: Uuid,
-}
-
 #[tokio::test]
 async fn create_player() {
     let app = helper::spawn_app().await;
     let client = reqwest::Client::new();
 
     let response = client
-        .post(&format!("{}/api/v1/players", app.address))
+        .post(format!("{}/api/v1/players", app.address))
         .json(&PlayerInput {
             name: Some("hello".to_string()),
         })
@@ -33,7 +26,7 @@ async fn create_player_with_random_name() {
     let client = reqwest::Client::new();
 
     let response = client
-        .post(&format!("{}/api/v1/players", app.address))
+        .post(format!("{}/api/v1/players", app.address))
         .json(&PlayerInput { name: None })
         .send()
         .await
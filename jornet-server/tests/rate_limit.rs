@@ -0,0 +1,219 @@
+use jornet_server::{
+    configuration::RateLimit,
+    domains::{
+        player::{Player, PlayerInput},
+        score::ScoreInput,
+    },
+};
+use reqwest::{header, StatusCode};
+use uuid::Uuid;
+
+mod helper;
+
+async fn spawn_app(trusted_proxies: &[&str]) -> helper::TestApp {
+    let mut configuration = helper::configuration();
+    configuration.rate_limits.player_creation_per_ip = Some(RateLimit {
+        burst: 2,
+        per_minute: 1,
+    });
+    configuration.rate_limits.score_submission_per_player = Some(RateLimit {
+        burst: 1,
+        per_minute: 2,
+    });
+    configuration.rate_limits.reads_per_ip = Some(RateLimit {
+        burst: 2,
+        per_minute: 6,
+    });
+    configuration.rate_limits.trusted_proxies = trusted_proxies
+        .iter()
+        .map(|proxy| proxy.to_string())
+        .collect();
+    helper::spawn_app_with_configuration(configuration).await
+}
+
+async fn create_player(
+    app: &helper::TestApp,
+    client: &reqwest::Client,
+    forwarded_for: Option<&str>,
+) -> reqwest::Response {
+    let mut request = client
+        .post(format!("{}/api/v1/players", app.address))
+        .json(&PlayerInput { name: None });
+    if let Some(forwarded_for) = forwarded_for {
+        request = request.header("X-Forwarded-For", forwarded_for);
+    }
+    request.send().await.expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn player_creation_is_limited_per_ip() {
+    let app = spawn_app(&[]).await;
+    let client = reqwest::Client::new();
+
+    for _ in 0..2 {
+        let response = create_player(&app, &client, None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = create_player(&app, &client, None).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()[header::RETRY_AFTER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after > 0 && retry_after <= 60);
+
+    // forwarded addresses are ignored without trusted proxies
+    let response = create_player(&app, &client, Some("10.0.0.1")).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn forwarded_addresses_are_limited_separately_behind_a_proxy() {
+    let app = spawn_app(&["127.0.0.0/8", "10.0.0.1"]).await;
+    let client = reqwest::Client::new();
+
+    for _ in 0..2 {
+        let response = create_player(&app, &client, Some("192.0.2.1")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = create_player(&app, &client, Some("192.0.2.1")).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // addresses before the one seen by the trusted proxies are set by the client
+    let response = create_player(&app, &client, Some("192.0.2.2, 192.0.2.1")).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let response = create_player(&app, &client, Some("192.0.2.1, 10.0.0.1")).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let response = create_player(&app, &client, Some("192.0.2.1, 192.0.2.2")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn score_submission_is_limited_per_player_and_leaderboard() {
+    let app = spawn_app(&[]).await;
+    let client = reqwest::Client::new();
+    let token = helper::new_admin(&app, &client).await;
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
+    let other_leaderboard = helper::create_leaderboard(&app, &client, &token).await;
+
+    let mut players = vec![];
+    for _ in 0..2 {
+        players.push(
+            create_player(&app, &client, None)
+                .await
+                .json::<Player>()
+                .await
+                .unwrap(),
+        );
+    }
+
+    // an unsigned score doesn't use the limit of the player
+    let forged = ScoreInput::new(1.0, players[0].clone(), None, Uuid::new_v4());
+    let response = client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&forged)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let response = client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(
+            10.0,
+            players[0].clone(),
+            None,
+            leaderboard.key,
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(
+            11.0,
+            players[0].clone(),
+            None,
+            leaderboard.key,
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.headers().contains_key(header::RETRY_AFTER));
+
+    // other players and other leaderboards have their own limits
+    let response = client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(
+            12.0,
+            players[1].clone(),
+            None,
+            leaderboard.key,
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/scores/{}",
+            app.address, other_leaderboard.id
+        ))
+        .json(&ScoreInput::new(
+            13.0,
+            players[0].clone(),
+            None,
+            other_leaderboard.key,
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn reads_are_limited_per_ip() {
+    let app = spawn_app(&[]).await;
+    let client = reqwest::Client::new();
+    let token = helper::new_admin(&app, &client).await;
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
+
+    for _ in 0..2 {
+        let response = client
+            .get(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = client
+        .get(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()[header::RETRY_AFTER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after > 0 && retry_after <= 10);
+
+    let response = client
+        .get(format!(
+            "{}/api/v1/scores/{}/live",
+            app.address, leaderboard.id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
@@ -0,0 +1,68 @@
+use reqwest::StatusCode;
+use uuid::Uuid;
+
+mod helper;
+
+#[tokio::test]
+async fn request_id_is_generated() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/health_check", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+    let request_id = response.headers()["x-request-id"].to_str().unwrap();
+    assert!(Uuid::parse_str(request_id).is_ok());
+
+    // each request gets its own
+    let response = client
+        .get(format!("{}/health_check", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_ne!(response.headers()["x-request-id"], request_id);
+}
+
+#[tokio::test]
+async fn request_id_is_propagated() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/health_check", app.address))
+        .header("X-Request-Id", "from-the-proxy.42")
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.headers()["x-request-id"], "from-the-proxy.42");
+
+    // also on errors
+    let response = client
+        .get(format!("{}/api/v1/leaderboards", app.address))
+        .header("X-Request-Id", "from-the-proxy.43")
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.headers()["x-request-id"], "from-the-proxy.43");
+}
+
+#[tokio::test]
+async fn invalid_request_id_is_replaced() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    for invalid in ["with spaces", &"a".repeat(200)] {
+        let response = client
+            .get(format!("{}/health_check", app.address))
+            .header("X-Request-Id", invalid)
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        let request_id = response.headers()["x-request-id"].to_str().unwrap();
+        assert!(Uuid::parse_str(request_id).is_ok());
+    }
+}
@@ -1,19 +1,8 @@
-use jornet_server::domains::{
-    admin::TokenReply,
-    leaderboard::{Leaderboard, LeaderboardInput},
-    player::{Player, PlayerInput},
-    score::ScoreInput,
-};
-use serde::Serialize;
+use jornet_server::domains::{player::Player, score::ScoreInput};
 use uuid::Uuid;
 
 mod helper;
 
-#[derive(Serialize)]
-struct UuidInput {
-    uuid: Uuid,
-}
-
 #[tokio::test]
 async fn save_score_unknown_player() {
     let app = helper::spawn_app().await;
@@ -25,33 +14,12 @@ async fn save_score_unknown_player() {
         key: Uuid::new_v4(),
     };
 
-    let token = client
-        .post(&format!("{}/oauth/by_uuid", app.address))
-        .json(&UuidInput {
-            uuid: Uuid::new_v4(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<TokenReply>()
-        .await
-        .expect("got body");
-
-    let leaderboard = client
-        .post(&format!("{}/api/v1/leaderboards", app.address))
-        .bearer_auth(token.token)
-        .json(&LeaderboardInput {
-            name: "my leaderboard".to_string(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Leaderboard>()
-        .await
-        .expect("valid leaderboard");
+    let token = helper::new_admin(&app, &client).await;
+
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
 
     let response = client
-        .post(&format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
         .json(&ScoreInput::new(543.21, player, None, leaderboard.key))
         .send()
         .await
@@ -65,45 +33,16 @@ async fn save_score_wrong_key() {
     let app = helper::spawn_app().await;
     let client = reqwest::Client::new();
 
-    let mut player = client
-        .post(&format!("{}/api/v1/players", app.address))
-        .json(&PlayerInput { name: None })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Player>()
-        .await
-        .unwrap();
+    let mut player = helper::create_player(&app, &client, None).await;
 
     player.key = Uuid::new_v4();
 
-    let token = client
-        .post(&format!("{}/oauth/by_uuid", app.address))
-        .json(&UuidInput {
-            uuid: Uuid::new_v4(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<TokenReply>()
-        .await
-        .expect("got body");
-
-    let leaderboard = client
-        .post(&format!("{}/api/v1/leaderboards", app.address))
-        .bearer_auth(token.token)
-        .json(&LeaderboardInput {
-            name: "my leaderboard".to_string(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Leaderboard>()
-        .await
-        .expect("valid leaderboard");
+    let token = helper::new_admin(&app, &client).await;
+
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
 
     let response = client
-        .post(&format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
         .json(&ScoreInput::new(543.21, player, None, leaderboard.key))
         .send()
         .await
@@ -117,43 +56,14 @@ async fn save_score_wrong_leaderboard_key() {
     let app = helper::spawn_app().await;
     let client = reqwest::Client::new();
 
-    let player = client
-        .post(&format!("{}/api/v1/players", app.address))
-        .json(&PlayerInput { name: None })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Player>()
-        .await
-        .unwrap();
+    let player = helper::create_player(&app, &client, None).await;
 
-    let token = client
-        .post(&format!("{}/oauth/by_uuid", app.address))
-        .json(&UuidInput {
-            uuid: Uuid::new_v4(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<TokenReply>()
-        .await
-        .expect("got body");
-
-    let leaderboard = client
-        .post(&format!("{}/api/v1/leaderboards", app.address))
-        .bearer_auth(token.token)
-        .json(&LeaderboardInput {
-            name: "my leaderboard".to_string(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Leaderboard>()
-        .await
-        .expect("valid leaderboard");
+    let token = helper::new_admin(&app, &client).await;
+
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
 
     let response = client
-        .post(&format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
         .json(&ScoreInput::new(543.21, player, None, Uuid::new_v4()))
         .send()
         .await
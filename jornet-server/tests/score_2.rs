@@ -1,61 +1,21 @@
-use jornet_server::domains::{
-    admin::TokenReply,
-    leaderboard::{Leaderboard, LeaderboardInput},
-    player::{Player, PlayerInput},
-    score::ScoreInput,
-};
-use serde::Serialize;
+use jornet_server::domains::score::ScoreInput;
 use uuid::Uuid;
 
 mod helper;
 
-#[derive(Serialize)]
-struct UuidInput {
-    uuid: Uuid,
-}
-
 #[tokio::test]
 async fn save_score() {
     let app = helper::spawn_app().await;
     let client = reqwest::Client::new();
 
-    let player = client
-        .post(&format!("{}/api/v1/players", app.address))
-        .json(&PlayerInput { name: None })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Player>()
-        .await
-        .unwrap();
+    let player = helper::create_player(&app, &client, None).await;
 
-    let token = client
-        .post(&format!("{}/oauth/by_uuid", app.address))
-        .json(&UuidInput {
-            uuid: Uuid::new_v4(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<TokenReply>()
-        .await
-        .expect("got body");
-
-    let leaderboard = client
-        .post(&format!("{}/api/v1/leaderboards", app.address))
-        .bearer_auth(token.token)
-        .json(&LeaderboardInput {
-            name: "my leaderboard".to_string(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Leaderboard>()
-        .await
-        .expect("valid leaderboard");
+    let token = helper::new_admin(&app, &client).await;
+
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
 
     let response = client
-        .post(&format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
         .json(&ScoreInput::new(543.21, player, None, leaderboard.key))
         .send()
         .await
@@ -69,18 +29,10 @@ async fn save_score_to_missing_dashboard() {
     let app = helper::spawn_app().await;
     let client = reqwest::Client::new();
 
-    let player = client
-        .post(&format!("{}/api/v1/players", app.address))
-        .json(&PlayerInput { name: None })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Player>()
-        .await
-        .unwrap();
+    let player = helper::create_player(&app, &client, None).await;
 
     let response = client
-        .post(&format!("{}/api/v1/scores/{}", app.address, Uuid::new_v4()))
+        .post(format!("{}/api/v1/scores/{}", app.address, Uuid::new_v4()))
         .json(&ScoreInput::new(543.21, player, None, Uuid::new_v4()))
         .send()
         .await
@@ -94,44 +46,17 @@ async fn save_score_wrong_hmac() {
     let app = helper::spawn_app().await;
     let client = reqwest::Client::new();
 
-    let player = client
-        .post(&format!("{}/api/v1/players", app.address))
-        .json(&PlayerInput { name: None })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Player>()
-        .await
-        .unwrap();
+    let player = helper::create_player(&app, &client, None).await;
 
-    let token = client
-        .post(&format!("{}/oauth/by_uuid", app.address))
-        .json(&UuidInput { uuid: player.id })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<TokenReply>()
-        .await
-        .expect("got body");
-
-    let leaderboard = client
-        .post(&format!("{}/api/v1/leaderboards", app.address))
-        .bearer_auth(token.token)
-        .json(&LeaderboardInput {
-            name: "my leaderboard".to_string(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Leaderboard>()
-        .await
-        .expect("valid leaderboard");
+    let token = helper::login(&app, &client, player.id).await;
+
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
 
     let mut score = ScoreInput::new(543.21, player, None, leaderboard.key);
     score.k = "a5c825056477825c755cac22aff7c4ab".to_string();
 
     let response = client
-        .post(&format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
         .json(&score)
         .send()
         .await
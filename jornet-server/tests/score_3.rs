@@ -1,63 +1,26 @@
 use jornet_server::domains::{
-    admin::TokenReply,
-    leaderboard::{Leaderboard, LeaderboardInput},
-    player::{Player, PlayerInput},
-    score::ScoreInput,
+    player::Player,
+    score::{ScoreInput, SortDirection},
 };
-use serde::Serialize;
 use uuid::Uuid;
 
 mod helper;
 
-#[derive(Serialize)]
-struct UuidInput {
-    uuid: Uuid,
-}
-
 #[tokio::test]
 async fn save_score_wrong_timestamp() {
     let app = helper::spawn_app().await;
     let client = reqwest::Client::new();
 
-    let player = client
-        .post(&format!("{}/api/v1/players", app.address))
-        .json(&PlayerInput { name: None })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Player>()
-        .await
-        .unwrap();
+    let player = helper::create_player(&app, &client, None).await;
 
-    let token = client
-        .post(&format!("{}/oauth/by_uuid", app.address))
-        .json(&UuidInput {
-            uuid: Uuid::new_v4(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<TokenReply>()
-        .await
-        .expect("got body");
-
-    let leaderboard = client
-        .post(&format!("{}/api/v1/leaderboards", app.address))
-        .bearer_auth(token.token)
-        .json(&LeaderboardInput {
-            name: "my leaderboard".to_string(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Leaderboard>()
-        .await
-        .expect("valid leaderboard");
+    let token = helper::new_admin(&app, &client).await;
+
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
 
     let mut score = ScoreInput::new(543.21, player, None, leaderboard.key);
     score.timestamp = 5120;
     let response = client
-        .post(&format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
         .json(&score)
         .send()
         .await
@@ -71,50 +34,21 @@ async fn save_score_repeat_score() {
     let app = helper::spawn_app().await;
     let client = reqwest::Client::new();
 
-    let player = client
-        .post(&format!("{}/api/v1/players", app.address))
-        .json(&PlayerInput { name: None })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Player>()
-        .await
-        .unwrap();
+    let player = helper::create_player(&app, &client, None).await;
 
-    let token = client
-        .post(&format!("{}/oauth/by_uuid", app.address))
-        .json(&UuidInput {
-            uuid: Uuid::new_v4(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<TokenReply>()
-        .await
-        .expect("got body");
-
-    let leaderboard = client
-        .post(&format!("{}/api/v1/leaderboards", app.address))
-        .bearer_auth(token.token)
-        .json(&LeaderboardInput {
-            name: "my leaderboard".to_string(),
-        })
-        .send()
-        .await
-        .expect("Failed to execute request.")
-        .json::<Leaderboard>()
-        .await
-        .expect("valid leaderboard");
+    let token = helper::new_admin(&app, &client).await;
+
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
 
     let score = ScoreInput::new(543.21, player, None, leaderboard.key);
     let response1 = client
-        .post(&format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
         .json(&score)
         .send()
         .await
         .expect("Failed to execute request.");
     let response2 = client
-        .post(&format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
         .json(&score)
         .send()
         .await
@@ -123,3 +57,62 @@ async fn save_score_repeat_score() {
     assert!(response1.status().is_success());
     assert!(response2.status().is_server_error());
 }
+
+#[tokio::test]
+async fn score_needs_existing_leaderboard_and_player() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let player = helper::create_player(&app, &client, None).await;
+
+    let token = helper::new_admin(&app, &client).await;
+
+    let leaderboard = helper::create_leaderboard(&app, &client, &token).await;
+
+    let unknown_player = Player {
+        id: Uuid::new_v4(),
+        name: "unknown".to_string(),
+        key: Uuid::new_v4(),
+    };
+    let score = ScoreInput::new(543.21, unknown_player, None, leaderboard.key);
+    assert!(!app.storage.insert_score(leaderboard.id, &score).await);
+
+    let score = ScoreInput::new(543.21, player, None, leaderboard.key);
+    assert!(!app.storage.insert_score(Uuid::new_v4(), &score).await);
+    assert!(app.storage.insert_score(leaderboard.id, &score).await);
+}
+
+#[tokio::test]
+async fn ranked_scores_are_paged_from_the_best() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (_, leaderboard, player) = helper::setup(&app, &client).await;
+
+    for (timestamp, value) in [10.0, 30.0, 20.0, 40.0].into_iter().enumerate() {
+        let mut score = ScoreInput::new(value, player.clone(), None, leaderboard.key);
+        score.timestamp += timestamp as u64;
+        assert!(app.storage.insert_score(leaderboard.id, &score).await);
+    }
+
+    assert_eq!(app.storage.count_scores(leaderboard.id).await, 4);
+    let page = app
+        .storage
+        .get_ranked_scores(leaderboard.id, SortDirection::Descending, 1, 2)
+        .await;
+    assert_eq!(
+        page.iter()
+            .map(|score| score.score.as_f64())
+            .collect::<Vec<_>>(),
+        vec![30.0, 20.0]
+    );
+    let page = app
+        .storage
+        .get_ranked_scores(leaderboard.id, SortDirection::Ascending, 1, 2)
+        .await;
+    assert_eq!(
+        page.iter()
+            .map(|score| score.score.as_f64())
+            .collect::<Vec<_>>(),
+        vec![20.0, 30.0]
+    );
+}
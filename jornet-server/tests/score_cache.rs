@@ -0,0 +1,170 @@
+use jornet_server::{
+    cache::{Page, ScoreCache},
+    domains::score::ScoreInput,
+};
+use reqwest::{header, StatusCode};
+use serde_json::Value;
+
+mod helper;
+
+#[tokio::test]
+async fn get_scores_not_modified() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (_, leaderboard, player) = helper::setup(&app, &client).await;
+
+    let response = client
+        .get(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[header::ETAG].clone();
+
+    let response = client
+        .get(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .header(header::IF_NONE_MATCH, etag.clone())
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()[header::ETAG], etag);
+    assert!(response.bytes().await.unwrap().is_empty());
+
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(543.21, player, None, leaderboard.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // a new score changes the tag
+    let response = client
+        .get(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .header(header::IF_NONE_MATCH, etag.clone())
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers()[header::ETAG], etag);
+    assert_eq!(response.json::<Vec<Value>>().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn moderation_updates_cached_scores() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, player) = helper::setup(&app, &client).await;
+
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(543.21, player, None, leaderboard.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let scores = client
+        .get(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<Value>>()
+        .await
+        .unwrap();
+    assert_eq!(scores.len(), 1);
+
+    let response = client
+        .delete(format!(
+            "{}/api/v1/leaderboards/{}/scores",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let scores = client
+        .get(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<Value>>()
+        .await
+        .unwrap();
+    assert!(scores.is_empty());
+}
+
+#[tokio::test]
+async fn concurrent_misses_read_the_storage_once() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (_, leaderboard, player) = helper::setup(&app, &client).await;
+    let score = ScoreInput::new(543.21, player, None, leaderboard.key);
+    assert!(app.storage.insert_score(leaderboard.id, &score).await);
+
+    let cache = ScoreCache::default();
+    let page = Page::Ranked {
+        page: 1,
+        per_page: 10,
+    };
+    let pages = futures_util::future::join_all(
+        (0..10).map(|_| cache.get(app.storage.as_ref(), leaderboard.id, page)),
+    )
+    .await;
+    assert!(pages.iter().all(|cached| cached.scores.len() == 1));
+    assert_eq!(cache.lookups().with_label_values(&["miss"]).get(), 1);
+    assert_eq!(cache.lookups().with_label_values(&["hit"]).get(), 9);
+
+    // other pages are cached separately, and all forgotten together
+    let other = Page::Ranked {
+        page: 2,
+        per_page: 10,
+    };
+    assert!(cache
+        .get(app.storage.as_ref(), leaderboard.id, other)
+        .await
+        .scores
+        .is_empty());
+    cache.invalidate(leaderboard.id);
+    cache.get(app.storage.as_ref(), leaderboard.id, page).await;
+    cache.get(app.storage.as_ref(), leaderboard.id, other).await;
+    assert_eq!(cache.lookups().with_label_values(&["miss"]).get(), 4);
+}
+
+#[tokio::test]
+async fn v2_pages_change_with_the_leaderboard() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (_, leaderboard, player) = helper::setup(&app, &client).await;
+
+    let page_two = format!(
+        "{}/api/v2/scores/{}?page=2&per_page=1",
+        app.address, leaderboard.id
+    );
+    let response = client
+        .get(&page_two)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let etag = response.headers()[header::ETAG].clone();
+
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(543.21, player, None, leaderboard.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // still empty, but the total changed
+    let response = client
+        .get(&page_two)
+        .header(header::IF_NONE_MATCH, etag.clone())
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+    let page = response.json::<Value>().await.unwrap();
+    assert_eq!(page["scores"], serde_json::json!([]));
+    assert_eq!(page["total"], 1);
+}
@@ -0,0 +1,275 @@
+use jornet_server::domains::{
+    leaderboard::Leaderboard,
+    player::Player,
+    score::{ScoreInput, ScoreType, ScoreValue, SortDirection},
+    v2::{ScoreInputV2, ScorePage},
+};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+mod helper;
+
+/// A leaderboard of the given type, and a player.
+async fn setup(
+    app: &helper::TestApp,
+    client: &reqwest::Client,
+    score_type: Option<ScoreType>,
+) -> (Leaderboard, Player) {
+    let token = helper::new_admin(app, client).await;
+    let leaderboard = helper::create_typed_leaderboard(app, client, &token, score_type).await;
+    let player = helper::create_player(app, client, None).await;
+
+    (leaderboard, player)
+}
+
+async fn send_score(
+    app: &helper::TestApp,
+    client: &reqwest::Client,
+    leaderboard: Uuid,
+    score: &ScoreInput,
+) -> StatusCode {
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard))
+        .json(score)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .status()
+}
+
+async fn get_scores(app: &helper::TestApp, client: &reqwest::Client, leaderboard: Uuid) -> Value {
+    client
+        .get(format!("{}/api/v1/scores/{}", app.address, leaderboard))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Value>()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn leaderboards_are_f32_by_default() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (leaderboard, player) = setup(&app, &client, None).await;
+    assert_eq!(leaderboard.score_type, ScoreType::F32);
+
+    let score = ScoreInput::new(543.21, player, None, leaderboard.key);
+    assert_eq!(
+        send_score(&app, &client, leaderboard.id, &score).await,
+        StatusCode::OK
+    );
+
+    // as sent, without the digits of a conversion to `f64`
+    let scores = get_scores(&app, &client, leaderboard.id).await;
+    assert_eq!(scores[0]["score"], json!(543.21));
+}
+
+#[tokio::test]
+async fn i64_scores_are_exact() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (leaderboard, player) = setup(&app, &client, Some(ScoreType::I64)).await;
+    assert_eq!(leaderboard.score_type, ScoreType::I64);
+
+    // neither exact as `f32` nor as `f64`
+    for value in [20_000_001, i64::MAX - 1, i64::MAX] {
+        let score = ScoreInput::with_type(
+            value.into(),
+            ScoreType::I64,
+            player.clone(),
+            None,
+            leaderboard.key,
+        );
+        assert_eq!(
+            send_score(&app, &client, leaderboard.id, &score).await,
+            StatusCode::OK
+        );
+    }
+
+    let scores = get_scores(&app, &client, leaderboard.id).await;
+    let mut values = scores
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|score| score["score"].as_i64().unwrap())
+        .collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(values, vec![20_000_001, i64::MAX - 1, i64::MAX]);
+
+    let best = app
+        .storage
+        .get_best_score(leaderboard.id, SortDirection::Descending)
+        .await;
+    assert_eq!(best, Some(ScoreValue::I64(i64::MAX)));
+
+    // decimals can't be kept
+    let score = ScoreInput::with_type(
+        1.5.into(),
+        ScoreType::I64,
+        player.clone(),
+        None,
+        leaderboard.key,
+    );
+    assert!(send_score(&app, &client, leaderboard.id, &score)
+        .await
+        .is_server_error());
+}
+
+#[tokio::test]
+async fn f64_scores_keep_their_precision() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (leaderboard, player) = setup(&app, &client, Some(ScoreType::F64)).await;
+
+    let value = 0.1 + 0.2;
+    let score = ScoreInput::with_type(
+        value.into(),
+        ScoreType::F64,
+        player.clone(),
+        None,
+        leaderboard.key,
+    );
+    assert_eq!(
+        send_score(&app, &client, leaderboard.id, &score).await,
+        StatusCode::OK
+    );
+
+    let scores = get_scores(&app, &client, leaderboard.id).await;
+    assert_eq!(scores[0]["score"].as_f64(), Some(value));
+}
+
+#[tokio::test]
+async fn durations_are_positive_integers() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (leaderboard, player) = setup(&app, &client, Some(ScoreType::DurationMs)).await;
+
+    let score = ScoreInput::with_type(
+        83_456.into(),
+        ScoreType::DurationMs,
+        player.clone(),
+        None,
+        leaderboard.key,
+    );
+    assert_eq!(
+        send_score(&app, &client, leaderboard.id, &score).await,
+        StatusCode::OK
+    );
+
+    let score = ScoreInput::with_type(
+        (-1).into(),
+        ScoreType::DurationMs,
+        player.clone(),
+        None,
+        leaderboard.key,
+    );
+    assert!(send_score(&app, &client, leaderboard.id, &score)
+        .await
+        .is_server_error());
+
+    let scores = get_scores(&app, &client, leaderboard.id).await;
+    assert_eq!(scores.as_array().unwrap().len(), 1);
+    assert_eq!(scores[0]["score"], json!(83_456));
+}
+
+#[tokio::test]
+async fn durations_rank_the_shortest_first() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (leaderboard, player) = setup(&app, &client, Some(ScoreType::DurationMs)).await;
+    assert_eq!(
+        leaderboard.score_type.sort_direction(),
+        SortDirection::Ascending
+    );
+
+    for value in [83_456, 61_002, 95_310] {
+        let score = ScoreInput::with_type(
+            value.into(),
+            ScoreType::DurationMs,
+            player.clone(),
+            None,
+            leaderboard.key,
+        );
+        assert_eq!(
+            send_score(&app, &client, leaderboard.id, &score).await,
+            StatusCode::OK
+        );
+    }
+
+    let best = app
+        .storage
+        .get_best_score(leaderboard.id, SortDirection::Ascending)
+        .await;
+    assert_eq!(best, Some(ScoreValue::I64(61_002)));
+
+    let page = client
+        .get(format!("{}/api/v2/scores/{}", app.address, leaderboard.id))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<ScorePage>()
+        .await
+        .unwrap();
+    assert_eq!(
+        page.scores
+            .iter()
+            .map(|score| score.score)
+            .collect::<Vec<_>>(),
+        vec![61_002, 83_456, 95_310]
+    );
+}
+
+#[tokio::test]
+async fn signature_covers_the_type() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (leaderboard, player) = setup(&app, &client, Some(ScoreType::DurationMs)).await;
+
+    // the same value, signed for other types
+    for (value, score_type) in [
+        (ScoreValue::I64(1000), ScoreType::I64),
+        (ScoreValue::F64(1000.0), ScoreType::F64),
+    ] {
+        let score = ScoreInput::with_type(value, score_type, player.clone(), None, leaderboard.key);
+        assert!(send_score(&app, &client, leaderboard.id, &score)
+            .await
+            .is_server_error());
+    }
+    let score = ScoreInput::new(1000.0, player.clone(), None, leaderboard.key);
+    assert!(send_score(&app, &client, leaderboard.id, &score)
+        .await
+        .is_server_error());
+
+    let scores = get_scores(&app, &client, leaderboard.id).await;
+    assert!(scores.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn v2_on_i64_leaderboard() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (leaderboard, player) = setup(&app, &client, Some(ScoreType::I64)).await;
+
+    // out of range for the `f32` leaderboards
+    let score = ScoreInputV2::new(20_000_001, &player, None, 1, leaderboard.key);
+    let response = client
+        .post(format!("{}/api/v2/scores/{}", app.address, leaderboard.id))
+        .json(&score)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let page = client
+        .get(format!("{}/api/v2/scores/{}", app.address, leaderboard.id))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<ScorePage>()
+        .await
+        .unwrap();
+    assert_eq!(page.scores[0].score, 20_000_001);
+}
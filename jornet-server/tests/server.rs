@@ -0,0 +1,36 @@
+use jornet_server::{
+    configuration::get_configuration,
+    domains::player::{Player, PlayerInput},
+};
+use reqwest::StatusCode;
+
+mod helper;
+
+#[tokio::test]
+async fn payloads_over_the_limit_are_rejected() {
+    let mut configuration = get_configuration();
+    configuration.server.max_payload_bytes = 1024;
+    let app = helper::spawn_app_with_configuration(configuration).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/api/v1/players", app.address))
+        .json(&PlayerInput {
+            name: Some("a".repeat(900)),
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.json::<Player>().await.unwrap().name.len(), 900);
+
+    let response = client
+        .post(format!("{}/api/v1/players", app.address))
+        .json(&PlayerInput {
+            name: Some("a".repeat(2000)),
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
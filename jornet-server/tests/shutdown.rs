@@ -0,0 +1,185 @@
+//! Sends SIGTERM to the test process, so it must be the only test of this file.
+
+use std::{
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use actix_web::{web, App, HttpResponse, HttpServer};
+use jornet_server::domains::{
+    admin::TokenReply,
+    leaderboard::{Leaderboard, LeaderboardInput},
+    player::{Player, PlayerInput},
+    score::ScoreInput,
+    webhook::{DeliveryStatus, RegisteredWebhook, WebhookEvent, WebhookFormat, WebhookInput},
+};
+use reqwest::StatusCode;
+use uuid::Uuid;
+
+mod helper;
+
+/// A webhook receiver answering after `delay`, with an error the first time. Returns its url and
+/// the number of requests it received.
+fn start_receiver(delay: Duration) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let received = Arc::new(AtomicUsize::new(0));
+
+    let data = web::Data::from(received.clone());
+    let server = HttpServer::new(move || {
+        App::new().app_data(data.clone()).route(
+            "/hook",
+            web::post().to(move |received: web::Data<AtomicUsize>| async move {
+                let count = received.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(delay).await;
+                if count == 0 {
+                    HttpResponse::InternalServerError().finish()
+                } else {
+                    HttpResponse::Ok().finish()
+                }
+            }),
+        )
+    })
+    .workers(1)
+    // keeps running after the SIGTERM to the jornet server
+    .disable_signals()
+    .listen(listener)
+    .unwrap()
+    .run();
+    tokio::spawn(server);
+
+    (format!("http://127.0.0.1:{}/hook", port), received)
+}
+
+#[tokio::test]
+async fn webhook_attempts_in_flight_are_drained_on_sigterm() {
+    let mut configuration = helper::configuration();
+    configuration.server.shutdown_timeout_seconds = 5;
+    configuration.webhooks.max_attempts = 3;
+    // never retried before the shutdown
+    configuration.webhooks.retry_delay_ms = 60_000;
+    let (storage, database) = helper::spawn_storage(&mut configuration).await;
+    let mut restarted = helper::configuration();
+    restarted.database.database_name = configuration.database.database_name.clone();
+    restarted.webhooks.retry_delay_ms = 50;
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let address = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+    let server = tokio::spawn(jornet_server::serve(
+        listener,
+        storage.clone(),
+        configuration,
+    ));
+    let client = reqwest::Client::new();
+
+    let token = client
+        .post(format!("{}/oauth/by_uuid", address))
+        .json(&helper::UuidInput {
+            uuid: Uuid::new_v4(),
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<TokenReply>()
+        .await
+        .expect("got body")
+        .token;
+    let leaderboard = client
+        .post(format!("{}/api/v1/leaderboards", address))
+        .bearer_auth(&token)
+        .json(&LeaderboardInput {
+            name: "my leaderboard".to_string(),
+            score_type: None,
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Leaderboard>()
+        .await
+        .expect("valid leaderboard");
+    let player = client
+        .post(format!("{}/api/v1/players", address))
+        .json(&PlayerInput { name: None })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Player>()
+        .await
+        .unwrap();
+
+    let (url, received) = start_receiver(Duration::from_millis(500));
+    let webhook = client
+        .post(format!(
+            "{}/api/v1/leaderboards/{}/webhooks",
+            address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .json(&WebhookInput {
+            url,
+            events: vec![WebhookEvent::Score],
+            format: WebhookFormat::Jornet,
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<RegisteredWebhook>()
+        .await
+        .expect("valid webhook")
+        .webhook;
+
+    let response = client
+        .post(format!("{}/api/v1/scores/{}", address, leaderboard.id))
+        .json(&ScoreInput::new(10.0, player, None, leaderboard.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let start = Instant::now();
+    while received.load(Ordering::SeqCst) == 0 {
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "webhook not called"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let status = std::process::Command::new("kill")
+        .args(["-TERM", &std::process::id().to_string()])
+        .status()
+        .expect("Failed to send SIGTERM");
+    assert!(status.success());
+
+    tokio::time::timeout(Duration::from_secs(10), server)
+        .await
+        .expect("server still running")
+        .unwrap()
+        .expect("server failed");
+
+    // the attempt in flight was completed and logged, the retry is left for the next start
+    let storage = helper::reopen_storage(&restarted.database, storage, &database).await;
+    let deliveries = storage.get_deliveries(webhook.id, 10).await;
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].attempts, 1);
+    assert_eq!(deliveries[0].status, DeliveryStatus::Pending);
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    tokio::spawn(
+        jornet_server::run_with_storage(listener, storage.clone(), restarted)
+            .expect("Failed to bind address"),
+    );
+    for _ in 0..100 {
+        let deliveries = storage.get_deliveries(webhook.id, 10).await;
+        if deliveries[0].status == DeliveryStatus::Delivered {
+            assert_eq!(deliveries[0].attempts, 2);
+            assert_eq!(received.load(Ordering::SeqCst), 2);
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("pending delivery not resumed");
+}
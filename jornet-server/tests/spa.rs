@@ -0,0 +1,166 @@
+use std::path::PathBuf;
+
+use jornet_server::configuration::{get_configuration, load_layers, StaticDirectory, StaticFiles};
+use reqwest::{header, StatusCode};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+use uuid::Uuid;
+
+mod helper;
+
+/// A static directory with a built UI, removed when dropped.
+struct StaticDir(PathBuf);
+
+impl StaticDir {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("jornet-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(path.join("static/js")).unwrap();
+        std::fs::write(path.join("index.html"), "<html>jornet</html>").unwrap();
+        std::fs::write(path.join("robots.txt"), "User-agent: *").unwrap();
+        std::fs::write(path.join("static/js/main.1a2b3c4d.js"), "main()").unwrap();
+        std::fs::write(path.join(".env"), "SECRET=1").unwrap();
+        Self(path)
+    }
+}
+
+impl Drop for StaticDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+async fn spawn_app(directory: &StaticDir) -> helper::TestApp {
+    let mut configuration = get_configuration();
+    configuration.static_files = StaticFiles::Directory(StaticDirectory {
+        path: directory.0.to_str().unwrap().to_string(),
+    });
+    helper::spawn_app_with_configuration(configuration).await
+}
+
+/// Send a request with the path exactly as given, as clients normalize `..` before sending.
+async fn raw_get(address: &str, path: &str) -> String {
+    let mut stream = TcpStream::connect(address.trim_start_matches("http://"))
+        .await
+        .unwrap();
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        path
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn routes_of_the_ui_get_the_index() {
+    let directory = StaticDir::new();
+    let app = spawn_app(&directory).await;
+    let client = reqwest::Client::new();
+
+    for path in ["/", "/dashboard", "/leaderboard/42/scores/"] {
+        let response = client
+            .get(format!("{}{}", &app.address, path))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+        assert_eq!(response.text().await.unwrap(), "<html>jornet</html>");
+    }
+}
+
+#[tokio::test]
+async fn files_are_served_with_cache_headers() {
+    let directory = StaticDir::new();
+    let app = spawn_app(&directory).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(format!("{}/static/js/main.1a2b3c4d.js", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CACHE_CONTROL],
+        "public, max-age=31536000, immutable"
+    );
+    assert_eq!(response.text().await.unwrap(), "main()");
+
+    let response = client
+        .get(format!("{}/robots.txt", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "no-cache");
+    let etag = response.headers()[header::ETAG].clone();
+
+    let response = client
+        .get(format!("{}/robots.txt", &app.address))
+        .header(header::IF_NONE_MATCH, etag)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    let response = client
+        .get(format!("{}/missing.js", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.headers().get(header::CACHE_CONTROL).is_none());
+}
+
+#[tokio::test]
+async fn paths_leaving_the_directory_are_rejected() {
+    let directory = StaticDir::new();
+    // a file next to the static directory
+    let outside = PathBuf::from(format!("{}.txt", directory.0.to_str().unwrap()));
+    std::fs::write(&outside, "outside").unwrap();
+    let outside_name = outside.file_name().unwrap().to_str().unwrap().to_string();
+    let app = spawn_app(&directory).await;
+
+    for path in [
+        format!("/../{}", outside_name),
+        format!("/static/../../{}", outside_name),
+        format!("/%2e%2e/{}", outside_name),
+        format!("/static/..%5c..%5c{}", outside_name),
+        format!("/{}", outside.to_str().unwrap()),
+        "/.env".to_string(),
+    ] {
+        let response = raw_get(&app.address, &path).await;
+        assert!(
+            response.starts_with("HTTP/1.1 400"),
+            "{} got {}",
+            path,
+            response
+        );
+        assert!(!response.contains("outside"), "{} got {}", path, response);
+        assert!(!response.contains("SECRET"), "{} got {}", path, response);
+    }
+
+    let _ = std::fs::remove_file(outside);
+}
+
+#[test]
+fn embedded_files_need_the_feature() {
+    let result = load_layers(
+        None,
+        vec![("JORNET_STATIC_FILES".to_string(), "Embedded".to_string())],
+    );
+
+    if cfg!(feature = "embedded-ui") {
+        assert!(result.is_ok());
+    } else {
+        assert_eq!(
+            result.expect_err("invalid configuration").errors,
+            vec!["static_files: the server was built without the `embedded-ui` feature"]
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use std::{net::TcpListener, path::PathBuf, time::Duration};
+
+use jornet_server::configuration::{get_configuration, load_layers, TlsSettings};
+use reqwest::{header, redirect::Policy, StatusCode};
+use uuid::Uuid;
+
+mod helper;
+
+/// A self-signed certificate for `localhost`, written to PEM files removed when dropped.
+struct Certificate {
+    pem: String,
+    certificate_path: PathBuf,
+    key_path: PathBuf,
+}
+
+impl Certificate {
+    fn generate() -> Self {
+        let id = Uuid::new_v4();
+        let certificate = Self {
+            pem: String::new(),
+            certificate_path: std::env::temp_dir().join(format!("jornet-{}.crt", id)),
+            key_path: std::env::temp_dir().join(format!("jornet-{}.key", id)),
+        };
+        certificate.renew()
+    }
+
+    /// A new certificate, written over the files of this one.
+    fn renew(mut self) -> Self {
+        let generated = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        self.pem = generated.cert.pem();
+        std::fs::write(&self.certificate_path, &self.pem).unwrap();
+        std::fs::write(&self.key_path, generated.signing_key.serialize_pem()).unwrap();
+        self
+    }
+
+    fn settings(&self, redirect_http_port: Option<u16>) -> TlsSettings {
+        TlsSettings {
+            certificate_path: self.certificate_path.to_str().unwrap().to_string(),
+            key_path: self.key_path.to_str().unwrap().to_string(),
+            redirect_http_port,
+        }
+    }
+
+    /// A client only trusting this certificate.
+    fn client(&self) -> reqwest::Client {
+        reqwest::Client::builder()
+            .tls_built_in_root_certs(false)
+            .add_root_certificate(reqwest::Certificate::from_pem(self.pem.as_bytes()).unwrap())
+            // a new connection for each request, to get the current certificate
+            .pool_max_idle_per_host(0)
+            .build()
+            .unwrap()
+    }
+}
+
+impl Drop for Certificate {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.certificate_path);
+        let _ = std::fs::remove_file(&self.key_path);
+    }
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// Start a server with HTTPS, returning its port.
+async fn spawn_app(tls: TlsSettings) -> u16 {
+    let mut configuration = get_configuration();
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    configuration.application_port = listener.local_addr().unwrap().port();
+    configuration.tls = Some(tls);
+    let (storage, _) = helper::spawn_storage(&mut configuration).await;
+
+    let port = configuration.application_port;
+    let server = jornet_server::run_with_storage(listener, storage, configuration)
+        .expect("Failed to bind address");
+    tokio::spawn(server);
+    port
+}
+
+#[tokio::test]
+async fn https_is_served_and_http_redirected() {
+    let certificate = Certificate::generate();
+    let redirect_port = free_port();
+    let port = spawn_app(certificate.settings(Some(redirect_port))).await;
+
+    let response = certificate
+        .client()
+        .get(format!("https://localhost:{}/health_check", port))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // plain HTTP isn't served on the HTTPS port
+    assert!(reqwest::Client::new()
+        .get(format!("http://localhost:{}/health_check", port))
+        .send()
+        .await
+        .is_err());
+
+    let client = reqwest::Client::builder()
+        .redirect(Policy::none())
+        .build()
+        .unwrap();
+    let response = client
+        .post(format!(
+            "http://localhost:{}/api/v1/scores/{}?since=2",
+            redirect_port,
+            Uuid::nil()
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(
+        response.headers()[header::LOCATION],
+        format!(
+            "https://localhost:{}/api/v1/scores/{}?since=2",
+            port,
+            Uuid::nil()
+        )
+        .as_str()
+    );
+}
+
+#[tokio::test]
+async fn certificate_is_reloaded_on_sighup() {
+    let certificate = Certificate::generate();
+    let port = spawn_app(certificate.settings(None)).await;
+    let url = format!("https://localhost:{}/health_check", port);
+
+    let old_client = certificate.client();
+    let response = old_client
+        .get(&url)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let certificate = certificate.renew();
+    let new_client = certificate.client();
+    // still the old one until the signal
+    assert!(new_client.get(&url).send().await.is_err());
+
+    let status = std::process::Command::new("kill")
+        .args(["-HUP", &std::process::id().to_string()])
+        .status()
+        .expect("Failed to send SIGHUP");
+    assert!(status.success());
+
+    for _ in 0..50 {
+        if let Ok(response) = new_client.get(&url).send().await {
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(old_client.get(&url).send().await.is_err());
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!("certificate not reloaded");
+}
+
+#[test]
+fn invalid_certificates_are_reported() {
+    let errors = load_layers(
+        None,
+        vec![
+            (
+                "JORNET_TLS__CERTIFICATE_PATH".to_string(),
+                "missing.crt".to_string(),
+            ),
+            (
+                "JORNET_TLS__KEY_PATH".to_string(),
+                "missing.key".to_string(),
+            ),
+        ],
+    )
+    .expect_err("invalid configuration")
+    .errors;
+    assert_eq!(errors.len(), 1, "{:?}", errors);
+    assert!(errors[0].starts_with("tls: missing.crt: "), "{:?}", errors);
+
+    let certificate = Certificate::generate();
+    let other = Certificate::generate();
+    let errors = load_layers(
+        None,
+        vec![
+            (
+                "JORNET_TLS__CERTIFICATE_PATH".to_string(),
+                certificate.certificate_path.to_str().unwrap().to_string(),
+            ),
+            (
+                "JORNET_TLS__KEY_PATH".to_string(),
+                other.key_path.to_str().unwrap().to_string(),
+            ),
+        ],
+    )
+    .expect_err("invalid configuration")
+    .errors;
+    assert_eq!(
+        errors,
+        vec!["tls: the private key doesn't match the certificate".to_string()]
+    );
+}
@@ -0,0 +1,198 @@
+use jornet_server::domains::{
+    leaderboard::Leaderboard,
+    transfer::{Transfer, TransferInput, TransferStatus},
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+mod helper;
+
+#[derive(Deserialize)]
+struct LeaderboardWithOrganization {
+    id: Uuid,
+    organization: Uuid,
+}
+
+async fn setup(
+    app: &helper::TestApp,
+    client: &reqwest::Client,
+) -> (String, Leaderboard, Transfer, Uuid, String) {
+    let token = helper::login(app, client, Uuid::new_v4()).await;
+    let leaderboard = helper::create_leaderboard(app, client, &token).await;
+
+    let recipient = Uuid::new_v4();
+    let recipient_token = helper::login(app, client, recipient).await;
+
+    let transfer = client
+        .post(format!(
+            "{}/api/v1/leaderboards/{}/transfer",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .json(&TransferInput { to: recipient })
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Transfer>()
+        .await
+        .expect("valid transfer");
+
+    (token, leaderboard, transfer, recipient, recipient_token)
+}
+
+#[tokio::test]
+async fn accepted_transfer_moves_leaderboard() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, transfer, recipient, recipient_token) = setup(&app, &client).await;
+
+    let pending = client
+        .get(format!("{}/api/v1/transfers", app.address))
+        .bearer_auth(&recipient_token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<Transfer>>()
+        .await
+        .expect("valid transfers");
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].id, transfer.id);
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/transfers/{}/accept",
+            app.address, transfer.id
+        ))
+        .bearer_auth(&recipient_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let leaderboards = client
+        .get(format!("{}/api/v1/leaderboards", app.address))
+        .bearer_auth(&recipient_token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<LeaderboardWithOrganization>>()
+        .await
+        .expect("valid leaderboards");
+    assert_eq!(leaderboards.len(), 1);
+    assert_eq!(leaderboards[0].id, leaderboard.id);
+    assert_eq!(leaderboards[0].organization, recipient);
+
+    let leaderboards = client
+        .get(format!("{}/api/v1/leaderboards", app.address))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<LeaderboardWithOrganization>>()
+        .await
+        .expect("valid leaderboards");
+    assert!(leaderboards.is_empty());
+
+    let saved = app.storage.get_transfer(transfer.id).await.unwrap();
+    assert_eq!(saved.status, TransferStatus::Accepted);
+}
+
+#[tokio::test]
+async fn declined_transfer_keeps_leaderboard() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, _, transfer, _, recipient_token) = setup(&app, &client).await;
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/transfers/{}/decline",
+            app.address, transfer.id
+        ))
+        .bearer_auth(&recipient_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let leaderboards = client
+        .get(format!("{}/api/v1/leaderboards", app.address))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<LeaderboardWithOrganization>>()
+        .await
+        .expect("valid leaderboards");
+    assert_eq!(leaderboards.len(), 1);
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/transfers/{}/accept",
+            app.address, transfer.id
+        ))
+        .bearer_auth(&recipient_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(409, response.status());
+}
+
+#[tokio::test]
+async fn only_recipient_can_accept() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, _, transfer, _, _) = setup(&app, &client).await;
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/transfers/{}/accept",
+            app.address, transfer.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(401, response.status());
+}
+
+#[tokio::test]
+async fn only_one_pending_transfer() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, _, recipient, _) = setup(&app, &client).await;
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/leaderboards/{}/transfer",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .json(&TransferInput { to: recipient })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(409, response.status());
+
+    let response = client
+        .delete(format!(
+            "{}/api/v1/leaderboards/{}/transfer",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/leaderboards/{}/transfer",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .json(&TransferInput { to: recipient })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+}
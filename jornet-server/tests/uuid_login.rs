@@ -0,0 +1,269 @@
+use jornet_server::{
+    configuration::{AnonymousLimits, UuidLogin},
+    domains::{
+        admin::{AdminAccount, GithubCodeInput, GithubUser, TokenReply},
+        leaderboard::LeaderboardInput,
+    },
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+mod helper;
+
+async fn spawn_app_with_uuid_login(uuid_login: UuidLogin) -> helper::TestApp {
+    let mut configuration = helper::configuration();
+    configuration.uuid_login = uuid_login;
+    helper::spawn_app_with_configuration(configuration).await
+}
+
+#[tokio::test]
+async fn dev_creates_account_for_any_uuid() {
+    let app = spawn_app_with_uuid_login(UuidLogin::Dev).await;
+    let client = reqwest::Client::new();
+
+    let uuid = Uuid::new_v4();
+    let response = client
+        .post(format!("{}/oauth/by_uuid", app.address))
+        .json(&helper::UuidInput { uuid })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert!(response.status().is_success());
+
+    assert!(app
+        .storage
+        .get_admins()
+        .await
+        .iter()
+        .any(|admin| admin.id == uuid));
+}
+
+#[tokio::test]
+async fn disabled_refuses_login() {
+    let app = spawn_app_with_uuid_login(UuidLogin::Disabled).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/oauth/by_uuid", app.address))
+        .json(&helper::UuidInput {
+            uuid: Uuid::new_v4(),
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(403, response.status());
+
+    let response = client
+        .post(format!("{}/oauth/anonymous", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(403, response.status());
+
+    assert!(app.storage.get_admins().await.is_empty());
+}
+
+#[tokio::test]
+async fn anonymous_refuses_unknown_uuid() {
+    let app = spawn_app_with_uuid_login(UuidLogin::Anonymous(AnonymousLimits {
+        max_leaderboards: 1,
+    }))
+    .await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/oauth/by_uuid", app.address))
+        .json(&helper::UuidInput {
+            uuid: Uuid::new_v4(),
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status());
+}
+
+#[tokio::test]
+async fn anonymous_refuses_other_accounts() {
+    let app = spawn_app_with_uuid_login(UuidLogin::Anonymous(AnonymousLimits {
+        max_leaderboards: 1,
+    }))
+    .await;
+    let client = reqwest::Client::new();
+
+    // created by GitHub, then unlinked
+    let account = AdminAccount { id: Uuid::new_v4() };
+    assert!(app.storage.create_admin(&account).await);
+
+    let response = client
+        .post(format!("{}/oauth/by_uuid", app.address))
+        .json(&helper::UuidInput { uuid: account.id })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(403, response.status());
+}
+
+#[tokio::test]
+async fn github_account_refuses_uuid() {
+    let app = spawn_app_with_uuid_login(UuidLogin::Dev).await;
+    let client = reqwest::Client::new();
+
+    let account = AdminAccount { id: Uuid::new_v4() };
+    assert!(app.storage.create_anonymous_admin(&account).await);
+    let github = GithubUser {
+        login: "octocat".to_string(),
+        id: 583231,
+    };
+    assert!(app.storage.link_github(&github, &account).await);
+
+    let response = client
+        .post(format!("{}/oauth/by_uuid", app.address))
+        .json(&helper::UuidInput { uuid: account.id })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(403, response.status());
+}
+
+#[derive(Deserialize)]
+struct Whoami {
+    admin: Admin,
+}
+
+#[derive(Deserialize)]
+struct Admin {
+    id: Uuid,
+}
+
+#[tokio::test]
+async fn anonymous_account_can_log_back_in() {
+    let app = spawn_app_with_uuid_login(UuidLogin::Anonymous(AnonymousLimits {
+        max_leaderboards: 1,
+    }))
+    .await;
+    let client = reqwest::Client::new();
+
+    let token = client
+        .post(format!("{}/oauth/anonymous", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<TokenReply>()
+        .await
+        .expect("got body");
+
+    let whoami = client
+        .get(format!("{}/api/v1/admin/whoami", app.address))
+        .bearer_auth(token.token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Whoami>()
+        .await
+        .expect("got body");
+
+    let response = client
+        .post(format!("{}/oauth/by_uuid", app.address))
+        .json(&helper::UuidInput {
+            uuid: whoami.admin.id,
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn anonymous_account_is_limited() {
+    let app = spawn_app_with_uuid_login(UuidLogin::Anonymous(AnonymousLimits {
+        max_leaderboards: 1,
+    }))
+    .await;
+    let client = reqwest::Client::new();
+
+    let token = client
+        .post(format!("{}/oauth/anonymous", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<TokenReply>()
+        .await
+        .expect("got body");
+
+    let response = client
+        .post(format!("{}/api/v1/leaderboards", app.address))
+        .bearer_auth(&token.token)
+        .json(&LeaderboardInput {
+            name: "first leaderboard".to_string(),
+            score_type: None,
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let response = client
+        .post(format!("{}/api/v1/leaderboards", app.address))
+        .bearer_auth(&token.token)
+        .json(&LeaderboardInput {
+            name: "second leaderboard".to_string(),
+            score_type: None,
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(403, response.status());
+}
+
+#[tokio::test]
+async fn dev_account_is_not_limited() {
+    let app = spawn_app_with_uuid_login(UuidLogin::Dev).await;
+    let client = reqwest::Client::new();
+
+    let token = client
+        .post(format!("{}/oauth/anonymous", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<TokenReply>()
+        .await
+        .expect("got body");
+
+    for i in 0..3 {
+        let response = client
+            .post(format!("{}/api/v1/leaderboards", app.address))
+            .bearer_auth(&token.token)
+            .json(&LeaderboardInput {
+                name: format!("leaderboard {}", i),
+                score_type: None,
+            })
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert!(response.status().is_success());
+    }
+}
+
+#[tokio::test]
+async fn link_github_requires_authentication() {
+    let app = spawn_app_with_uuid_login(UuidLogin::Dev).await;
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(format!("{}/api/v1/admin/github", app.address))
+        .json(&GithubCodeInput {
+            code: "not-a-code".to_string(),
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(401, response.status());
+}
@@ -0,0 +1,317 @@
+use jornet_server::{
+    configuration::RateLimit,
+    domains::{
+        leaderboard::BanInput,
+        player::Player,
+        score::{ScoreInput, MAX_EXACT_F32},
+        v2::{ApiError, ErrorCode, ScoreInputV2, ScorePage},
+    },
+};
+use reqwest::{header, StatusCode};
+use uuid::{uuid, Uuid};
+
+mod helper;
+
+async fn send_score(
+    app: &helper::TestApp,
+    client: &reqwest::Client,
+    leaderboard: Uuid,
+    score: &ScoreInputV2,
+) -> reqwest::Response {
+    client
+        .post(format!("{}/api/v2/scores/{}", app.address, leaderboard))
+        .json(score)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn error_of(response: reqwest::Response) -> (StatusCode, ErrorCode) {
+    let status = response.status();
+    (status, response.json::<ApiError>().await.unwrap().error)
+}
+
+#[test]
+fn signature_of_integer_scores() {
+    let player = Player {
+        id: uuid!("5b7c8f1e-3d2a-4c6b-9e8f-1a2b3c4d5e6f"),
+        name: "bevy".to_string(),
+        key: uuid!("0f1e2d3c-4b5a-4968-8776-a5b4c3d2e1f0"),
+    };
+    let score = ScoreInputV2::new(
+        20_000_000,
+        &player,
+        None,
+        1_700_000_000,
+        uuid!("c0ffee00-1234-4abc-8def-0123456789ab"),
+    );
+    assert_eq!(
+        score.k,
+        "41881cc2b6046ff00234f8bc05da1b2caa0cd57e9dd8f4071a19b8f650717a9b"
+    );
+}
+
+#[tokio::test]
+async fn save_score() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (_, leaderboard, player) = helper::setup(&app, &client).await;
+
+    let score = ScoreInputV2::new(1_000_000, &player, None, 1, leaderboard.key);
+    let response = send_score(&app, &client, leaderboard.id, &score).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = send_score(&app, &client, leaderboard.id, &score).await;
+    assert_eq!(
+        error_of(response).await,
+        (StatusCode::CONFLICT, ErrorCode::DuplicateScore)
+    );
+
+    let mut tampered = ScoreInputV2::new(12, &player, None, 2, leaderboard.key);
+    tampered.score = 13;
+    let response = send_score(&app, &client, leaderboard.id, &tampered).await;
+    assert_eq!(
+        error_of(response).await,
+        (StatusCode::FORBIDDEN, ErrorCode::InvalidSignature)
+    );
+
+    let mut unsigned = ScoreInputV2::new(12, &player, None, 3, leaderboard.key);
+    unsigned.k = "not hex".to_string();
+    let response = send_score(&app, &client, leaderboard.id, &unsigned).await;
+    assert_eq!(
+        error_of(response).await,
+        (StatusCode::FORBIDDEN, ErrorCode::InvalidSignature)
+    );
+
+    let unknown = Player {
+        id: Uuid::new_v4(),
+        name: "unknown".to_string(),
+        key: Uuid::new_v4(),
+    };
+    let score = ScoreInputV2::new(12, &unknown, None, 4, leaderboard.key);
+    let response = send_score(&app, &client, leaderboard.id, &score).await;
+    assert_eq!(
+        error_of(response).await,
+        (StatusCode::NOT_FOUND, ErrorCode::UnknownPlayer)
+    );
+
+    let score = ScoreInputV2::new(12, &player, None, 5, leaderboard.key);
+    let response = send_score(&app, &client, Uuid::new_v4(), &score).await;
+    assert_eq!(
+        error_of(response).await,
+        (StatusCode::NOT_FOUND, ErrorCode::UnknownLeaderboard)
+    );
+
+    // the leaderboard keeps `f32` scores
+    for value in [MAX_EXACT_F32 + 1, -MAX_EXACT_F32 - 1, i64::MAX] {
+        let score = ScoreInputV2::new(value, &player, None, 6, leaderboard.key);
+        let response = send_score(&app, &client, leaderboard.id, &score).await;
+        assert_eq!(
+            error_of(response).await,
+            (StatusCode::UNPROCESSABLE_ENTITY, ErrorCode::ScoreOutOfRange)
+        );
+    }
+}
+
+#[tokio::test]
+async fn banned_player() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, player) = helper::setup(&app, &client).await;
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/leaderboards/{}/bans",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .json(&BanInput { player: player.id })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let score = ScoreInputV2::new(12, &player, None, 1, leaderboard.key);
+    let response = send_score(&app, &client, leaderboard.id, &score).await;
+    assert_eq!(
+        error_of(response).await,
+        (StatusCode::FORBIDDEN, ErrorCode::PlayerBanned)
+    );
+}
+
+#[tokio::test]
+async fn invalid_requests() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (_, leaderboard, player) = helper::setup(&app, &client).await;
+
+    let response = client
+        .post(format!("{}/api/v2/players", app.address))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body("{")
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(
+        error_of(response).await,
+        (StatusCode::BAD_REQUEST, ErrorCode::InvalidRequest)
+    );
+
+    // a decimal score
+    let response = client
+        .post(format!("{}/api/v2/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(1.5, player.clone(), None, leaderboard.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(
+        error_of(response).await,
+        (StatusCode::BAD_REQUEST, ErrorCode::InvalidRequest)
+    );
+
+    let score = ScoreInputV2::new(12, &player, None, 1, leaderboard.key);
+    let response = client
+        .post(format!("{}/api/v2/scores/not-a-uuid", app.address))
+        .json(&score)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(
+        error_of(response).await,
+        (StatusCode::BAD_REQUEST, ErrorCode::InvalidRequest)
+    );
+
+    let response = client
+        .get(format!(
+            "{}/api/v2/scores/{}?page=first",
+            app.address, leaderboard.id
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(
+        error_of(response).await,
+        (StatusCode::BAD_REQUEST, ErrorCode::InvalidRequest)
+    );
+}
+
+#[tokio::test]
+async fn get_scores_by_page() {
+    let app = helper::spawn_app().await;
+    let client = reqwest::Client::new();
+    let (_, leaderboard, player) = helper::setup(&app, &client).await;
+
+    for (timestamp, value) in [(1, 300), (2, 100), (3, 200)] {
+        let score = ScoreInputV2::new(value, &player, None, timestamp, leaderboard.key);
+        let response = send_score(&app, &client, leaderboard.id, &score).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+    // sent to version 1 with decimals
+    let response = client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(
+            49.6,
+            player.clone(),
+            None,
+            leaderboard.key,
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let url = format!("{}/api/v2/scores/{}", app.address, leaderboard.id);
+    let response = client
+        .get(format!("{}?per_page=3", url))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()[header::ETAG].clone();
+    let page = response.json::<ScorePage>().await.unwrap();
+    assert_eq!((page.page, page.per_page, page.total), (1, 3, 4));
+    assert_eq!(
+        page.scores
+            .iter()
+            .map(|score| score.score)
+            .collect::<Vec<_>>(),
+        vec![300, 200, 100]
+    );
+    assert_eq!(page.scores[0].player, player.name);
+
+    let response = client
+        .get(format!("{}?per_page=3&page=2", url))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    // each page has its own tag
+    assert_ne!(response.headers()[header::ETAG], etag);
+    let etag = response.headers()[header::ETAG].clone();
+    let page = response.json::<ScorePage>().await.unwrap();
+    assert_eq!(page.page, 2);
+    assert_eq!(page.scores.len(), 1);
+    assert_eq!(page.scores[0].score, 50);
+
+    let page = client
+        .get(format!("{}?per_page=1000&page=-3", url))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<ScorePage>()
+        .await
+        .unwrap();
+    assert_eq!((page.page, page.per_page, page.scores.len()), (1, 100, 4));
+
+    let response = client
+        .get(format!("{}?per_page=3&page=2", url))
+        .header(header::IF_NONE_MATCH, etag)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    let response = client
+        .get(format!("{}/api/v2/scores/{}", app.address, Uuid::new_v4()))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(
+        error_of(response).await,
+        (StatusCode::NOT_FOUND, ErrorCode::UnknownLeaderboard)
+    );
+
+    let (_, empty, _) = helper::setup(&app, &client).await;
+    let page = client
+        .get(format!("{}/api/v2/scores/{}", app.address, empty.id))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<ScorePage>()
+        .await
+        .unwrap();
+    assert_eq!((page.total, page.scores.len()), (0, 0));
+}
+
+#[tokio::test]
+async fn rate_limited() {
+    let mut configuration = helper::configuration();
+    configuration.rate_limits.score_submission_per_player = Some(RateLimit {
+        burst: 1,
+        per_minute: 1,
+    });
+    let app = helper::spawn_app_with_configuration(configuration).await;
+    let client = reqwest::Client::new();
+    let (_, leaderboard, player) = helper::setup(&app, &client).await;
+
+    let score = ScoreInputV2::new(1, &player, None, 1, leaderboard.key);
+    let response = send_score(&app, &client, leaderboard.id, &score).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let score = ScoreInputV2::new(2, &player, None, 2, leaderboard.key);
+    let response = send_score(&app, &client, leaderboard.id, &score).await;
+    assert!(response.headers().contains_key(header::RETRY_AFTER));
+    assert_eq!(
+        error_of(response).await,
+        (StatusCode::TOO_MANY_REQUESTS, ErrorCode::RateLimited)
+    );
+}
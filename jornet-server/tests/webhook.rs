@@ -0,0 +1,677 @@
+use std::{
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use hmac::{Hmac, Mac};
+use jornet_server::domains::{
+    score::{ScoreInput, ScoreType},
+    webhook::{
+        Delivery, DeliveryStatus, RegisteredWebhook, Webhook, WebhookEvent, WebhookFormat,
+        WebhookInput,
+    },
+};
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use uuid::Uuid;
+
+mod helper;
+
+#[derive(Serialize)]
+struct BanInput {
+    player: Uuid,
+}
+
+/// A request received by the [`Receiver`].
+#[derive(Clone)]
+struct Received {
+    event: String,
+    delivery: String,
+    timestamp: String,
+    signature: String,
+    body: String,
+}
+
+#[derive(Default)]
+struct ReceiverState {
+    received: Vec<Received>,
+    /// Requests to answer with an error before accepting them.
+    failures: usize,
+}
+
+/// A local webhook receiver, recording the requests it gets.
+struct Receiver {
+    url: String,
+    state: Arc<Mutex<ReceiverState>>,
+}
+
+impl Receiver {
+    fn start(failures: usize) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+        let port = listener.local_addr().unwrap().port();
+        let state = Arc::new(Mutex::new(ReceiverState {
+            received: vec![],
+            failures,
+        }));
+
+        let data = web::Data::from(state.clone());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(data.clone())
+                .route("/hook", web::post().to(receive))
+        })
+        .workers(1)
+        .listen(listener)
+        .unwrap()
+        .run();
+        tokio::spawn(server);
+
+        Self {
+            url: format!("http://127.0.0.1:{}/hook", port),
+            state,
+        }
+    }
+
+    /// Wait until `count` requests were received.
+    async fn wait_for(&self, count: usize) -> Vec<Received> {
+        for _ in 0..100 {
+            let received = self.state.lock().unwrap().received.clone();
+            if received.len() >= count {
+                return received;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("webhook not called {} times", count);
+    }
+}
+
+async fn receive(
+    state: web::Data<Mutex<ReceiverState>>,
+    request: HttpRequest,
+    body: String,
+) -> HttpResponse {
+    let header = |name: &str| {
+        request
+            .headers()
+            .get(name)
+            .map(|value| value.to_str().unwrap().to_string())
+            .unwrap_or_default()
+    };
+    let mut state = state.lock().unwrap();
+    state.received.push(Received {
+        event: header("X-Jornet-Event"),
+        delivery: header("X-Jornet-Delivery"),
+        timestamp: header("X-Jornet-Timestamp"),
+        signature: header("X-Jornet-Signature"),
+        body,
+    });
+    if state.failures > 0 {
+        state.failures -= 1;
+        HttpResponse::InternalServerError().finish()
+    } else {
+        HttpResponse::Ok().finish()
+    }
+}
+
+fn signature(secret: &str, received: &Received) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(format!("{}.{}", received.timestamp, received.body).as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+async fn spawn_app() -> helper::TestApp {
+    let mut configuration = helper::configuration();
+    configuration.webhooks.max_attempts = 3;
+    configuration.webhooks.retry_delay_ms = 50;
+    helper::spawn_app_with_configuration(configuration).await
+}
+
+async fn register(
+    app: &helper::TestApp,
+    client: &reqwest::Client,
+    token: &str,
+    leaderboard: Uuid,
+    webhook: &WebhookInput,
+) -> RegisteredWebhook {
+    client
+        .post(format!(
+            "{}/api/v1/leaderboards/{}/webhooks",
+            app.address, leaderboard
+        ))
+        .bearer_auth(token)
+        .json(webhook)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<RegisteredWebhook>()
+        .await
+        .expect("valid webhook")
+}
+
+async fn get_deliveries(
+    app: &helper::TestApp,
+    client: &reqwest::Client,
+    token: &str,
+    webhook: &Webhook,
+) -> Vec<Delivery> {
+    client
+        .get(format!(
+            "{}/api/v1/leaderboards/{}/webhooks/{}/deliveries",
+            app.address, webhook.leaderboard, webhook.id
+        ))
+        .bearer_auth(token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<Delivery>>()
+        .await
+        .expect("valid deliveries")
+}
+
+/// Wait until the last attempt of the latest delivery is logged.
+async fn wait_for_status(
+    app: &helper::TestApp,
+    client: &reqwest::Client,
+    token: &str,
+    webhook: &Webhook,
+    status: DeliveryStatus,
+) -> Delivery {
+    for _ in 0..100 {
+        let deliveries = get_deliveries(app, client, token, webhook).await;
+        if let Some(delivery) = deliveries.into_iter().next() {
+            if delivery.status == status {
+                return delivery;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("delivery never {:?}", status);
+}
+
+#[tokio::test]
+async fn signed_score_events() {
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, player) = helper::setup(&app, &client).await;
+    let receiver = Receiver::start(0);
+
+    let registered = register(
+        &app,
+        &client,
+        &token,
+        leaderboard.id,
+        &WebhookInput {
+            url: receiver.url.clone(),
+            events: vec![WebhookEvent::Score, WebhookEvent::NewLeader],
+            format: WebhookFormat::Jornet,
+        },
+    )
+    .await;
+    assert_eq!(registered.secret.len(), 64);
+
+    let response = client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(
+            10.0,
+            player.clone(),
+            None,
+            leaderboard.key,
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    // the first score is always a new leader
+    let received = receiver.wait_for(2).await;
+    let mut events: Vec<_> = received.iter().map(|r| r.event.as_str()).collect();
+    events.sort();
+    assert_eq!(events, vec!["new_leader", "score"]);
+    for request in &received {
+        assert_eq!(request.signature, signature(&registered.secret, request));
+        let body: Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(body["event"], request.event.as_str());
+        assert_eq!(body["leaderboard"]["id"], json!(leaderboard.id));
+        assert_eq!(body["data"]["player"]["name"], player.name);
+        assert_eq!(body["data"]["score"], 10.0);
+    }
+
+    // a lower score is not a new leader
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(5.0, player.clone(), None, leaderboard.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let received = receiver.wait_for(3).await;
+    assert_eq!(received[2].event, "score");
+
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(20.0, player, None, leaderboard.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let received = receiver.wait_for(5).await;
+    let new_leader = received[3..]
+        .iter()
+        .find(|request| request.event == "new_leader")
+        .expect("new leader event");
+    let body: Value = serde_json::from_str(&new_leader.body).unwrap();
+    assert_eq!(body["data"]["previous_best"], 10.0);
+
+    let deliveries = get_deliveries(&app, &client, &token, &registered.webhook).await;
+    assert_eq!(deliveries.len(), 5);
+}
+
+#[tokio::test]
+async fn shorter_durations_are_new_leaders() {
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+    let token = helper::new_admin(&app, &client).await;
+    let leaderboard =
+        helper::create_typed_leaderboard(&app, &client, &token, Some(ScoreType::DurationMs)).await;
+    let player = helper::create_player(&app, &client, None).await;
+    let receiver = Receiver::start(0);
+
+    let registered = register(
+        &app,
+        &client,
+        &token,
+        leaderboard.id,
+        &WebhookInput {
+            url: receiver.url.clone(),
+            events: vec![WebhookEvent::NewLeader],
+            format: WebhookFormat::Jornet,
+        },
+    )
+    .await;
+
+    for value in [1_000, 2_000, 500] {
+        let score = ScoreInput::with_type(
+            value.into(),
+            ScoreType::DurationMs,
+            player.clone(),
+            None,
+            leaderboard.key,
+        );
+        let response = client
+            .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+            .json(&score)
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert!(response.status().is_success());
+    }
+
+    // a longer duration is not a new leader
+    let received = receiver.wait_for(2).await;
+    let mut leaders: Vec<_> = received
+        .iter()
+        .map(|request| {
+            let body: Value = serde_json::from_str(&request.body).unwrap();
+            (
+                body["data"]["score"].clone(),
+                body["data"]["previous_best"].clone(),
+            )
+        })
+        .collect();
+    leaders.sort_by_key(|(score, _)| score.as_i64());
+    assert_eq!(
+        leaders,
+        vec![(json!(500), json!(1_000)), (json!(1_000), Value::Null)]
+    );
+    let deliveries = get_deliveries(&app, &client, &token, &registered.webhook).await;
+    assert_eq!(deliveries.len(), 2);
+}
+
+#[tokio::test]
+async fn failed_deliveries_are_retried() {
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, player) = helper::setup(&app, &client).await;
+    let receiver = Receiver::start(2);
+
+    let registered = register(
+        &app,
+        &client,
+        &token,
+        leaderboard.id,
+        &WebhookInput {
+            url: receiver.url.clone(),
+            events: vec![WebhookEvent::Score],
+            format: WebhookFormat::Jornet,
+        },
+    )
+    .await;
+
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(10.0, player, None, leaderboard.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let delivery = wait_for_status(
+        &app,
+        &client,
+        &token,
+        &registered.webhook,
+        DeliveryStatus::Delivered,
+    )
+    .await;
+    assert_eq!(delivery.attempts, 3);
+    assert_eq!(delivery.response_status, Some(200));
+    assert!(delivery.error.is_none());
+    assert!(delivery.last_attempt_at.is_some());
+
+    // all attempts are the same delivery
+    let received = receiver.wait_for(3).await;
+    assert!(received
+        .iter()
+        .all(|request| request.delivery == delivery.id.to_string()));
+}
+
+#[tokio::test]
+async fn deliveries_fail_after_max_attempts() {
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, player) = helper::setup(&app, &client).await;
+    let receiver = Receiver::start(10);
+
+    let registered = register(
+        &app,
+        &client,
+        &token,
+        leaderboard.id,
+        &WebhookInput {
+            url: receiver.url.clone(),
+            events: vec![WebhookEvent::Score],
+            format: WebhookFormat::Jornet,
+        },
+    )
+    .await;
+
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(10.0, player, None, leaderboard.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let delivery = wait_for_status(
+        &app,
+        &client,
+        &token,
+        &registered.webhook,
+        DeliveryStatus::Failed,
+    )
+    .await;
+    assert_eq!(delivery.attempts, 3);
+    assert_eq!(delivery.response_status, Some(500));
+    assert!(delivery.error.is_some());
+    assert_eq!(receiver.wait_for(3).await.len(), 3);
+}
+
+#[tokio::test]
+async fn moderation_events() {
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, player) = helper::setup(&app, &client).await;
+    let receiver = Receiver::start(0);
+
+    register(
+        &app,
+        &client,
+        &token,
+        leaderboard.id,
+        &WebhookInput {
+            url: receiver.url.clone(),
+            events: vec![WebhookEvent::PlayerBan, WebhookEvent::LeaderboardReset],
+            format: WebhookFormat::Discord,
+        },
+    )
+    .await;
+
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(
+            10.0,
+            player.clone(),
+            None,
+            leaderboard.key,
+        ))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/leaderboards/{}/bans",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .json(&BanInput { player: player.id })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let received = receiver.wait_for(1).await;
+    assert_eq!(received[0].event, "player_ban");
+    let body: Value = serde_json::from_str(&received[0].body).unwrap();
+    assert_eq!(
+        body["content"],
+        "A player was banned from my leaderboard, 1 of their scores were removed"
+    );
+
+    let response = client
+        .delete(format!(
+            "{}/api/v1/leaderboards/{}/scores",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let received = receiver.wait_for(2).await;
+    assert_eq!(received[1].event, "leaderboard_reset");
+}
+
+#[tokio::test]
+async fn manage_webhooks() {
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, _) = helper::setup(&app, &client).await;
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/leaderboards/{}/webhooks",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .json(&WebhookInput {
+            url: "ftp://example.com/hook".to_string(),
+            events: vec![WebhookEvent::Score],
+            format: WebhookFormat::Jornet,
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client
+        .post(format!(
+            "{}/api/v1/leaderboards/{}/webhooks",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .json(&WebhookInput {
+            url: "https://example.com/hook".to_string(),
+            events: vec![],
+            format: WebhookFormat::Jornet,
+        })
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let registered = register(
+        &app,
+        &client,
+        &token,
+        leaderboard.id,
+        &WebhookInput {
+            url: "https://example.com/hook".to_string(),
+            events: vec![WebhookEvent::Score],
+            format: WebhookFormat::Jornet,
+        },
+    )
+    .await;
+
+    // the secret is only returned when registering
+    let webhooks = client
+        .get(format!(
+            "{}/api/v1/leaderboards/{}/webhooks",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<Value>>()
+        .await
+        .unwrap();
+    assert_eq!(webhooks.len(), 1);
+    assert_eq!(webhooks[0]["id"], json!(registered.webhook.id));
+    assert_eq!(webhooks[0]["url"], "https://example.com/hook");
+    assert!(webhooks[0].get("secret").is_none());
+
+    // other admins can't see them
+    let other_token = helper::new_admin(&app, &client).await;
+    let response = client
+        .get(format!(
+            "{}/api/v1/leaderboards/{}/webhooks",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&other_token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client
+        .delete(format!(
+            "{}/api/v1/leaderboards/{}/webhooks/{}",
+            app.address, leaderboard.id, registered.webhook.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_success());
+
+    let response = client
+        .delete(format!(
+            "{}/api/v1/leaderboards/{}/webhooks/{}",
+            app.address, leaderboard.id, registered.webhook.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let webhooks = client
+        .get(format!(
+            "{}/api/v1/leaderboards/{}/webhooks",
+            app.address, leaderboard.id
+        ))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .json::<Vec<Value>>()
+        .await
+        .unwrap();
+    assert!(webhooks.is_empty());
+}
+
+#[tokio::test]
+async fn private_addresses_are_refused() {
+    let mut configuration = helper::configuration();
+    configuration.webhooks.allow_private_addresses = false;
+    let app = helper::spawn_app_with_configuration(configuration).await;
+    let client = reqwest::Client::new();
+    let (token, leaderboard, player) = helper::setup(&app, &client).await;
+
+    for url in [
+        "http://127.0.0.1:8080/hook",
+        "http://localhost/hook",
+        "http://169.254.169.254/latest/meta-data",
+        "http://10.0.0.1/hook",
+        "http://[::1]/hook",
+        "http://[::ffff:192.168.0.1]/hook",
+    ] {
+        let response = client
+            .post(format!(
+                "{}/api/v1/leaderboards/{}/webhooks",
+                app.address, leaderboard.id
+            ))
+            .bearer_auth(&token)
+            .json(&WebhookInput {
+                url: url.to_string(),
+                events: vec![WebhookEvent::Score],
+                format: WebhookFormat::Jornet,
+            })
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", url);
+    }
+
+    // public addresses are accepted without being contacted
+    register(
+        &app,
+        &client,
+        &token,
+        leaderboard.id,
+        &WebhookInput {
+            url: "http://93.184.215.14/hook".to_string(),
+            events: vec![WebhookEvent::PlayerBan],
+            format: WebhookFormat::Jornet,
+        },
+    )
+    .await;
+
+    // registered before the address was refused, it's checked again on delivery
+    let receiver = Receiver::start(0);
+    let webhook = Webhook {
+        id: Uuid::new_v4(),
+        leaderboard: leaderboard.id,
+        url: receiver.url.clone(),
+        events: vec![WebhookEvent::Score],
+        format: WebhookFormat::Jornet,
+        secret: "secret".to_string(),
+        created_by: Uuid::new_v4(),
+    };
+    assert!(app.storage.create_webhook(&webhook).await);
+
+    client
+        .post(format!("{}/api/v1/scores/{}", app.address, leaderboard.id))
+        .json(&ScoreInput::new(10.0, player, None, leaderboard.key))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    let delivery = wait_for_status(&app, &client, &token, &webhook, DeliveryStatus::Failed).await;
+    assert_eq!(delivery.attempts, 1);
+    assert_eq!(delivery.error.as_deref(), Some("URL not allowed"));
+    assert!(receiver.state.lock().unwrap().received.is_empty());
+}
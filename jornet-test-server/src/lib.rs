@@ -0,0 +1,204 @@
+#![warn(missing_docs)]
+
+//! A local Jornet server to test games using [`bevy_jornet`], without reaching
+//! <https://jornet.vleue.com>.
+//!
+//! The server runs in the background on a random port, keeps its data in memory, and is seeded
+//! with a leaderboard. [`TestServer::plugin`] gives a [`JornetPlugin`] already configured to use
+//! it:
+//!
+//! ```no_run
+//! # use bevy_app::App;
+//! let server = jornet_test_server::TestServer::start();
+//! let mut app = App::new();
+//! app.add_plugins(server.plugin());
+//! ```
+//!
+//! The server is stopped when the [`TestServer`] is dropped.
+
+use std::net::TcpListener;
+
+use actix_web::dev::ServerHandle;
+use bevy_jornet::JornetPlugin;
+use jornet_server::{
+    configuration::{
+        CorsSettings, DatabaseSettings, LogFormat, LogSettings, MetricsSettings, OAuth,
+        PoolSettings, RateLimitSettings, ServerSettings, Settings, StaticDirectory, StaticFiles,
+        StorageBackend, UuidLogin, WebhookSettings,
+    },
+    domains::{
+        leaderboard::Leaderboard,
+        score::{Score, ScoreType},
+    },
+    storage::{LeaderboardStore, MemoryStorage, ScoreStore},
+};
+use tokio::runtime::Runtime;
+use uuid::Uuid;
+
+/// A Jornet server running in the background, with a leaderboard ready to receive scores.
+pub struct TestServer {
+    host: String,
+    leaderboard: Uuid,
+    key: Uuid,
+    storage: MemoryStorage,
+    handle: ServerHandle,
+    runtime: Runtime,
+}
+
+impl TestServer {
+    /// Start a server with a new leaderboard.
+    pub fn start() -> Self {
+        Self::with_leaderboard(Uuid::new_v4(), Uuid::new_v4())
+    }
+
+    /// Start a server with a leaderboard using the given `id` and `key`, for games that have
+    /// them hardcoded.
+    pub fn with_leaderboard(id: Uuid, key: Uuid) -> Self {
+        Self::with_typed_leaderboard(id, key, ScoreType::default())
+    }
+
+    /// Start a server with a new leaderboard keeping scores of the given type.
+    pub fn with_score_type(score_type: ScoreType) -> Self {
+        Self::with_typed_leaderboard(Uuid::new_v4(), Uuid::new_v4(), score_type)
+    }
+
+    fn with_typed_leaderboard(id: Uuid, key: Uuid, score_type: ScoreType) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+        let port = listener.local_addr().unwrap().port();
+        let runtime = Runtime::new().expect("Failed to start the runtime");
+
+        let storage = MemoryStorage::new();
+        let leaderboard = Leaderboard {
+            id,
+            key,
+            name: "test leaderboard".to_string(),
+            score_type,
+        };
+        // the leaderboard doesn't belong to any admin, it's only there to receive scores
+        runtime.block_on(storage.create_leaderboard(&leaderboard, Uuid::new_v4()));
+
+        let handle = {
+            // the server must be created from within the runtime
+            let _guard = runtime.enter();
+            let server = jornet_server::run_in_memory(listener, storage.clone(), settings(port))
+                .expect("Failed to bind address");
+            let handle = server.handle();
+            runtime.spawn(server);
+            handle
+        };
+
+        Self {
+            host: format!("http://127.0.0.1:{}", port),
+            leaderboard: id,
+            key,
+            storage,
+            handle,
+            runtime,
+        }
+    }
+
+    /// Address of the server, to use with [`JornetPlugin::with_host`].
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Id of the seeded leaderboard.
+    pub fn leaderboard_id(&self) -> Uuid {
+        self.leaderboard
+    }
+
+    /// Key of the seeded leaderboard.
+    pub fn leaderboard_key(&self) -> Uuid {
+        self.key
+    }
+
+    /// A [`JornetPlugin`] using the seeded leaderboard on this server.
+    pub fn plugin(&self) -> JornetPlugin {
+        JornetPlugin::with_leaderboard(&self.leaderboard.to_string(), &self.key.to_string())
+            .with_host(&self.host)
+    }
+
+    /// Scores saved by the server in the seeded leaderboard.
+    pub fn scores(&self) -> Vec<Score> {
+        self.runtime
+            .block_on(self.storage.get_scores(self.leaderboard))
+    }
+
+    /// The storage used by the server, to seed or inspect more data. Scores are cached by the
+    /// server once read, changes made here to a leaderboard already refreshed by a game are not
+    /// seen by it until a new score is sent.
+    pub fn storage(&self) -> &MemoryStorage {
+        &self.storage
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.runtime.block_on(self.handle.stop(false));
+    }
+}
+
+fn settings(port: u16) -> Settings {
+    Settings {
+        database: DatabaseSettings {
+            username: String::new(),
+            password: String::new(),
+            port: 0,
+            host: String::new(),
+            database_name: String::new(),
+        },
+        application_host: "127.0.0.1".to_string(),
+        application_port: port,
+        private_key: None,
+        github_admin_app: OAuth {
+            client_id: "disabled".to_string(),
+            client_secret: "disabled".to_string(),
+        },
+        uuid_login: UuidLogin::Dev,
+        superadmins: vec![],
+        storage: StorageBackend::Memory,
+        auto_migrate: false,
+        webhooks: WebhookSettings {
+            max_attempts: 3,
+            retry_delay_ms: 100,
+            // receivers of the games under test run locally
+            allow_private_addresses: true,
+        },
+        // games under test are not throttled
+        rate_limits: RateLimitSettings {
+            player_creation_per_ip: None,
+            score_submission_per_player: None,
+            reads_per_ip: None,
+            trusted_proxies: vec![],
+        },
+        metrics: MetricsSettings { token: None },
+        // no subscriber is installed, the game under test owns the logs
+        logging: LogSettings {
+            filter: "info".to_string(),
+            format: LogFormat::Text,
+            otlp_endpoint: None,
+        },
+        server: ServerSettings {
+            workers: Some(1),
+            keep_alive_seconds: 5,
+            max_payload_bytes: 65536,
+            shutdown_timeout_seconds: 1,
+        },
+        pool: PoolSettings {
+            max_connections: 1,
+            min_connections: 0,
+            acquire_timeout_seconds: 1,
+        },
+        cors: CorsSettings {
+            allowed_origins: vec!["*".to_string()],
+            leaderboard_origins: vec![],
+            allow_credentials: false,
+            max_age_seconds: Some(3600),
+        },
+        tls: None,
+        static_files: StaticFiles::Directory(StaticDirectory {
+            path: "static".to_string(),
+        }),
+        warnings: vec![],
+    }
+}
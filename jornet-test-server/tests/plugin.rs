@@ -0,0 +1,230 @@
+use std::time::{Duration, Instant};
+
+use bevy_app::{App, TaskPoolPlugin, Update};
+use bevy_ecs::{message::MessageReader, resource::Resource, system::ResMut};
+use bevy_jornet::{JornetEvent, Leaderboard, Rank, ScoreValue};
+use jornet_server::domains::score::{self, ScoreType};
+use jornet_test_server::TestServer;
+
+#[derive(Resource, Default)]
+struct ReceivedEvents(Vec<JornetEvent>);
+
+fn collect_events(mut reader: MessageReader<JornetEvent>, mut received: ResMut<ReceivedEvents>) {
+    received.0.extend(reader.read().copied());
+}
+
+fn app(server: &TestServer) -> App {
+    let mut app = App::new();
+    app.add_plugins((TaskPoolPlugin::default(), server.plugin()))
+        .init_resource::<ReceivedEvents>()
+        .add_systems(Update, collect_events);
+    app
+}
+
+/// Update the app until `event` is received.
+fn wait_for(app: &mut App, event: JornetEvent) {
+    let start = Instant::now();
+    while !app.world().resource::<ReceivedEvents>().0.contains(&event) {
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "timed out waiting for {:?}, received {:?}",
+            event,
+            app.world().resource::<ReceivedEvents>().0
+        );
+        app.update();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn send_score_and_refresh() {
+    let server = TestServer::start();
+    let mut app = app(&server);
+
+    app.world_mut()
+        .resource_mut::<Leaderboard>()
+        .create_player(Some("tester"));
+    wait_for(&mut app, JornetEvent::CreatePlayerSuccess);
+    // the player is set on the resource during the update after the event
+    app.update();
+    assert_eq!(
+        app.world()
+            .resource::<Leaderboard>()
+            .get_player()
+            .unwrap()
+            .name,
+        "tester"
+    );
+
+    app.world().resource::<Leaderboard>().send_score(42.0);
+    wait_for(&mut app, JornetEvent::SendScoreSuccess);
+
+    let scores = server.scores();
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].score.as_f64(), 42.0);
+    assert_eq!(scores[0].player, "tester");
+
+    app.world().resource::<Leaderboard>().refresh_leaderboard();
+    wait_for(&mut app, JornetEvent::RefreshLeaderboardSuccess);
+    app.update();
+    let leaderboard = app.world().resource::<Leaderboard>().get_leaderboard();
+    assert_eq!(leaderboard.len(), 1);
+    assert_eq!(leaderboard[0].score, 42.0);
+}
+
+#[test]
+fn send_integer_score() {
+    let server = TestServer::with_score_type(ScoreType::I64);
+    let mut app = app(&server);
+
+    app.world_mut()
+        .resource_mut::<Leaderboard>()
+        .create_player(None);
+    wait_for(&mut app, JornetEvent::CreatePlayerSuccess);
+    app.update();
+
+    // too large to be exact as a `f32`
+    app.world()
+        .resource::<Leaderboard>()
+        .send_score_i64(20_000_001);
+    wait_for(&mut app, JornetEvent::SendScoreSuccess);
+    let scores = server.scores();
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].score, score::ScoreValue::I64(20_000_001));
+
+    // signed as a `f32`, which is not the type of the leaderboard
+    app.world_mut().resource_mut::<ReceivedEvents>().0.clear();
+    app.world().resource::<Leaderboard>().send_score(42.0);
+    wait_for(&mut app, JornetEvent::SendScoreFailure);
+    assert_eq!(server.scores().len(), 1);
+
+    app.world().resource::<Leaderboard>().refresh_leaderboard();
+    wait_for(&mut app, JornetEvent::RefreshLeaderboardSuccess);
+    app.update();
+    let leaderboard = app.world().resource::<Leaderboard>().get_leaderboard();
+    assert_eq!(leaderboard[0].value, ScoreValue::Integer(20_000_001));
+}
+
+#[test]
+fn send_duration_score() {
+    let server = TestServer::with_score_type(ScoreType::DurationMs);
+    let mut app = app(&server);
+
+    app.world_mut()
+        .resource_mut::<Leaderboard>()
+        .create_player(None);
+    wait_for(&mut app, JornetEvent::CreatePlayerSuccess);
+    app.update();
+
+    app.world()
+        .resource::<Leaderboard>()
+        .send_score_duration(Duration::from_millis(83_456));
+    wait_for(&mut app, JornetEvent::SendScoreSuccess);
+
+    app.world().resource::<Leaderboard>().refresh_leaderboard();
+    wait_for(&mut app, JornetEvent::RefreshLeaderboardSuccess);
+    app.update();
+    let leaderboard = app.world().resource::<Leaderboard>().get_leaderboard();
+    assert_eq!(
+        leaderboard[0].value.as_duration(),
+        Some(Duration::from_millis(83_456))
+    );
+}
+
+#[test]
+fn refresh_unchanged_leaderboard() {
+    let server = TestServer::start();
+    let mut app = app(&server);
+
+    app.world_mut()
+        .resource_mut::<Leaderboard>()
+        .create_player(None);
+    wait_for(&mut app, JornetEvent::CreatePlayerSuccess);
+    app.update();
+    app.world().resource::<Leaderboard>().send_score(42.0);
+    wait_for(&mut app, JornetEvent::SendScoreSuccess);
+
+    app.world().resource::<Leaderboard>().refresh_leaderboard();
+    wait_for(&mut app, JornetEvent::RefreshLeaderboardSuccess);
+    app.update();
+    assert_eq!(
+        app.world()
+            .resource::<Leaderboard>()
+            .get_leaderboard()
+            .len(),
+        1
+    );
+
+    // the server answers that nothing changed, and the scores are kept
+    app.world_mut().resource_mut::<ReceivedEvents>().0.clear();
+    app.world().resource::<Leaderboard>().refresh_leaderboard();
+    wait_for(&mut app, JornetEvent::RefreshLeaderboardSuccess);
+    app.update();
+    assert_eq!(
+        app.world()
+            .resource::<Leaderboard>()
+            .get_leaderboard()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn subscribe_to_live_updates() {
+    let server = TestServer::start();
+    let mut app = app(&server);
+
+    app.world_mut()
+        .resource_mut::<Leaderboard>()
+        .create_player(Some("tester"));
+    wait_for(&mut app, JornetEvent::CreatePlayerSuccess);
+    app.update();
+
+    app.world_mut().resource_mut::<Leaderboard>().subscribe(5);
+    wait_for(&mut app, JornetEvent::LiveRankUpdated);
+    app.update();
+    assert_eq!(app.world().resource::<Leaderboard>().get_rank(), None);
+
+    app.world_mut().resource_mut::<ReceivedEvents>().0.clear();
+    app.world().resource::<Leaderboard>().send_score(42.0);
+    wait_for(&mut app, JornetEvent::LiveLeaderboardUpdated);
+    wait_for(&mut app, JornetEvent::LiveRankUpdated);
+    app.update();
+    let leaderboard = app.world().resource::<Leaderboard>();
+    assert_eq!(leaderboard.get_leaderboard().len(), 1);
+    assert_eq!(leaderboard.get_leaderboard()[0].player, "tester");
+    assert_eq!(
+        leaderboard.get_rank(),
+        Some(Rank {
+            rank: 1,
+            score: 42.0,
+            value: ScoreValue::Float(42.0),
+        })
+    );
+
+    app.world_mut().resource_mut::<Leaderboard>().unsubscribe();
+}
+
+#[test]
+fn score_to_unknown_leaderboard_fails() {
+    let server = TestServer::start();
+    let other = TestServer::start();
+    let mut app = App::new();
+    // a leaderboard that only exists on the other server
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        other.plugin().with_host(server.host()),
+    ))
+    .init_resource::<ReceivedEvents>()
+    .add_systems(Update, collect_events);
+
+    app.world_mut()
+        .resource_mut::<Leaderboard>()
+        .create_player(None);
+    wait_for(&mut app, JornetEvent::CreatePlayerSuccess);
+    app.update();
+
+    app.world().resource::<Leaderboard>().send_score(42.0);
+    wait_for(&mut app, JornetEvent::SendScoreFailure);
+    assert!(server.scores().is_empty());
+}